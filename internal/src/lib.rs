@@ -11,24 +11,88 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod output;
+#[cfg(test)]
+mod tests;
+
 use im::HashMap;
 use model::base_types::VehicleTypeIdx;
 use rapid_solve::heuristics::Solver;
 use solution::transition::Transition;
+use solver::dummy_reassignment::DummyReassignmentSolver;
 use solver::local_search::neighborhood::swaps::SwapInfo;
-use solver::local_search::ScheduleWithInfo;
-use solver::min_cost_flow_solver::MinCostFlowSolver;
+use solver::local_search::{MoveTally, ScheduleWithInfo};
 use solver::objective;
+use solver::start_solver::compute_start_schedule;
 
-use model::json_serialisation::load_rolling_stock_problem_instance_from_json;
+use model::config::ConfigError;
+use model::json_serialisation::try_load_rolling_stock_problem_instance_from_json;
 use solver::transition_local_search::{build_transition_local_search_solver, TransitionWithInfo};
 
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time as stdtime;
 
-pub fn run(input_data: serde_json::Value) -> serde_json::Value {
+/// Wall-clock time spent in each phase of a run. The phases are measured back to back, so they
+/// add up to the total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveTiming {
+    /// Parsing the input and building the network.
+    pub load: stdtime::Duration,
+    /// Computing the start schedule with the configured start solver (including depot improvement).
+    pub warm_start: stdtime::Duration,
+    /// Local search, dummy reassignment, transition optimization and final post-processing.
+    pub local_search: stdtime::Duration,
+    /// Creating the output JSON.
+    pub serialization: stdtime::Duration,
+    /// Total time of the run.
+    pub total: stdtime::Duration,
+}
+
+/// Solves the instance and returns the output JSON. Fails if the parameters are invalid.
+pub fn run(input_data: serde_json::Value) -> Result<serde_json::Value, ConfigError> {
+    run_interruptible(input_data, Arc::new(AtomicBool::new(false)))
+}
+
+/// Same as run, but the local search stops as soon as the stop flag is set (e.g., on Ctrl-C) and
+/// the best schedule found so far is post-processed and returned as usual.
+pub fn run_interruptible(
+    input_data: serde_json::Value,
+    stop_flag: Arc<AtomicBool>,
+) -> Result<serde_json::Value, ConfigError> {
+    run_with_timing(input_data, stop_flag).map(|(output, _)| output)
+}
+
+/// Entry point for benchmarks (e.g., criterion or CI): solves the instance like run and returns
+/// the time spent in each phase instead of the output.
+pub fn solve_for_timing(input_data: serde_json::Value) -> Result<SolveTiming, ConfigError> {
+    run_with_timing(input_data, Arc::new(AtomicBool::new(false))).map(|(_, timing)| timing)
+}
+
+/// Dry run: loads the instance without solving it and lists data-quality warnings, i.e., dead
+/// head distances that violate the triangle-inequality. Fails if the parameters are invalid.
+pub fn check_input(input_data: serde_json::Value) -> Result<Vec<String>, ConfigError> {
+    let network = try_load_rolling_stock_problem_instance_from_json(input_data)?;
+    Ok(network
+        .locations()
+        .triangle_inequality_violations()
+        .into_iter()
+        .map(|(a, b, c)| {
+            format!(
+                "Dead head distance {} -> {} is longer than the detour {} -> {} -> {}.",
+                a, c, a, b, c
+            )
+        })
+        .collect())
+}
+
+fn run_with_timing(
+    input_data: serde_json::Value,
+    stop_flag: Arc<AtomicBool>,
+) -> Result<(serde_json::Value, SolveTiming), ConfigError> {
     let start_time = stdtime::Instant::now();
-    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let network = try_load_rolling_stock_problem_instance_from_json(input_data)?;
+    let loaded_time = stdtime::Instant::now();
     println!(
         "Instance with {} vehicle types and {} trips loaded (elapsed time: {:0.2}sec)",
         network.vehicle_types().iter().count(),
@@ -36,23 +100,25 @@ pub fn run(input_data: serde_json::Value) -> serde_json::Value {
         start_time.elapsed().as_secs_f32()
     );
 
-    let objective = Arc::new(objective::build());
+    let objective = Arc::new(objective::build_for(&network));
 
-    println!("Solve with MinCostFlowSolver:");
-    let min_cost_flow_solver = MinCostFlowSolver::initialize(network.clone());
-    let start_schedule = min_cost_flow_solver.solve();
+    let start_solver = network.config().start_solver;
+    println!("Solve with {:?} start solver:", start_solver);
+    let start_schedule = compute_start_schedule(network.clone());
     println!(
-        "MinCostFlowSolver computed schedule (elapsed time: {:0.2}sec)",
+        "{:?} start solver computed schedule (elapsed time: {:0.2}sec)",
+        start_solver,
         start_time.elapsed().as_secs_f32()
     );
 
     let start_schedule_with_info = ScheduleWithInfo::new(
         start_schedule.improve_depots(None),
         SwapInfo::NoSwap,
-        "Result from min cost flow solver".to_string(),
+        format!("Result from {:?} start solver", start_solver),
     );
+    let warm_started_time = stdtime::Instant::now();
 
-    let solution = if network.maintenance_considered() {
+    let (solution, telemetry) = if network.maintenance_considered() {
         println!("\nStarting local search:\n");
         println!("Initial objective value:");
         objective.print_objective_value(
@@ -62,14 +128,28 @@ pub fn run(input_data: serde_json::Value) -> serde_json::Value {
         );
         println!();
 
-        let local_search_solver = solver::local_search::build_local_search_solver(network.clone());
+        // the local search stops as soon as the lower bound is reached
+        let target_objective = objective::lower_bound(&network);
 
-        local_search_solver.solve(start_schedule_with_info)
+        let local_search_solver = solver::local_search::build_local_search_solver(
+            network.clone(),
+            Some(target_objective),
+            Some(stop_flag),
+        );
+
+        let solution = local_search_solver.solve(start_schedule_with_info);
+        (solution, local_search_solver.move_tally().to_json())
     } else {
-        println!("\nMaintenance is not considered, returning MinCostFlowSolver solution as final solution");
-        objective.evaluate(start_schedule_with_info.clone())
+        println!("\nMaintenance is not considered, returning start solution as final solution");
+        (
+            objective.evaluate(start_schedule_with_info.clone()),
+            MoveTally::default().to_json(),
+        )
     };
 
+    // cover remaining dummy tours if this improves the objective
+    let solution = DummyReassignmentSolver::initialize(objective.clone()).solve(solution);
+
     // optimize transitions
     println!("\nOptimizing transitions:");
     let start_time_transition_optimization = stdtime::Instant::now();
@@ -101,9 +181,11 @@ pub fn run(input_data: serde_json::Value) -> serde_json::Value {
     );
     schedule_with_optimized_transitions.print_next_day_transitions();
 
-    // reassign end depots to be consistent with transitions
-    let final_schedule =
-        schedule_with_optimized_transitions.reassign_end_depots_consistent_with_transitions();
+    // reassign end depots to be consistent with transitions and renumber the vehicles
+    // canonically, such that equivalent schedules give identical outputs
+    let final_schedule = schedule_with_optimized_transitions
+        .reassign_end_depots_consistent_with_transitions()
+        .compact_ids();
     let final_schedule_with_info = ScheduleWithInfo::new(
         final_schedule,
         SwapInfo::NoSwap,
@@ -139,15 +221,22 @@ pub fn run(input_data: serde_json::Value) -> serde_json::Value {
     objective.print_objective_value(final_solution.objective_value());
 
     // final_solution.solution().print_depot_balances();
-    println!(
-        "Total depot balance violations: {}",
-        final_solution
-            .solution()
-            .get_schedule()
-            .total_depot_balance_violation()
-    );
+    println!("\nSummary:");
+    final_schedule.statistics().print();
 
     println!("Running time: {:0.2}sec", runtime_duration.as_secs_f32());
-
-    server::create_output_json(&final_solution, &objective, runtime_duration)
+    let solved_time = stdtime::Instant::now();
+
+    let output =
+        server::create_output_json(&final_solution, &objective, runtime_duration, telemetry);
+    let serialized_time = stdtime::Instant::now();
+
+    let timing = SolveTiming {
+        load: loaded_time - start_time,
+        warm_start: warm_started_time - loaded_time,
+        local_search: solved_time - warm_started_time,
+        serialization: serialized_time - solved_time,
+        total: serialized_time - start_time,
+    };
+    Ok((output, timing))
 }
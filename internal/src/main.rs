@@ -11,17 +11,57 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::fs::{self, File};
+use internal::output::{parse_output_formats, write_output, OutputFormat};
+use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// On the first Ctrl-C the local search is stopped and the best schedule so far is written as
+/// usual. A second Ctrl-C terminates the process immediately.
+fn install_interrupt_handler() -> Arc<AtomicBool> {
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = stop_flag.clone();
+    ctrlc::set_handler(move || {
+        if handler_flag.swap(true, Ordering::Relaxed) {
+            std::process::exit(130);
+        }
+    })
+    .expect("Error setting Ctrl-C handler");
+    stop_flag
+}
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
-    if args.len() < 2 {
-        println!("Usage: {} <input_file>", args[0]);
-        std::process::exit(1)
+    let usage = format!(
+        "Usage: {} <input_file> [--output-format <json|compact-json|csv>[,...]]\n       {} <input_file> --dry-run\n       {} --print-schema",
+        args[0], args[0], args[0]
+    );
+    if args.len() == 2 && args[1] == "--print-schema" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&server::schema()).unwrap()
+        );
+        std::process::exit(0)
     }
+    let dry_run = args.len() == 3 && args[2] == "--dry-run";
+    let output_formats = match args.len() {
+        2 => vec![OutputFormat::Json],
+        3 if dry_run => vec![],
+        4 if args[2] == "--output-format" => match parse_output_formats(&args[3]) {
+            Ok(formats) => formats,
+            Err(error) => {
+                println!("Error: {}", error);
+                println!("{}", usage);
+                std::process::exit(1)
+            }
+        },
+        _ => {
+            println!("{}", usage);
+            std::process::exit(1)
+        }
+    };
 
     let path = &args[1];
 
@@ -36,28 +76,45 @@ fn main() {
     let mut input_data = String::new();
     file.read_to_string(&mut input_data).unwrap();
     let input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
-    println!("\n---------- RUN: {} ----------", path);
 
-    let output = internal::run(input_data);
+    if dry_run {
+        match internal::check_input(input_data) {
+            Ok(warnings) => {
+                for warning in warnings.iter() {
+                    println!("Warning: {}", warning);
+                }
+                println!("Input {} checked ({} warnings).", path, warnings.len());
+                std::process::exit(0)
+            }
+            Err(error) => {
+                println!("Error: invalid parameters: {}", error);
+                std::process::exit(1)
+            }
+        }
+    }
 
-    // output path with sub-directory creation
-    let output_dir_name = "output";
-    let output_path = ensure_output_path(path, output_dir_name);
-    let file = File::create(output_path).expect("Error creating file");
-    serde_json::to_writer_pretty(file, &output).expect("Error writing JSON");
+    println!("\n---------- RUN: {} ----------", path);
 
-    std::process::exit(0)
-}
+    let stop_flag = install_interrupt_handler();
+    let output = match internal::run_interruptible(input_data, stop_flag) {
+        Ok(output) => output,
+        Err(error) => {
+            println!("Error: invalid parameters: {}", error);
+            std::process::exit(1)
+        }
+    };
 
-fn ensure_output_path(input_path: &str, output_dir_name: &str) -> String {
-    let file_name = Path::new(input_path)
-        .file_name()
-        .expect("Error getting file name")
-        .to_str()
-        .expect("Error converting file name to string");
-    let output_path = format!("{}/output_{}", output_dir_name, file_name);
-    if let Some(parent_dir) = Path::new(&output_path).parent() {
-        fs::create_dir_all(parent_dir).expect("Error creating directories");
+    match write_output(&output, path, "output", &output_formats) {
+        Ok(written_files) => {
+            for written_file in written_files {
+                println!("Output written to {}", written_file);
+            }
+        }
+        Err(error) => {
+            println!("Error writing output: {}", error);
+            std::process::exit(1)
+        }
     }
-    output_path
+
+    std::process::exit(0)
 }
@@ -0,0 +1,197 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Writing the output of a single run to disk in one or several formats.
+
+use std::fs::{self, File};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Pretty-printed JSON (default).
+    Json,
+    /// JSON without any whitespace.
+    CompactJson,
+    /// One row per departure segment, maintenance slot and dead head trip of each vehicle.
+    Csv,
+}
+
+impl OutputFormat {
+    fn file_name(&self, input_path: &str) -> Result<String, String> {
+        let input_path = Path::new(input_path);
+        let file_name = input_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format!("Invalid input path: {}", input_path.display()))?;
+        let stem = input_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(file_name);
+        Ok(match self {
+            OutputFormat::Json => format!("output_{}", file_name),
+            OutputFormat::CompactJson => format!("output_{}_compact.json", stem),
+            OutputFormat::Csv => format!("output_{}.csv", stem),
+        })
+    }
+}
+
+/// Parses a comma-separated list of output formats, e.g. "json,csv".
+/// Valid formats are "json", "compact-json" and "csv"; duplicates are ignored.
+pub fn parse_output_formats(formats: &str) -> Result<Vec<OutputFormat>, String> {
+    let mut result = Vec::new();
+    for format in formats.split(',').map(|f| f.trim().to_lowercase()) {
+        let format = match format.as_str() {
+            "json" => OutputFormat::Json,
+            "compact-json" => OutputFormat::CompactJson,
+            "csv" => OutputFormat::Csv,
+            _ => {
+                return Err(format!(
+                    "Unknown output format '{}'. Valid formats are: json, compact-json, csv.",
+                    format
+                ))
+            }
+        };
+        if !result.contains(&format) {
+            result.push(format);
+        }
+    }
+    Ok(result)
+}
+
+/// Writes the output into the given directory (created if needed), one file per format.
+/// The file names are derived from the name of the input file.
+/// Returns the paths of the written files.
+pub fn write_output(
+    output: &serde_json::Value,
+    input_path: &str,
+    output_dir_name: &str,
+    formats: &[OutputFormat],
+) -> Result<Vec<String>, String> {
+    fs::create_dir_all(output_dir_name).map_err(|err| err.to_string())?;
+    let mut written_files = Vec::new();
+    for format in formats {
+        let output_path = format!("{}/{}", output_dir_name, format.file_name(input_path)?);
+        match format {
+            OutputFormat::Json => {
+                let file = File::create(&output_path).map_err(|err| err.to_string())?;
+                serde_json::to_writer_pretty(file, output).map_err(|err| err.to_string())?;
+            }
+            OutputFormat::CompactJson => {
+                let file = File::create(&output_path).map_err(|err| err.to_string())?;
+                serde_json::to_writer(file, output).map_err(|err| err.to_string())?;
+            }
+            OutputFormat::Csv => {
+                fs::write(&output_path, output_to_csv(output)?).map_err(|err| err.to_string())?;
+            }
+        }
+        written_files.push(output_path);
+    }
+    Ok(written_files)
+}
+
+const CSV_HEADER: [&str; 8] = [
+    "vehicle",
+    "vehicleType",
+    "activity",
+    "id",
+    "origin",
+    "destination",
+    "departure",
+    "arrival",
+];
+
+/// Converts the fleet of the output JSON into CSV. Each row is an activity of a vehicle
+/// (departure segment, maintenance slot or dead head trip); the activities of a vehicle are
+/// sorted by departure.
+pub fn output_to_csv(output: &serde_json::Value) -> Result<String, String> {
+    let fleet = output["schedule"]["fleet"]
+        .as_array()
+        .ok_or("Output does not contain a fleet.")?;
+
+    let mut csv = CSV_HEADER.join(",") + "\n";
+    for fleet_entry in fleet {
+        let vehicle_type = json_str(&fleet_entry["vehicleType"])?;
+        for vehicle in fleet_entry["vehicles"].as_array().into_iter().flatten() {
+            let vehicle_id = json_str(&vehicle["id"])?;
+            let mut rows: Vec<[&str; 8]> = Vec::new();
+            for segment in vehicle["departureSegments"]
+                .as_array()
+                .into_iter()
+                .flatten()
+            {
+                rows.push([
+                    vehicle_id,
+                    vehicle_type,
+                    "departureSegment",
+                    json_str(&segment["departureSegment"])?,
+                    json_str(&segment["origin"])?,
+                    json_str(&segment["destination"])?,
+                    json_str(&segment["departure"])?,
+                    json_str(&segment["arrival"])?,
+                ]);
+            }
+            for slot in vehicle["maintenanceSlots"].as_array().into_iter().flatten() {
+                rows.push([
+                    vehicle_id,
+                    vehicle_type,
+                    "maintenanceSlot",
+                    json_str(&slot["maintenanceSlot"])?,
+                    json_str(&slot["location"])?,
+                    json_str(&slot["location"])?,
+                    json_str(&slot["start"])?,
+                    json_str(&slot["end"])?,
+                ]);
+            }
+            for trip in vehicle["deadHeadTrips"].as_array().into_iter().flatten() {
+                rows.push([
+                    vehicle_id,
+                    vehicle_type,
+                    "deadHeadTrip",
+                    json_str(&trip["id"])?,
+                    json_str(&trip["origin"])?,
+                    json_str(&trip["destination"])?,
+                    json_str(&trip["departure"])?,
+                    json_str(&trip["arrival"])?,
+                ]);
+            }
+            // ISO timestamps sort chronologically
+            rows.sort_by_key(|row| (row[6], row[7]));
+            for row in rows {
+                csv += &row
+                    .iter()
+                    .map(|field| csv_escape(field))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                csv += "\n";
+            }
+        }
+    }
+    Ok(csv)
+}
+
+fn json_str(value: &serde_json::Value) -> Result<&str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("Expected a string in output, found: {}", value))
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
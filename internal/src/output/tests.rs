@@ -0,0 +1,133 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use super::{output_to_csv, parse_output_formats, write_output, OutputFormat};
+
+fn solve_test_instance() -> serde_json::Value {
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    crate::run(serde_json::from_str(&input_data).unwrap()).unwrap()
+}
+
+#[test]
+fn parse_output_formats_test() {
+    // ACT
+    let formats = parse_output_formats("json, CSV,compact-json,csv");
+    let invalid = parse_output_formats("json,xml");
+
+    // ASSERT
+    assert_eq!(
+        formats.unwrap(),
+        vec![
+            OutputFormat::Json,
+            OutputFormat::Csv,
+            OutputFormat::CompactJson
+        ]
+    );
+    assert!(invalid.unwrap_err().contains("'xml'"));
+}
+
+#[test]
+fn write_csv_output_test() {
+    // ARRANGE
+    let output = solve_test_instance();
+    let output_dir = std::env::temp_dir().join(format!("rssched_output_{}", std::process::id()));
+    let output_dir = output_dir.to_str().unwrap();
+
+    // ACT
+    let written_files = write_output(
+        &output,
+        "some/dir/test_instance.json",
+        output_dir,
+        &[OutputFormat::Csv, OutputFormat::CompactJson],
+    )
+    .unwrap();
+
+    // ASSERT
+    assert_eq!(
+        written_files,
+        vec![
+            format!("{}/output_test_instance.csv", output_dir),
+            format!("{}/output_test_instance_compact.json", output_dir)
+        ]
+    );
+    let csv = fs::read_to_string(&written_files[0]).unwrap();
+    let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+    assert_eq!(
+        rows[0],
+        vec![
+            "vehicle",
+            "vehicleType",
+            "activity",
+            "id",
+            "origin",
+            "destination",
+            "departure",
+            "arrival"
+        ]
+    );
+    assert!(rows.iter().all(|row| row.len() == 8));
+    assert_eq!(csv, output_to_csv(&output).unwrap());
+    let departure_segments = rows
+        .iter()
+        .filter(|row| row[2] == "departureSegment")
+        .count();
+    let expected_departure_segments: usize = output["schedule"]["fleet"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|fleet| fleet["vehicles"].as_array().unwrap())
+        .map(|vehicle| vehicle["departureSegments"].as_array().unwrap().len())
+        .sum();
+    assert!(departure_segments > 0);
+    assert_eq!(departure_segments, expected_departure_segments);
+
+    let compact_json = fs::read_to_string(&written_files[1]).unwrap();
+    assert!(!compact_json.contains('\n'));
+    let parsed: serde_json::Value = serde_json::from_str(&compact_json).unwrap();
+    assert_eq!(parsed["schedule"], output["schedule"]);
+
+    fs::remove_dir_all(output_dir).unwrap();
+}
+
+#[test]
+fn interrupted_run_produces_valid_output_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let mut input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    // maintenance slots enable the local search
+    input_data["maintenanceSlots"] = serde_json::json!([{"id": "maintenance_loc2",
+        "location": "loc2", "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00",
+        "trackCount": 2}]);
+    let stop_flag = Arc::new(AtomicBool::new(true));
+
+    // ACT
+    let output = crate::run_interruptible(input_data, stop_flag).unwrap();
+
+    // ASSERT
+    assert!(output["objectiveValue"].is_object());
+    assert_eq!(output["summary"]["numberOfDummyTours"], 0);
+    let csv = output_to_csv(&output).unwrap();
+    assert!(csv
+        .lines()
+        .skip(1)
+        .any(|row| row.contains("departureSegment")));
+}
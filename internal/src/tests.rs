@@ -0,0 +1,128 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fs::File;
+use std::io::Read;
+use std::time::Duration;
+
+use jsonschema::JSONSchema;
+use serde_json::Value;
+
+use super::{check_input, run, solve_for_timing};
+
+#[test]
+fn solve_for_timing_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+
+    // ACT
+    let timing = solve_for_timing(serde_json::from_str(&input_data).unwrap()).unwrap();
+
+    // ASSERT
+    assert!(timing.load > Duration::ZERO);
+    assert!(timing.warm_start > Duration::ZERO);
+    assert!(timing.local_search > Duration::ZERO);
+    assert!(timing.serialization > Duration::ZERO);
+    let sum = timing.load + timing.warm_start + timing.local_search + timing.serialization;
+    assert!(sum <= timing.total + Duration::from_millis(1));
+    assert!(timing.total <= sum + Duration::from_millis(1));
+}
+
+#[test]
+fn check_input_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let clean_input: Value = serde_json::from_str(&input_data).unwrap();
+    // loc1 -> loc3 directly is longer than each detour via loc2, loc4 or loc5
+    let mut planted_input = clean_input.clone();
+    planted_input["deadHeadTrips"]["distances"][0][2] = Value::from(100000);
+
+    // ACT
+    let clean_warnings = check_input(clean_input).unwrap();
+    let planted_warnings = check_input(planted_input).unwrap();
+
+    // ASSERT
+    assert!(clean_warnings.is_empty());
+    assert_eq!(planted_warnings.len(), 3);
+    assert_eq!(
+        planted_warnings[0],
+        "Dead head distance loc1 -> loc3 is longer than the detour loc1 -> loc2 -> loc3."
+    );
+}
+
+#[test]
+fn output_validates_against_schema_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let schema = JSONSchema::compile(&serde_json::to_value(server::schema()).unwrap()).unwrap();
+
+    // ACT
+    let output = run(serde_json::from_str(&input_data).unwrap()).unwrap();
+
+    // ASSERT
+    assert!(schema.is_valid(&output));
+    assert!(!output["schedule"]["fleet"][0]["vehicles"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    let mut missing_field = output.clone();
+    missing_field["schedule"]["fleet"][0]["vehicles"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("startDepot");
+    assert!(!schema.is_valid(&missing_field));
+
+    let mut additional_field = output.clone();
+    additional_field["summary"]["unknown"] = Value::from(1);
+    assert!(!schema.is_valid(&additional_field));
+
+    let mut wrong_type = output;
+    wrong_type["summary"]["numberOfVehicles"] = Value::from("many");
+    assert!(!schema.is_valid(&wrong_type));
+}
+
+#[test]
+fn start_solvers_produce_valid_schedules_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let input: Value = serde_json::from_str(&input_data).unwrap();
+    let schema = JSONSchema::compile(&serde_json::to_value(server::schema()).unwrap()).unwrap();
+
+    for start_solver in ["minCostFlow", "oneNodePerTour"] {
+        let mut input = input.clone();
+        input["parameters"]["startSolver"] = Value::from(start_solver);
+
+        // ACT
+        let output = run(input).unwrap();
+
+        // ASSERT
+        assert!(schema.is_valid(&output), "{}", start_solver);
+        assert!(
+            !output["schedule"]["fleet"][0]["vehicles"]
+                .as_array()
+                .unwrap()
+                .is_empty(),
+            "{}",
+            start_solver
+        );
+    }
+}
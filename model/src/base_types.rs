@@ -16,15 +16,30 @@ use derive_more::From;
 
 pub mod distance;
 pub mod location;
+pub mod rounding;
 
 pub use distance::Distance;
 pub use location::Location;
+pub use rounding::RoundingPolicy;
 
 pub type Idx = u16;
 
 #[derive(Display, From, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LocationIdx(pub Idx);
 
+/// Side of the station at which a vehicle departs or arrives. By default, trips depart at the
+/// back and arrive at the front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StationSide {
+    Front,
+    Back,
+}
+
+impl StationSide {
+    pub const DEFAULT_ORIGIN: StationSide = StationSide::Back;
+    pub const DEFAULT_DESTINATION: StationSide = StationSide::Front;
+}
+
 #[derive(Display, From, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VehicleTypeIdx(pub Idx);
 
@@ -104,6 +119,7 @@ impl NodeIdx {
 pub type VehicleCount = u32;
 pub type PassengerCount = u32;
 pub type Meter = u64;
+pub type TrainLength = Meter;
 pub type Cost = u64;
 pub const INF_DISTANCE: Meter = 10_000_000; // distance for Infinity (used for maintenance counter
                                             // from the overhead depot)
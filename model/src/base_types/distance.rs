@@ -11,7 +11,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::base_types::Meter;
+#[cfg(test)]
+mod tests;
+
+use crate::base_types::{Meter, RoundingPolicy};
 use std::fmt;
 use std::ops::{Add, Sub};
 
@@ -60,6 +63,11 @@ impl Distance {
         Distance::from_meter((km * 1000.0) as Meter)
     }
 
+    /// Converts kilometers to meters with the given rounding policy.
+    pub fn from_km_rounded(km: f64, rounding: RoundingPolicy) -> Distance {
+        Distance::from_meter(rounding.to_unsigned(km * 1000.0))
+    }
+
     pub fn from_km_str(km_string: &str) -> Distance {
         // could be an int string ("1000") or a float string ("1000.0")
         let km: f32 = if km_string.contains('.') {
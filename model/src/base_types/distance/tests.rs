@@ -0,0 +1,45 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::base_types::RoundingPolicy;
+
+use super::Distance;
+
+#[test]
+fn from_km_truncates_test() {
+    // ACT & ASSERT
+    assert_eq!(Distance::from_km(1.0), Distance::from_meter(1000));
+    assert_eq!(Distance::from_km(0.0009), Distance::from_meter(0));
+    assert_eq!(Distance::from_km_str("12.5"), Distance::from_meter(12500));
+}
+
+#[test]
+fn from_km_rounded_test() {
+    // ACT & ASSERT
+    assert_eq!(
+        Distance::from_km_rounded(1.0005, RoundingPolicy::Truncate),
+        Distance::from_meter(1000)
+    );
+    assert_eq!(
+        Distance::from_km_rounded(1.0005, RoundingPolicy::RoundHalfUp),
+        Distance::from_meter(1001)
+    );
+    assert_eq!(
+        Distance::from_km_rounded(1.0004, RoundingPolicy::RoundHalfUp),
+        Distance::from_meter(1000)
+    );
+    assert_eq!(
+        Distance::from_km_rounded(1.0001, RoundingPolicy::Ceil),
+        Distance::from_meter(1001)
+    );
+}
@@ -0,0 +1,45 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+/// How fractional values are converted to integers (e.g., when scaling objective values or
+/// converting kilometers to meters).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingPolicy {
+    /// Cuts off the fractional part (rounds towards zero).
+    Truncate,
+    /// Rounds to the nearest integer, halves are rounded up (towards positive infinity).
+    #[default]
+    RoundHalfUp,
+    /// Rounds up to the next integer.
+    Ceil,
+}
+
+impl RoundingPolicy {
+    pub fn round(&self, value: f64) -> f64 {
+        match self {
+            RoundingPolicy::Truncate => value.trunc(),
+            RoundingPolicy::RoundHalfUp => (value + 0.5).floor(),
+            RoundingPolicy::Ceil => value.ceil(),
+        }
+    }
+
+    /// Rounds the value and converts it to an integer (saturating at the bounds of i64).
+    pub fn to_integer(&self, value: f64) -> i64 {
+        self.round(value) as i64
+    }
+
+    /// Rounds the value and converts it to an unsigned integer (negative values become 0).
+    pub fn to_unsigned(&self, value: f64) -> u64 {
+        self.round(value) as u64
+    }
+}
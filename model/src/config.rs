@@ -11,36 +11,206 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod config_error;
+#[cfg(test)]
+mod tests;
+
+pub use config_error::ConfigError;
+
+use std::collections::HashMap;
+
 use rapid_time::Duration;
 
-use crate::base_types::{Cost, Distance};
+use crate::base_types::{Cost, Distance, RoundingPolicy};
 
 pub struct Config {
     pub forbid_dead_head_trip: bool,
+    pub fixed_fleet: bool, // vehicles can only be spawned at the depots the fleet is stationed at
     pub day_limit_threshold: Duration,
     pub shunting: ShuntingConfig,
     pub maintenance: MaintenanceConfig,
     pub costs: CostsConfig,
+    pub local_search: LocalSearchConfig,
+    pub unserved_demand_bucket: Duration, // time bucket to group the unserved demand in the output
+    pub rounding: RoundingPolicy,         // how fractional values are converted to integers
+    pub max_dummy_tours: usize, // more dummy tours abort the solver as the instance is infeasible
+    pub rotation_closure: RotationClosure, // whether vehicles must return to their start depot
+    pub start_solver: StartSolver, // computes the start schedule of the local search
+    pub reference_assignment: Option<ReferenceAssignment>, // previous plan to stay close to
+}
+
+/// Ids of the vehicles that operated each service trip (by id) in a previous plan, e.g., the
+/// assignments of the output of the last run of a rolling re-optimization. Vehicles are matched
+/// by id, service trips missing in the map were not operated.
+pub type ReferenceAssignment = HashMap<String, Vec<String>>;
+
+/// Solver that computes the start schedule of the local search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StartSolver {
+    #[default]
+    MinCostFlow, // min cost flow weighted by the dead head travel time
+    MinCostFlowByDistance, // min cost flow weighted by the dead head distance
+    OneNodePerTour,        // each service trip gets its own vehicles (fast but many vehicles)
+}
+
+/// Whether the rotation of each vehicle must be closed, i.e., whether it has to end at the depot
+/// it started from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RotationClosure {
+    #[default]
+    Open, // vehicles may end at any reachable depot
+    Soft(Cost), // each open rotation is penalized by these costs
+    Strict,     // the end depot is always the depot the vehicle started from
 }
 
 pub struct ShuntingConfig {
     pub minimal: Duration,
     pub dead_head_trip: Duration,
+    pub zero_same_location: bool, // no shunting between service trips at the same location
 }
 
 pub struct MaintenanceConfig {
     pub maximal_distance: Distance,
+    pub mandatory_daily_visit: bool, // each vehicle must visit at least one maintenance slot
 }
 
+#[derive(Default)]
 pub struct CostsConfig {
     pub staff: Cost,
     pub service_trip: Cost,
     pub maintenance: Cost,
     pub dead_head_trip: Cost,
     pub idle: Cost,
+    pub non_home_spawn: Cost, // per vehicle that does not start and end at the same home depot
+}
+
+pub struct LocalSearchConfig {
+    pub plateau_length: u32, // restarts without improvement before stopping (0: no restarts)
+    pub perturbation_strength: u32, // random moves applied per restart
+    pub deterministic: bool, // select the best improving neighbor reproducibly
+    pub time_window: Option<Duration>, // only moves touching nodes in a sliding window (None: all)
+    pub operator_weights: OperatorWeights,
+    pub adaptation_rate: f64, // how fast the weights follow recent successes (0: static weights)
+    pub max_iterations: Option<usize>, // neighborhood evaluations over all restarts (None: no cap)
+    pub threshold_accepting: Option<ThresholdAcceptingConfig>, // None: only improving moves
+    pub evaluation_cache_size: usize, // recent evaluations kept to skip re-evaluations (0: none)
+    pub recursion: Option<RecursionConfig>, // None: only direct neighbors
+}
+
+/// Accepting neighbors whose costs exceed the costs of the current schedule by less than the
+/// threshold (all other objective levels must not get worse). The threshold is multiplied by
+/// decay after each iteration, such that the search ends as a plain descent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThresholdAcceptingConfig {
+    pub initial_threshold: Cost,
+    pub decay: f64,
+}
+
+/// Taking any improving neighbor found in parallel. If there is none, the best width neighbors
+/// (with distinct objective values) are explored recursively, up to depth levels deep.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecursionConfig {
+    pub depth: u8,
+    pub width: u8,
+    pub candidate_budget: Option<usize>, // schedules retained for the next level (None: no cap)
+}
+
+/// Relative weights of the neighborhood operators. The operator with the largest weight generates
+/// all its candidates, the others a proportional share. Zero disables an operator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OperatorWeights {
+    pub spawn_vehicle_for_maintenance: f64,
+    pub path_exchange: f64,
+    pub add_trip_for_hitch_hiking: f64,
+    pub remove_single_node: f64,
+    pub home_vehicle: f64,
+}
+
+impl OperatorWeights {
+    pub fn as_array(&self) -> [f64; 5] {
+        [
+            self.spawn_vehicle_for_maintenance,
+            self.path_exchange,
+            self.add_trip_for_hitch_hiking,
+            self.remove_single_node,
+            self.home_vehicle,
+        ]
+    }
+}
+
+impl Default for OperatorWeights {
+    fn default() -> Self {
+        OperatorWeights {
+            spawn_vehicle_for_maintenance: 1.0,
+            path_exchange: 1.0,
+            add_trip_for_hitch_hiking: 1.0,
+            remove_single_node: 1.0,
+            home_vehicle: 1.0,
+        }
+    }
+}
+
+impl Default for Config {
+    /// Defaults of the optional JSON parameters. The shunting durations and the costs of the
+    /// JSON input are mandatory and default to zero here.
+    fn default() -> Self {
+        Config {
+            forbid_dead_head_trip: false,
+            fixed_fleet: false,
+            day_limit_threshold: Duration::ZERO,
+            shunting: ShuntingConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            costs: CostsConfig::default(),
+            local_search: LocalSearchConfig::default(),
+            unserved_demand_bucket: Duration::from_seconds(3600),
+            rounding: RoundingPolicy::default(),
+            max_dummy_tours: 10000,
+            rotation_closure: RotationClosure::default(),
+            start_solver: StartSolver::default(),
+            reference_assignment: None,
+        }
+    }
+}
+
+impl Default for ShuntingConfig {
+    fn default() -> Self {
+        ShuntingConfig {
+            minimal: Duration::ZERO,
+            dead_head_trip: Duration::ZERO,
+            zero_same_location: false,
+        }
+    }
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        MaintenanceConfig {
+            maximal_distance: Distance::ZERO,
+            mandatory_daily_visit: false,
+        }
+    }
+}
+
+impl Default for LocalSearchConfig {
+    fn default() -> Self {
+        LocalSearchConfig {
+            plateau_length: 0,
+            perturbation_strength: 2,
+            deterministic: false,
+            time_window: None,
+            operator_weights: OperatorWeights::default(),
+            adaptation_rate: 0.0,
+            max_iterations: None,
+            threshold_accepting: None,
+            evaluation_cache_size: 0,
+            recursion: None,
+        }
+    }
 }
 
 impl Config {
+    /// Creates a validated config from the mandatory parameters of the JSON input. All other
+    /// parameters take their defaults; use the struct update syntax and validated to set them.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         forbid_dead_head_trip: bool,
@@ -53,16 +223,18 @@ impl Config {
         costs_maintenance: Cost,
         costs_dead_head_trip: Cost,
         costs_idle: Cost,
-    ) -> Config {
+    ) -> Result<Config, ConfigError> {
         Config {
             forbid_dead_head_trip,
             day_limit_threshold,
             shunting: ShuntingConfig {
                 minimal: shunting_minimal,
                 dead_head_trip: shunting_dead_head_trip,
+                ..ShuntingConfig::default()
             },
             maintenance: MaintenanceConfig {
                 maximal_distance: maintenance_maximal_distance,
+                ..MaintenanceConfig::default()
             },
             costs: CostsConfig {
                 staff: costs_staff,
@@ -70,7 +242,150 @@ impl Config {
                 maintenance: costs_maintenance,
                 dead_head_trip: costs_dead_head_trip,
                 idle: costs_idle,
+                ..CostsConfig::default()
             },
+            ..Config::default()
+        }
+        .validated()
+    }
+
+    /// Validates the parameters, e.g., after setting them with the struct update syntax:
+    /// `Config { fixed_fleet: true, ..Config::default() }.validated()`.
+    /// Negative durations, distances and costs are already ruled out by their unsigned types.
+    pub fn validated(self) -> Result<Config, ConfigError> {
+        self.validate_durations()?;
+        self.validate_costs()?;
+        self.validate_local_search()?;
+        if self.max_dummy_tours == 0 {
+            return Err(ConfigError::InvalidLimit(String::from(
+                "Maximal number of dummy tours must be positive.",
+            )));
+        }
+        Ok(self)
+    }
+
+    fn validate_durations(&self) -> Result<(), ConfigError> {
+        let finite = [
+            (self.day_limit_threshold, "Day limit threshold"),
+            (self.shunting.minimal, "Minimal shunting duration"),
+            (
+                self.shunting.dead_head_trip,
+                "Shunting duration for dead head trips",
+            ),
+        ];
+        if let Some((_, name)) = finite
+            .iter()
+            .find(|&&(duration, _)| duration == Duration::Infinity)
+        {
+            return Err(ConfigError::InvalidDuration(format!(
+                "{} must be finite.",
+                name
+            )));
+        }
+        let positive_and_finite = [
+            (self.local_search.time_window, "Local search time window"),
+            (Some(self.unserved_demand_bucket), "Unserved demand bucket"),
+        ];
+        if let Some((_, name)) = positive_and_finite.iter().find(|&&(duration, _)| {
+            duration.is_some_and(|duration| {
+                duration == Duration::ZERO || duration == Duration::Infinity
+            })
+        }) {
+            return Err(ConfigError::InvalidDuration(format!(
+                "{} must be positive and finite.",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Costs end up in the (signed) objective value, so each coefficient must fit into an i64.
+    fn validate_costs(&self) -> Result<(), ConfigError> {
+        let soft_rotation_penalty = match self.rotation_closure {
+            RotationClosure::Soft(penalty) => penalty,
+            RotationClosure::Open | RotationClosure::Strict => 0,
+        };
+        let costs = [
+            (self.costs.staff, "Staff costs"),
+            (self.costs.service_trip, "Service trip costs"),
+            (self.costs.maintenance, "Maintenance costs"),
+            (self.costs.dead_head_trip, "Dead head trip costs"),
+            (self.costs.idle, "Idle costs"),
+            (self.costs.non_home_spawn, "Non-home spawn costs"),
+            (soft_rotation_penalty, "Closed rotation penalty"),
+        ];
+        match costs.iter().find(|&&(cost, _)| cost > i64::MAX as Cost) {
+            Some((_, name)) => Err(ConfigError::InvalidCost(format!(
+                "{} must not exceed {}.",
+                name,
+                i64::MAX
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_local_search(&self) -> Result<(), ConfigError> {
+        let local_search = &self.local_search;
+        let error = |message: &str| Err(ConfigError::InvalidLocalSearch(String::from(message)));
+        if local_search.plateau_length > 0 && local_search.perturbation_strength == 0 {
+            return error(
+                "Perturbation strength must be positive if plateau restarts are enabled.",
+            );
+        }
+        let weights = local_search.operator_weights.as_array();
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return error("Operator weights must be non-negative and finite.");
+        }
+        if weights.iter().all(|w| *w == 0.0) {
+            return error("At least one operator weight must be positive.");
+        }
+        if !(0.0..=1.0).contains(&local_search.adaptation_rate) {
+            return error("Operator adaptation rate must be between 0 and 1.");
+        }
+        if let Some(threshold_accepting) = local_search.threshold_accepting {
+            if !(threshold_accepting.decay > 0.0 && threshold_accepting.decay < 1.0) {
+                return error("Threshold decay must be greater than 0 and less than 1.");
+            }
+            if threshold_accepting.initial_threshold > i64::MAX as Cost {
+                return Err(ConfigError::InvalidCost(format!(
+                    "Initial threshold must not exceed {}.",
+                    i64::MAX
+                )));
+            }
+        }
+        if let Some(recursion) = local_search.recursion {
+            if recursion.depth > 0 && recursion.width == 0 {
+                return error("Recursion width must be positive if the recursion depth is.");
+            }
+            if recursion.candidate_budget == Some(0) {
+                return error("Recursion candidate budget must be positive.");
+            }
+        }
+        // threshold accepting and recursion always evaluate the whole neighborhood
+        let improver = match (local_search.threshold_accepting, local_search.recursion) {
+            (Some(_), Some(_)) => {
+                return error("Threshold accepting cannot be combined with recursion.")
+            }
+            (Some(_), None) => "Threshold accepting",
+            (None, Some(_)) => "Recursion",
+            (None, None) => return Ok(()),
+        };
+        let incompatible = |option: &str| {
+            Err(ConfigError::InvalidLocalSearch(format!(
+                "{} cannot be combined with {}.",
+                improver, option
+            )))
+        };
+        if local_search.time_window.is_some() {
+            return incompatible("a time window");
+        }
+        if local_search.evaluation_cache_size > 0 {
+            return incompatible("the evaluation cache");
+        }
+        let enabled: Vec<f64> = weights.iter().copied().filter(|w| *w > 0.0).collect();
+        if local_search.adaptation_rate > 0.0 || enabled.iter().any(|w| *w != enabled[0]) {
+            return incompatible("operator weights");
         }
+        Ok(())
     }
 }
@@ -0,0 +1,47 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+/// Error returned by Config::validated. Each variant carries the human-readable message, which
+/// is also used by Display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// A duration is infinite or, if it must be positive, zero.
+    InvalidDuration(String),
+    /// A cost coefficient does not fit into the objective value.
+    InvalidCost(String),
+    /// The local search parameters are inconsistent (e.g., all operator weights are zero).
+    InvalidLocalSearch(String),
+    /// A limit is zero although it must be positive.
+    InvalidLimit(String),
+}
+
+impl ConfigError {
+    pub fn message(&self) -> &str {
+        match self {
+            ConfigError::InvalidDuration(message)
+            | ConfigError::InvalidCost(message)
+            | ConfigError::InvalidLocalSearch(message)
+            | ConfigError::InvalidLimit(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for ConfigError {}
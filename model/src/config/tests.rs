@@ -0,0 +1,460 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use rapid_time::Duration;
+
+use crate::base_types::Distance;
+
+use super::{
+    Config, ConfigError, CostsConfig, LocalSearchConfig, OperatorWeights, RecursionConfig,
+    RotationClosure, ShuntingConfig, ThresholdAcceptingConfig,
+};
+
+fn valid_config() -> Config {
+    Config {
+        day_limit_threshold: Duration::from_seconds(3600),
+        shunting: ShuntingConfig {
+            minimal: Duration::from_seconds(120),
+            dead_head_trip: Duration::from_seconds(300),
+            zero_same_location: false,
+        },
+        costs: CostsConfig {
+            staff: 20,
+            service_trip: 50,
+            dead_head_trip: 100,
+            idle: 10,
+            ..CostsConfig::default()
+        },
+        ..Config::default()
+    }
+}
+
+fn with_local_search(local_search: LocalSearchConfig) -> Config {
+    Config {
+        local_search,
+        ..valid_config()
+    }
+}
+
+#[test]
+fn valid_config_test() {
+    // ACT
+    let config = valid_config().validated();
+
+    // ASSERT
+    let config = config.unwrap();
+    assert_eq!(config.shunting.minimal, Duration::from_seconds(120));
+    assert_eq!(config.shunting.dead_head_trip, Duration::from_seconds(300));
+}
+
+#[test]
+fn new_test() {
+    // ACT
+    let config = Config::new(
+        false,
+        Duration::from_seconds(3600),
+        Duration::from_seconds(120),
+        Duration::from_seconds(300),
+        Distance::from_meter(30000000),
+        20,
+        50,
+        0,
+        100,
+        10,
+    );
+    let invalid_config = Config::new(
+        false,
+        Duration::from_seconds(3600),
+        Duration::Infinity,
+        Duration::from_seconds(300),
+        Distance::from_meter(30000000),
+        20,
+        50,
+        0,
+        100,
+        10,
+    );
+
+    // ASSERT
+    let config = config.unwrap();
+    assert_eq!(config.shunting.minimal, Duration::from_seconds(120));
+    assert_eq!(config.costs.dead_head_trip, 100);
+    assert_eq!(config.max_dummy_tours, Config::default().max_dummy_tours);
+    assert_eq!(
+        invalid_config.err().unwrap(),
+        ConfigError::InvalidDuration(String::from("Minimal shunting duration must be finite."))
+    );
+}
+
+#[test]
+fn infinite_durations_test() {
+    // ACT
+    let day_limit_threshold = Config {
+        day_limit_threshold: Duration::Infinity,
+        ..valid_config()
+    }
+    .validated();
+    let minimal_shunting = Config {
+        shunting: ShuntingConfig {
+            minimal: Duration::Infinity,
+            ..ShuntingConfig::default()
+        },
+        ..valid_config()
+    }
+    .validated();
+    let dead_head_trip_shunting = Config {
+        shunting: ShuntingConfig {
+            dead_head_trip: Duration::Infinity,
+            ..ShuntingConfig::default()
+        },
+        ..valid_config()
+    }
+    .validated();
+
+    // ASSERT
+    assert_eq!(
+        day_limit_threshold.err().unwrap(),
+        ConfigError::InvalidDuration(String::from("Day limit threshold must be finite."))
+    );
+    assert_eq!(
+        minimal_shunting.err().unwrap(),
+        ConfigError::InvalidDuration(String::from("Minimal shunting duration must be finite."))
+    );
+    assert_eq!(
+        dead_head_trip_shunting.err().unwrap().to_string(),
+        "Shunting duration for dead head trips must be finite."
+    );
+}
+
+#[test]
+fn empty_durations_test() {
+    // ACT
+    let time_window = with_local_search(LocalSearchConfig {
+        time_window: Some(Duration::ZERO),
+        ..LocalSearchConfig::default()
+    })
+    .validated();
+    let unserved_demand_bucket = Config {
+        unserved_demand_bucket: Duration::ZERO,
+        ..valid_config()
+    }
+    .validated();
+
+    // ASSERT
+    assert_eq!(
+        time_window.err().unwrap(),
+        ConfigError::InvalidDuration(String::from(
+            "Local search time window must be positive and finite."
+        ))
+    );
+    assert_eq!(
+        unserved_demand_bucket.err().unwrap(),
+        ConfigError::InvalidDuration(String::from(
+            "Unserved demand bucket must be positive and finite."
+        ))
+    );
+}
+
+#[test]
+fn invalid_costs_test() {
+    // ACT
+    let staff = Config {
+        costs: CostsConfig {
+            staff: u64::MAX,
+            ..CostsConfig::default()
+        },
+        ..valid_config()
+    }
+    .validated();
+    let rotation_penalty = Config {
+        rotation_closure: RotationClosure::Soft(i64::MAX as u64 + 1),
+        ..valid_config()
+    }
+    .validated();
+    let largest_cost = Config {
+        costs: CostsConfig {
+            idle: i64::MAX as u64,
+            ..CostsConfig::default()
+        },
+        ..valid_config()
+    }
+    .validated();
+
+    // ASSERT
+    assert_eq!(
+        staff.err().unwrap(),
+        ConfigError::InvalidCost(format!("Staff costs must not exceed {}.", i64::MAX))
+    );
+    assert_eq!(
+        rotation_penalty.err().unwrap(),
+        ConfigError::InvalidCost(format!(
+            "Closed rotation penalty must not exceed {}.",
+            i64::MAX
+        ))
+    );
+    assert!(largest_cost.is_ok());
+}
+
+#[test]
+fn plateau_restarts_without_perturbation_test() {
+    // ACT
+    let config = with_local_search(LocalSearchConfig {
+        plateau_length: 3,
+        perturbation_strength: 0,
+        ..LocalSearchConfig::default()
+    })
+    .validated();
+
+    // ASSERT
+    assert_eq!(
+        config.err().unwrap(),
+        ConfigError::InvalidLocalSearch(String::from(
+            "Perturbation strength must be positive if plateau restarts are enabled."
+        ))
+    );
+}
+
+#[test]
+fn invalid_operator_weights_test() {
+    // ARRANGE
+    let config = |operator_weights: OperatorWeights, adaptation_rate: f64| {
+        with_local_search(LocalSearchConfig {
+            operator_weights,
+            adaptation_rate,
+            ..LocalSearchConfig::default()
+        })
+        .validated()
+    };
+    let zero_weights = OperatorWeights {
+        spawn_vehicle_for_maintenance: 0.0,
+        path_exchange: 0.0,
+        add_trip_for_hitch_hiking: 0.0,
+        remove_single_node: 0.0,
+        home_vehicle: 0.0,
+    };
+
+    // ACT
+    let negative_weight = config(
+        OperatorWeights {
+            path_exchange: -1.0,
+            ..OperatorWeights::default()
+        },
+        0.0,
+    );
+    let all_zero = config(zero_weights, 0.0);
+    let invalid_rate = config(OperatorWeights::default(), 1.5);
+    let single_operator = config(
+        OperatorWeights {
+            path_exchange: 2.0,
+            ..zero_weights
+        },
+        0.5,
+    );
+
+    // ASSERT
+    assert_eq!(
+        negative_weight.err().unwrap().message(),
+        "Operator weights must be non-negative and finite."
+    );
+    assert_eq!(
+        all_zero.err().unwrap().message(),
+        "At least one operator weight must be positive."
+    );
+    assert_eq!(
+        invalid_rate.err().unwrap().message(),
+        "Operator adaptation rate must be between 0 and 1."
+    );
+    let single_operator = single_operator.unwrap();
+    assert_eq!(
+        single_operator.local_search.operator_weights.as_array(),
+        [0.0, 2.0, 0.0, 0.0, 0.0]
+    );
+    assert_eq!(single_operator.local_search.adaptation_rate, 0.5);
+}
+
+#[test]
+fn threshold_accepting_decay_test() {
+    // ARRANGE
+    let config = |decay: f64| {
+        with_local_search(LocalSearchConfig {
+            threshold_accepting: Some(ThresholdAcceptingConfig {
+                initial_threshold: 1000,
+                decay,
+            }),
+            ..LocalSearchConfig::default()
+        })
+        .validated()
+    };
+
+    // ACT
+    let no_decay = config(1.0);
+    let zero_decay = config(0.0);
+    let valid = config(0.5);
+
+    // ASSERT
+    let decay_error = ConfigError::InvalidLocalSearch(String::from(
+        "Threshold decay must be greater than 0 and less than 1.",
+    ));
+    assert_eq!(no_decay.err().unwrap(), decay_error);
+    assert_eq!(zero_decay.err().unwrap(), decay_error);
+    assert_eq!(
+        valid.unwrap().local_search.threshold_accepting,
+        Some(ThresholdAcceptingConfig {
+            initial_threshold: 1000,
+            decay: 0.5
+        })
+    );
+}
+
+#[test]
+fn threshold_accepting_excludes_restricted_neighborhoods_test() {
+    // ARRANGE
+    let threshold_accepting = Some(ThresholdAcceptingConfig {
+        initial_threshold: 1000,
+        decay: 0.5,
+    });
+    let config = |local_search: LocalSearchConfig| {
+        with_local_search(LocalSearchConfig {
+            threshold_accepting,
+            ..local_search
+        })
+        .validated()
+    };
+
+    // ACT
+    let time_window = config(LocalSearchConfig {
+        time_window: Some(Duration::from_seconds(3600)),
+        ..LocalSearchConfig::default()
+    });
+    let evaluation_cache = config(LocalSearchConfig {
+        evaluation_cache_size: 100,
+        ..LocalSearchConfig::default()
+    });
+    let operator_weights = config(LocalSearchConfig {
+        operator_weights: OperatorWeights {
+            path_exchange: 2.0,
+            ..OperatorWeights::default()
+        },
+        ..LocalSearchConfig::default()
+    });
+    let disabled_operator = config(LocalSearchConfig {
+        operator_weights: OperatorWeights {
+            home_vehicle: 0.0,
+            ..OperatorWeights::default()
+        },
+        ..LocalSearchConfig::default()
+    });
+
+    // ASSERT
+    assert_eq!(
+        time_window.err().unwrap().message(),
+        "Threshold accepting cannot be combined with a time window."
+    );
+    assert_eq!(
+        evaluation_cache.err().unwrap().message(),
+        "Threshold accepting cannot be combined with the evaluation cache."
+    );
+    assert_eq!(
+        operator_weights.err().unwrap().message(),
+        "Threshold accepting cannot be combined with operator weights."
+    );
+    assert!(disabled_operator.is_ok());
+}
+
+#[test]
+fn recursion_test() {
+    // ARRANGE
+    let config = |recursion: RecursionConfig, local_search: LocalSearchConfig| {
+        with_local_search(LocalSearchConfig {
+            recursion: Some(recursion),
+            ..local_search
+        })
+        .validated()
+    };
+    let recursion = RecursionConfig {
+        depth: 2,
+        width: 3,
+        candidate_budget: Some(4),
+    };
+
+    // ACT
+    let zero_width = config(
+        RecursionConfig {
+            width: 0,
+            ..recursion
+        },
+        LocalSearchConfig::default(),
+    );
+    let zero_budget = config(
+        RecursionConfig {
+            candidate_budget: Some(0),
+            ..recursion
+        },
+        LocalSearchConfig::default(),
+    );
+    let with_threshold_accepting = config(
+        recursion,
+        LocalSearchConfig {
+            threshold_accepting: Some(ThresholdAcceptingConfig {
+                initial_threshold: 1000,
+                decay: 0.5,
+            }),
+            ..LocalSearchConfig::default()
+        },
+    );
+    let with_time_window = config(
+        recursion,
+        LocalSearchConfig {
+            time_window: Some(Duration::from_seconds(3600)),
+            ..LocalSearchConfig::default()
+        },
+    );
+    let valid = config(recursion, LocalSearchConfig::default());
+
+    // ASSERT
+    assert_eq!(
+        zero_width.err().unwrap().message(),
+        "Recursion width must be positive if the recursion depth is."
+    );
+    assert_eq!(
+        zero_budget.err().unwrap().message(),
+        "Recursion candidate budget must be positive."
+    );
+    assert_eq!(
+        with_threshold_accepting.err().unwrap().message(),
+        "Threshold accepting cannot be combined with recursion."
+    );
+    assert_eq!(
+        with_time_window.err().unwrap().message(),
+        "Recursion cannot be combined with a time window."
+    );
+    assert_eq!(valid.unwrap().local_search.recursion, Some(recursion));
+}
+
+#[test]
+fn zero_max_dummy_tours_test() {
+    // ACT
+    let config = Config {
+        max_dummy_tours: 0,
+        ..valid_config()
+    }
+    .validated();
+
+    // ASSERT
+    assert_eq!(
+        config.err().unwrap(),
+        ConfigError::InvalidLimit(String::from(
+            "Maximal number of dummy tours must be positive."
+        ))
+    );
+}
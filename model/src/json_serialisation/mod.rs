@@ -13,30 +13,44 @@
 
 #[cfg(test)]
 mod tests;
+mod units;
 
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use rapid_time::{DateTime, Duration};
 
 use crate::base_types::{
-    DepotIdx, Distance, Idx, LocationIdx, Meter, PassengerCount, VehicleCount, VehicleTypeIdx,
-    MAX_DISTANCE,
+    DepotIdx, Distance, Idx, LocationIdx, PassengerCount, RoundingPolicy, StationSide, TrainLength,
+    VehicleCount, VehicleTypeIdx, MAX_DISTANCE,
+};
+use crate::config::{
+    Config, ConfigError, CostsConfig, LocalSearchConfig, MaintenanceConfig, OperatorWeights,
+    RecursionConfig, RotationClosure, ShuntingConfig, StartSolver, ThresholdAcceptingConfig,
+};
+use crate::locations::{
+    CoordinateDistances, Coordinates, DeadHeadTrip, DistanceProvider, Locations, MatrixDistances,
+    TimeBand,
 };
-use crate::config::Config;
-use crate::locations::{DeadHeadTrip, Locations};
 use crate::network::depot::Depot as ModelDepot;
 use crate::network::nodes::MaintenanceSlot as ModelMaintenanceSlot;
 use crate::network::nodes::Node;
 use crate::network::nodes::ServiceTrip as ModelServiceTrip;
 use crate::network::Network;
 use crate::vehicle_types::VehicleType as ModelVehicleType;
-use crate::vehicle_types::VehicleTypes;
+use crate::vehicle_types::{RosterUnit, VehicleTypes};
+use units::JsonDistance;
 
 type IdType = String;
 type Integer = u64;
 type DateTimeString = String;
 
+/// Schema version of instances that still use the legacy field names
+/// (`numberOfSeats` for vehicle types and `travelDistanceInMeter` for route segments).
+const LEGACY_SCHEMA_VERSION: Integer = 1;
+const CURRENT_SCHEMA_VERSION: Integer = 2;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct JsonInput {
@@ -48,6 +62,8 @@ struct JsonInput {
     maintenance_slots: Option<Vec<MaintenanceSlots>>,
     dead_head_trips: DeadHeadTrips,
     parameters: Parameters,
+    fixed_fleet: Option<Vec<FleetEntry>>,
+    vehicle_roster: Option<Vec<RosterEntry>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -56,7 +72,10 @@ struct VehicleType {
     id: IdType,
     capacity: Integer,
     seats: Integer,
+    length: Option<Integer>,
     maximal_formation_count: Option<Integer>,
+    home_depots: Option<Vec<IdType>>,
+    maintenance_range_km: Option<Integer>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -64,6 +83,17 @@ struct VehicleType {
 struct Location {
     id: IdType,
     day_limit: Option<Integer>,
+    #[serde(default, deserialize_with = "units::optional_seconds")]
+    min_turnaround: Option<Integer>,
+    coordinates: Option<JsonCoordinates>,
+}
+
+/// Position of a location in degrees (WGS 84).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonCoordinates {
+    latitude: f64,
+    longitude: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -71,13 +101,36 @@ struct Location {
 struct Depot {
     id: IdType,
     location: IdType,
-    capacity: Integer,
+    #[serde(deserialize_with = "units::capacity")]
+    capacity: Option<Integer>, // None: unlimited ("Infinity")
     allowed_types: Vec<TypeCapacities>,
 }
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct TypeCapacities {
     vehicle_type: IdType,
+    #[serde(default, deserialize_with = "units::optional_capacity")]
+    capacity: Option<Integer>,
+}
+
+/// Number of vehicles of a vehicle type that are stationed at a depot. If a fixed fleet is given,
+/// these are the only vehicles that can be spawned.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FleetEntry {
+    depot: IdType,
+    vehicle_type: IdType,
+    count: Integer,
+}
+
+/// Seats and capacity of a specific vehicle (as named in the output) that deviate from its
+/// vehicle type.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RosterEntry {
+    id: IdType,
+    vehicle_type: IdType,
+    seats: Option<Integer>,
     capacity: Option<Integer>,
 }
 
@@ -87,6 +140,9 @@ struct Route {
     id: IdType,
     vehicle_type: IdType,
     segments: Vec<RouteSegment>,
+    min_vehicles: Option<Integer>,
+    allowed_vehicle_types: Option<Vec<IdType>>, // in addition to vehicle_type
+    forbidden_vehicle_types: Option<Vec<IdType>>, // all other types are allowed
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -96,9 +152,12 @@ struct RouteSegment {
     order: Integer,
     origin: IdType,
     destination: IdType,
-    distance: Integer,
+    distance: JsonDistance,
+    #[serde(deserialize_with = "units::seconds")]
     duration: Integer,
     maximal_formation_count: Option<Integer>,
+    origin_side: Option<JsonStationSide>,
+    destination_side: Option<JsonStationSide>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -107,6 +166,17 @@ struct Departures {
     id: IdType,
     route: IdType,
     segments: Vec<DepartureSegment>,
+    frequency: Option<Frequency>,
+}
+
+/// The departure is repeated `count` times, each `headway` after the previous one. The segment
+/// departures of the first repetition are given in the segments.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Frequency {
+    #[serde(deserialize_with = "units::seconds")]
+    headway: Integer,
+    count: Integer,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -117,6 +187,7 @@ struct DepartureSegment {
     departure: DateTimeString,
     passengers: Integer,
     seated: Integer,
+    preferred_successor: Option<IdType>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -133,31 +204,151 @@ struct MaintenanceSlots {
 #[serde(rename_all = "camelCase")]
 struct DeadHeadTrips {
     indices: Vec<IdType>,
+    #[serde(deserialize_with = "units::seconds_matrix")]
     durations: Vec<Vec<Integer>>,
-    distances: Vec<Vec<Integer>>,
+    distances: Option<Vec<Vec<JsonDistance>>>, // None: distances are computed from the coordinates
+    time_bands: Option<Vec<JsonTimeBand>>,
+    origin_side: Option<JsonStationSide>, // applies to all dead head trips
+    destination_side: Option<JsonStationSide>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum JsonStationSide {
+    Front,
+    Back,
+}
+
+/// Station sides of a trip, where missing sides are the defaults (see StationSide).
+fn station_sides(
+    origin_side: Option<JsonStationSide>,
+    destination_side: Option<JsonStationSide>,
+) -> (StationSide, StationSide) {
+    let convert = |side: Option<JsonStationSide>, default: StationSide| match side {
+        Some(JsonStationSide::Front) => StationSide::Front,
+        Some(JsonStationSide::Back) => StationSide::Back,
+        None => default,
+    };
+    (
+        convert(origin_side, StationSide::DEFAULT_ORIGIN),
+        convert(destination_side, StationSide::DEFAULT_DESTINATION),
+    )
+}
+
+/// Overrides the duration of the dead head trip from origin to destination for departures within
+/// [start, end).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonTimeBand {
+    origin: IdType,
+    destination: IdType,
+    start: String,
+    end: String,
+    #[serde(deserialize_with = "units::seconds")]
+    duration: Integer,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct Parameters {
     forbid_dead_head_trips: Option<bool>,
+    #[serde(default, deserialize_with = "units::optional_seconds")]
     day_limit_threshold: Option<Integer>,
     shunting: Shunting,
     maintenance: Option<Maintenance>,
     costs: Costs,
+    local_search: Option<LocalSearch>,
+    depot_capacity_scale: Option<f64>,
+    #[serde(default, deserialize_with = "units::optional_seconds")]
+    unserved_demand_bucket: Option<Integer>,
+    rounding: Option<JsonRounding>,
+    max_dummy_tours: Option<Integer>,
+    closed_rotations: Option<JsonClosedRotations>,
+    start_solver: Option<JsonStartSolver>,
+    reference_assignment: Option<HashMap<IdType, Vec<IdType>>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum JsonRounding {
+    Truncate,
+    RoundHalfUp,
+    Ceil,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum JsonStartSolver {
+    MinCostFlow,
+    MinCostFlowByDistance,
+    OneNodePerTour,
+}
+
+/// "open", {"soft": penaltyPerOpenRotation} or "strict".
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum JsonClosedRotations {
+    Open,
+    Soft(Integer),
+    Strict,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct Shunting {
+    #[serde(deserialize_with = "units::seconds")]
     minimal_duration: Integer,
+    #[serde(deserialize_with = "units::seconds")]
     dead_head_trip_duration: Integer,
+    zero_same_location: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct Maintenance {
-    maximal_distance: Integer,
+    maximal_distance: JsonDistance,
+    mandatory_daily_visit: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LocalSearch {
+    plateau_length: Option<Integer>,
+    perturbation_strength: Option<Integer>,
+    deterministic: Option<bool>,
+    #[serde(default, deserialize_with = "units::optional_seconds")]
+    time_window: Option<Integer>,
+    operator_weights: Option<JsonOperatorWeights>,
+    adaptation_rate: Option<f64>,
+    max_iterations: Option<Integer>,
+    threshold_accepting: Option<JsonThresholdAccepting>,
+    evaluation_cache_size: Option<Integer>,
+    recursion: Option<JsonRecursion>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonThresholdAccepting {
+    initial_threshold: Integer,
+    decay: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonRecursion {
+    depth: u8,
+    width: u8,
+    candidate_budget: Option<Integer>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct JsonOperatorWeights {
+    spawn_vehicle_for_maintenance: Option<f64>,
+    path_exchange: Option<f64>,
+    add_trip_for_hitch_hiking: Option<f64>,
+    remove_single_node: Option<f64>,
+    home_vehicle: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -168,34 +359,165 @@ struct Costs {
     maintenance: Option<Integer>,
     dead_head_trip: Integer,
     idle: Integer,
+    non_home_spawn: Option<Integer>,
 }
 
+/// Same as try_load_rolling_stock_problem_instance_from_json, but panics on invalid parameters.
 pub fn load_rolling_stock_problem_instance_from_json(
     input_data: serde_json::Value,
 ) -> Arc<Network> {
-    let json_input = serde_json::from_value(input_data).expect(
+    try_load_rolling_stock_problem_instance_from_json(input_data)
+        .unwrap_or_else(|err| panic!("Invalid parameters: {}", err))
+}
+
+/// Loads the instance. Invalid parameters are returned as ConfigError, other invalid input
+/// (e.g., unknown ids) still panics.
+pub fn try_load_rolling_stock_problem_instance_from_json(
+    input_data: serde_json::Value,
+) -> Result<Arc<Network>, ConfigError> {
+    let input_data =
+        normalize_to_current_schema(input_data).unwrap_or_else(|err| panic!("{}", err));
+    let mut json_input: JsonInput = serde_json::from_value(input_data).expect(
         "Could not parse input data. Please check if the input data is in the correct format",
     );
+    json_input.departures = expand_frequencies(std::mem::take(&mut json_input.departures));
 
-    let (locations, location_lookup) = create_locations(&json_input);
+    let config = create_config(&json_input)?;
+    let (locations, location_lookup) = create_locations(&json_input, config.rounding);
     let (vehicle_types, vehicle_type_lookup) = create_vehicle_types(&json_input);
-    let config = create_config(&json_input);
 
-    Arc::new(create_network(
+    Ok(Arc::new(create_network(
         &json_input,
         locations,
         vehicle_types,
         config,
         location_lookup,
         vehicle_type_lookup,
-    ))
+    )))
+}
+
+/// Replaces each departure with a frequency by its individual repetitions. The departure id and
+/// the segment ids of the k-th repetition (starting at 0) are suffixed with "_k", such that the
+/// service trips in the output can be traced back to the frequency. Preferred successors within
+/// the same departure refer to the same repetition.
+fn expand_frequencies(departures: Vec<Departures>) -> Vec<Departures> {
+    let mut expanded_departures = Vec::new();
+    for departure in departures {
+        let Some(frequency) = &departure.frequency else {
+            expanded_departures.push(departure);
+            continue;
+        };
+        let segment_ids: HashSet<&IdType> = departure
+            .segments
+            .iter()
+            .map(|segment| &segment.id)
+            .collect();
+        for k in 0..frequency.count {
+            let offset = Duration::from_seconds(k * frequency.headway);
+            let suffixed = |id: &IdType| format!("{}_{}", id, k);
+            expanded_departures.push(Departures {
+                id: suffixed(&departure.id),
+                route: departure.route.clone(),
+                segments: departure
+                    .segments
+                    .iter()
+                    .map(|segment| DepartureSegment {
+                        id: suffixed(&segment.id),
+                        route_segment: segment.route_segment.clone(),
+                        departure: (DateTime::new(&segment.departure) + offset).as_iso(),
+                        passengers: segment.passengers,
+                        seated: segment.seated,
+                        preferred_successor: segment.preferred_successor.as_ref().map(
+                            |successor| {
+                                if segment_ids.contains(successor) {
+                                    suffixed(successor)
+                                } else {
+                                    successor.clone()
+                                }
+                            },
+                        ),
+                    })
+                    .collect(),
+                frequency: None,
+            });
+        }
+    }
+    expanded_departures
+}
+
+/// Dispatches on the top-level `schemaVersion` field and converts the input into the current
+/// schema. Instances without a version are accepted with both the legacy and the current field
+/// names. Unknown versions result in an error.
+fn normalize_to_current_schema(
+    mut input_data: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let schema_version = match input_data.get("schemaVersion") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(version) => Some(version.as_u64().ok_or_else(|| {
+            format!(
+                "Invalid schemaVersion {}. Expected a non-negative integer.",
+                version
+            )
+        })?),
+    };
+
+    match schema_version {
+        Some(CURRENT_SCHEMA_VERSION) => Ok(input_data),
+        None | Some(LEGACY_SCHEMA_VERSION) => {
+            if let Some(vehicle_types) = input_data
+                .get_mut("vehicleTypes")
+                .and_then(|v| v.as_array_mut())
+            {
+                for vehicle_type in vehicle_types.iter_mut() {
+                    rename_legacy_field(vehicle_type, "numberOfSeats", "seats");
+                }
+            }
+            if let Some(routes) = input_data.get_mut("routes").and_then(|r| r.as_array_mut()) {
+                for route in routes.iter_mut() {
+                    if let Some(segments) = route.get_mut("segments").and_then(|s| s.as_array_mut())
+                    {
+                        for segment in segments.iter_mut() {
+                            rename_legacy_field(segment, "travelDistanceInMeter", "distance");
+                        }
+                    }
+                }
+            }
+            if let Some(object) = input_data.as_object_mut() {
+                object.insert(
+                    String::from("schemaVersion"),
+                    serde_json::Value::from(CURRENT_SCHEMA_VERSION),
+                );
+            }
+            Ok(input_data)
+        }
+        Some(version) => Err(format!(
+            "Unknown schemaVersion {}. Supported versions are {} (legacy) and {} (current).",
+            version, LEGACY_SCHEMA_VERSION, CURRENT_SCHEMA_VERSION
+        )),
+    }
+}
+
+/// Moves the value of `legacy_name` to `current_name`. If both are present, the current one wins.
+fn rename_legacy_field(value: &mut serde_json::Value, legacy_name: &str, current_name: &str) {
+    if let Some(object) = value.as_object_mut() {
+        if let Some(legacy_value) = object.remove(legacy_name) {
+            object
+                .entry(current_name.to_string())
+                .or_insert(legacy_value);
+        }
+    }
 }
 
-fn create_locations(json_input: &JsonInput) -> (Locations, HashMap<IdType, LocationIdx>) {
+fn create_locations(
+    json_input: &JsonInput,
+    rounding: RoundingPolicy,
+) -> (Locations, HashMap<IdType, LocationIdx>) {
     let planning_days = determine_planning_days(json_input);
     let mut stations: HashMap<LocationIdx, (String, Option<VehicleCount>)> = HashMap::new(); // PpRF: use vec instead
     let mut dead_head_trips: HashMap<LocationIdx, HashMap<LocationIdx, DeadHeadTrip>> =
         HashMap::new();
+    let mut distances: HashMap<LocationIdx, HashMap<LocationIdx, Distance>> = HashMap::new();
+    let mut min_turnarounds: HashMap<LocationIdx, Duration> = HashMap::new();
 
     let mut location_lookup: HashMap<IdType, LocationIdx> = HashMap::new();
 
@@ -209,49 +531,151 @@ fn create_locations(json_input: &JsonInput) -> (Locations, HashMap<IdType, Locat
                 location_json.day_limit.map(|x| x as VehicleCount),
             ),
         );
+        if let Some(min_turnaround) = location_json.min_turnaround {
+            min_turnarounds.insert(location_idx, Duration::from_seconds(min_turnaround));
+        }
         location_lookup.insert(location_json.id.clone(), location_idx);
     }
 
-    // add dead head trips
-    let mut duration_warning_printed = false;
-    let mut distance_warning_printed = false;
-    for (i, origin_json) in json_input.dead_head_trips.indices.iter().enumerate() {
-        let origin_station = location_lookup[origin_json];
-        let mut destination_map: HashMap<LocationIdx, DeadHeadTrip> = HashMap::new();
-        for (j, destination_json) in json_input.dead_head_trips.indices.iter().enumerate() {
-            let mut duration = Duration::from_seconds(json_input.dead_head_trips.durations[i][j]);
-            if duration > planning_days {
-                if !duration_warning_printed {
-                    println!(
-                        "\x1b[93mwarning:\x1b[0m Some dead head trip durations exceed planning duration of {} day(s). \
-                        Taking planning duration instead.",
-                        planning_days.in_min().unwrap() / 1440
-                    );
-                    duration_warning_printed = true;
+    // add dead head trips (rows in parallel, assembled in order)
+    let (origin_side, destination_side) = station_sides(
+        json_input.dead_head_trips.origin_side,
+        json_input.dead_head_trips.destination_side,
+    );
+    let rows: Vec<_> = json_input
+        .dead_head_trips
+        .indices
+        .par_iter()
+        .enumerate()
+        .map(|(i, origin_json)| {
+            let mut duration_exceeded = false;
+            let mut distance_exceeded = false;
+            let mut destination_map: HashMap<LocationIdx, DeadHeadTrip> = HashMap::new();
+            let mut distance_map: HashMap<LocationIdx, Distance> = HashMap::new();
+            for (j, destination_json) in json_input.dead_head_trips.indices.iter().enumerate() {
+                let mut duration =
+                    Duration::from_seconds(json_input.dead_head_trips.durations[i][j]);
+                if duration > planning_days {
+                    duration_exceeded = true;
+                    duration = planning_days;
                 }
-                duration = planning_days;
-            }
-            let mut distance = Distance::from_meter(json_input.dead_head_trips.distances[i][j]);
-            if distance > Distance::from_meter(MAX_DISTANCE) {
-                if !distance_warning_printed {
-                    println!(
-                        "\x1b[93mwarning:\x1b[0m Some dead head trip distances exceed {}m. \
-                        This might be a mistake. Distance reduced to {}m.",
-                        MAX_DISTANCE, MAX_DISTANCE
-                    );
-                    distance_warning_printed = true;
+                if let Some(matrix) = json_input.dead_head_trips.distances.as_ref() {
+                    let mut distance = matrix[i][j].to_distance(rounding);
+                    if distance > Distance::from_meter(MAX_DISTANCE) {
+                        distance_exceeded = true;
+                        distance = Distance::from_meter(MAX_DISTANCE);
+                    }
+                    distance_map.insert(location_lookup[destination_json], distance);
                 }
-                distance = Distance::from_meter(MAX_DISTANCE);
+                destination_map.insert(
+                    location_lookup[destination_json],
+                    DeadHeadTrip::new(duration).with_station_sides(origin_side, destination_side),
+                );
             }
-            destination_map.insert(
-                location_lookup[destination_json],
-                DeadHeadTrip::new(distance, duration),
+            (
+                location_lookup[origin_json],
+                destination_map,
+                distance_map,
+                duration_exceeded,
+                distance_exceeded,
+            )
+        })
+        .collect();
+    if rows
+        .iter()
+        .any(|(_, _, _, duration_exceeded, _)| *duration_exceeded)
+    {
+        println!(
+            "\x1b[93mwarning:\x1b[0m Some dead head trip durations exceed planning duration of {} day(s). \
+            Taking planning duration instead.",
+            planning_days.in_min().unwrap() / 1440
+        );
+    }
+    if rows
+        .iter()
+        .any(|(_, _, _, _, distance_exceeded)| *distance_exceeded)
+    {
+        println!(
+            "\x1b[93mwarning:\x1b[0m Some dead head trip distances exceed {}m. \
+            This might be a mistake. Distance reduced to {}m.",
+            MAX_DISTANCE, MAX_DISTANCE
+        );
+    }
+    for (origin_station, destination_map, distance_map, _, _) in rows {
+        dead_head_trips.insert(origin_station, destination_map);
+        distances.insert(origin_station, distance_map);
+    }
+
+    // distances from the matrix or, if there is none, from the coordinates of the locations
+    let distance_provider: Box<dyn DistanceProvider> =
+        if json_input.dead_head_trips.distances.is_some() {
+            Box::new(MatrixDistances::new(distances))
+        } else {
+            let coordinates = json_input
+                .locations
+                .iter()
+                .map(|location_json| {
+                    let coordinates = location_json.coordinates.as_ref().unwrap_or_else(|| {
+                        panic!(
+                            "Invalid deadHeadTrips: without distances, all locations need \
+                            coordinates, but {} has none.",
+                            location_json.id
+                        )
+                    });
+                    (
+                        location_lookup[&location_json.id],
+                        Coordinates::new(coordinates.latitude, coordinates.longitude),
+                    )
+                })
+                .collect();
+            Box::new(CoordinateDistances::new(coordinates))
+        };
+
+    // add time bands
+    let mut time_bands: HashMap<(LocationIdx, LocationIdx), Vec<TimeBand>> = HashMap::new();
+    for band in json_input.dead_head_trips.time_bands.iter().flatten() {
+        let location = |id: &IdType| {
+            *location_lookup.get(id).unwrap_or_else(|| {
+                panic!(
+                    "Invalid deadHeadTrips: unknown location {} in timeBands.",
+                    id
+                )
+            })
+        };
+        let start = DateTime::new(&band.start);
+        let end = DateTime::new(&band.end);
+        if start >= end {
+            panic!(
+                "Invalid deadHeadTrips: time band from {} to {} needs to start before it ends.",
+                band.start, band.end
             );
         }
-        dead_head_trips.insert(origin_station, destination_map);
+        time_bands
+            .entry((location(&band.origin), location(&band.destination)))
+            .or_default()
+            .push(TimeBand::new(
+                start,
+                end,
+                Duration::from_seconds(band.duration).min(planning_days),
+            ));
+    }
+    for ((origin, destination), bands) in time_bands {
+        let destination_map = dead_head_trips.get_mut(&origin).unwrap_or_else(|| {
+            panic!("Invalid deadHeadTrips: time band for a missing dead head trip.")
+        });
+        let dead_head_trip = destination_map.remove(&destination).unwrap_or_else(|| {
+            panic!("Invalid deadHeadTrips: time band for a missing dead head trip.")
+        });
+        destination_map.insert(destination, dead_head_trip.with_time_bands(bands));
     }
 
-    (Locations::new(stations, dead_head_trips), location_lookup)
+    let locations = Locations::new(
+        stations,
+        dead_head_trips,
+        distance_provider,
+        min_turnarounds,
+    );
+    (locations, location_lookup)
 }
 
 fn determine_planning_days(json_input: &JsonInput) -> Duration {
@@ -297,6 +721,7 @@ fn determine_planning_days(json_input: &JsonInput) -> Duration {
 
 fn create_vehicle_types(json_input: &JsonInput) -> (VehicleTypes, HashMap<IdType, VehicleTypeIdx>) {
     let mut vehicle_type_lookup: HashMap<IdType, VehicleTypeIdx> = HashMap::new();
+    let depot_lookup = create_depot_lookup(json_input);
     let vehicle_types: Vec<ModelVehicleType> = json_input
         .vehicle_types
         .iter()
@@ -309,45 +734,214 @@ fn create_vehicle_types(json_input: &JsonInput) -> (VehicleTypes, HashMap<IdType
                 vehicle_type.id.clone(),
                 vehicle_type.capacity as PassengerCount,
                 vehicle_type.seats as PassengerCount,
+                vehicle_type.length.unwrap_or(0) as TrainLength,
+                positive_formation_count(vehicle_type.maximal_formation_count, &vehicle_type.id),
+                vehicle_type.home_depots.as_ref().map(|home_depots| {
+                    home_depots
+                        .iter()
+                        .map(|depot| depot_lookup[depot])
+                        .collect()
+                }),
                 vehicle_type
-                    .maximal_formation_count
-                    .map(|x| x as VehicleCount),
+                    .maintenance_range_km
+                    .map(|km| Distance::from_meter(km * 1000)),
             )
         })
         .collect();
 
-    (VehicleTypes::new(vehicle_types), vehicle_type_lookup)
+    let mut unit_ids: HashSet<&IdType> = HashSet::new();
+    let roster = json_input
+        .vehicle_roster
+        .iter()
+        .flatten()
+        .map(|entry| {
+            if !unit_ids.insert(&entry.id) {
+                panic!("Invalid vehicleRoster: duplicate unit id {}.", entry.id);
+            }
+            let vehicle_type = *vehicle_type_lookup
+                .get(&entry.vehicle_type)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Invalid vehicleRoster: unknown vehicle type {}.",
+                        entry.vehicle_type
+                    )
+                });
+            RosterUnit::new(
+                entry.id.clone(),
+                vehicle_type,
+                entry.seats.map(|seats| seats as PassengerCount),
+                entry.capacity.map(|capacity| capacity as PassengerCount),
+            )
+        })
+        .collect();
+
+    let vehicle_types = VehicleTypes::new(vehicle_types).with_roster(roster);
+    if let Some(warning) = vehicle_types.capacity_below_seats_warning() {
+        println!("\x1b[93mwarning:\x1b[0m {}", warning);
+    }
+    (vehicle_types, vehicle_type_lookup)
+}
+
+/// A maximal formation count of zero would make the service trips impossible to cover, hence it is
+/// rejected (None means unbounded).
+fn positive_formation_count(count: Option<Integer>, id: &IdType) -> Option<VehicleCount> {
+    if count == Some(0) {
+        panic!(
+            "Invalid parameters: maximalFormationCount of {} must be positive.",
+            id
+        );
+    }
+    count.map(|count| count as VehicleCount)
+}
+
+/// Maps the depot ids to their indices (same order as in create_depots).
+fn create_depot_lookup(json_input: &JsonInput) -> HashMap<IdType, DepotIdx> {
+    match &json_input.depots {
+        None => json_input
+            .locations
+            .iter()
+            .enumerate()
+            .map(|(idx, location)| (format!("depot_{}", location.id), DepotIdx::from(idx as Idx)))
+            .collect(),
+        Some(depots) => depots
+            .iter()
+            .enumerate()
+            .map(|(idx, depot)| (depot.id.clone(), DepotIdx::from(idx as Idx)))
+            .collect(),
+    }
 }
 
-fn create_config(json_input: &JsonInput) -> Config {
-    if json_input.parameters.costs.dead_head_trip <= json_input.parameters.costs.service_trip {
+fn create_config(json_input: &JsonInput) -> Result<Config, ConfigError> {
+    let parameters = &json_input.parameters;
+    if parameters.costs.dead_head_trip <= parameters.costs.service_trip {
         println!(
             "\x1b[93mwarning:\x1b[0m Dead head trip costs are lower than service trip costs. \
             Vehicle will not hitch-hike on service trips."
         );
     }
-    Config::new(
-        json_input
-            .parameters
-            .forbid_dead_head_trips
-            .unwrap_or(false),
-        Duration::from_seconds(json_input.parameters.day_limit_threshold.unwrap_or(0)),
-        Duration::from_seconds(json_input.parameters.shunting.minimal_duration),
-        Duration::from_seconds(json_input.parameters.shunting.dead_head_trip_duration),
-        Distance::from_meter(
-            json_input
-                .parameters
+    let default = Config::default();
+    let local_search = parameters.local_search.as_ref();
+    let rounding = match parameters.rounding {
+        Some(JsonRounding::Truncate) => RoundingPolicy::Truncate,
+        Some(JsonRounding::RoundHalfUp) => RoundingPolicy::RoundHalfUp,
+        Some(JsonRounding::Ceil) => RoundingPolicy::Ceil,
+        None => default.rounding,
+    };
+    Config {
+        forbid_dead_head_trip: parameters.forbid_dead_head_trips.unwrap_or(false),
+        fixed_fleet: json_input.fixed_fleet.is_some(),
+        day_limit_threshold: Duration::from_seconds(parameters.day_limit_threshold.unwrap_or(0)),
+        shunting: ShuntingConfig {
+            minimal: Duration::from_seconds(parameters.shunting.minimal_duration),
+            dead_head_trip: Duration::from_seconds(parameters.shunting.dead_head_trip_duration),
+            zero_same_location: parameters.shunting.zero_same_location.unwrap_or(false),
+        },
+        maintenance: MaintenanceConfig {
+            maximal_distance: parameters
                 .maintenance
                 .as_ref()
-                .map(|m| m.maximal_distance)
-                .unwrap_or(0),
-        ),
-        json_input.parameters.costs.staff,
-        json_input.parameters.costs.service_trip,
-        json_input.parameters.costs.maintenance.unwrap_or(0),
-        json_input.parameters.costs.dead_head_trip,
-        json_input.parameters.costs.idle,
-    )
+                .map_or(Distance::ZERO, |m| m.maximal_distance.to_distance(rounding)),
+            mandatory_daily_visit: parameters
+                .maintenance
+                .as_ref()
+                .and_then(|m| m.mandatory_daily_visit)
+                .unwrap_or(false),
+        },
+        costs: CostsConfig {
+            staff: parameters.costs.staff,
+            service_trip: parameters.costs.service_trip,
+            maintenance: parameters.costs.maintenance.unwrap_or(0),
+            dead_head_trip: parameters.costs.dead_head_trip,
+            idle: parameters.costs.idle,
+            non_home_spawn: parameters.costs.non_home_spawn.unwrap_or(0),
+        },
+        local_search: LocalSearchConfig {
+            plateau_length: local_search
+                .and_then(|l| l.plateau_length)
+                .map_or(default.local_search.plateau_length, |length| length as u32),
+            perturbation_strength: local_search
+                .and_then(|l| l.perturbation_strength)
+                .map_or(default.local_search.perturbation_strength, |strength| {
+                    strength as u32
+                }),
+            deterministic: local_search
+                .and_then(|l| l.deterministic)
+                .unwrap_or(default.local_search.deterministic),
+            time_window: local_search
+                .and_then(|l| l.time_window)
+                .map(Duration::from_seconds),
+            operator_weights: create_operator_weights(json_input),
+            adaptation_rate: local_search
+                .and_then(|l| l.adaptation_rate)
+                .unwrap_or(default.local_search.adaptation_rate),
+            max_iterations: local_search
+                .and_then(|l| l.max_iterations)
+                .map(|max_iterations| max_iterations as usize),
+            threshold_accepting: local_search
+                .and_then(|l| l.threshold_accepting.as_ref())
+                .map(|threshold_accepting| ThresholdAcceptingConfig {
+                    initial_threshold: threshold_accepting.initial_threshold,
+                    decay: threshold_accepting.decay,
+                }),
+            evaluation_cache_size: local_search
+                .and_then(|l| l.evaluation_cache_size)
+                .map_or(default.local_search.evaluation_cache_size, |size| {
+                    size as usize
+                }),
+            recursion: local_search
+                .and_then(|l| l.recursion.as_ref())
+                .map(|recursion| RecursionConfig {
+                    depth: recursion.depth,
+                    width: recursion.width,
+                    candidate_budget: recursion.candidate_budget.map(|budget| budget as usize),
+                }),
+        },
+        unserved_demand_bucket: parameters
+            .unserved_demand_bucket
+            .map_or(default.unserved_demand_bucket, Duration::from_seconds),
+        rounding,
+        max_dummy_tours: parameters
+            .max_dummy_tours
+            .map_or(default.max_dummy_tours, |max| max as usize),
+        rotation_closure: match parameters.closed_rotations {
+            Some(JsonClosedRotations::Open) | None => RotationClosure::Open,
+            Some(JsonClosedRotations::Soft(penalty)) => RotationClosure::Soft(penalty),
+            Some(JsonClosedRotations::Strict) => RotationClosure::Strict,
+        },
+        start_solver: match parameters.start_solver {
+            Some(JsonStartSolver::MinCostFlow) | None => StartSolver::MinCostFlow,
+            Some(JsonStartSolver::MinCostFlowByDistance) => StartSolver::MinCostFlowByDistance,
+            Some(JsonStartSolver::OneNodePerTour) => StartSolver::OneNodePerTour,
+        },
+        reference_assignment: parameters.reference_assignment.clone(),
+    }
+    .validated()
+}
+
+/// Operator weights of the local search. Missing weights default to 1.
+fn create_operator_weights(json_input: &JsonInput) -> OperatorWeights {
+    let default = OperatorWeights::default();
+    match json_input
+        .parameters
+        .local_search
+        .as_ref()
+        .and_then(|l| l.operator_weights.as_ref())
+    {
+        None => default,
+        Some(weights) => OperatorWeights {
+            spawn_vehicle_for_maintenance: weights
+                .spawn_vehicle_for_maintenance
+                .unwrap_or(default.spawn_vehicle_for_maintenance),
+            path_exchange: weights.path_exchange.unwrap_or(default.path_exchange),
+            add_trip_for_hitch_hiking: weights
+                .add_trip_for_hitch_hiking
+                .unwrap_or(default.add_trip_for_hitch_hiking),
+            remove_single_node: weights
+                .remove_single_node
+                .unwrap_or(default.remove_single_node),
+            home_vehicle: weights.home_vehicle.unwrap_or(default.home_vehicle),
+        },
+    }
 }
 
 fn create_network(
@@ -364,6 +958,7 @@ fn create_network(
         &vehicle_types,
         &location_lookup,
         &vehicle_type_lookup,
+        config.rounding,
     );
 
     let number_of_service_trips: VehicleCount = service_trips
@@ -397,6 +992,13 @@ fn create_depots(
     vehicle_type_lookup: &HashMap<IdType, VehicleTypeIdx>,
     vehicle_upper_limit: VehicleCount,
 ) -> Vec<ModelDepot> {
+    let scale = json_input.parameters.depot_capacity_scale.unwrap_or(1.0);
+    if !scale.is_finite() || scale < 0.0 {
+        panic!(
+            "Invalid parameters: depotCapacityScale must be a non-negative number, got {}.",
+            scale
+        );
+    }
     match &json_input.depots {
         None => {
             // add a depot at every location with unlimited capacity for each type
@@ -408,12 +1010,20 @@ fn create_depots(
             loc.iter()
                 .enumerate()
                 .map(|(idx, location)| {
+                    let id = format!("depot_{}", loc.get_id(location).unwrap());
+                    let (capacity, allowed_types) = apply_fixed_fleet(
+                        json_input,
+                        &id,
+                        vehicle_upper_limit,
+                        allowed_vehicle_types.clone(),
+                        vehicle_type_lookup,
+                    );
                     ModelDepot::new(
                         DepotIdx::from(idx as Idx),
-                        format!("depot_{}", loc.get_id(location).unwrap()),
+                        id,
                         location,
-                        VehicleCount::from(vehicle_upper_limit),
-                        allowed_vehicle_types.clone(),
+                        capacity,
+                        allowed_types,
                     )
                 })
                 .collect()
@@ -424,99 +1034,216 @@ fn create_depots(
             .map(|(idx, depot)| {
                 let idx = DepotIdx::from(idx as Idx);
                 let location = loc.get(location_lookup[&depot.location]).unwrap();
-                let capacity: VehicleCount = depot.capacity as VehicleCount;
+                // an unlimited depot can hold at most one vehicle per service trip (as if
+                // there were no depots)
+                let capacity = depot
+                    .capacity
+                    .map(|x| scale_capacity(x as VehicleCount, scale))
+                    .unwrap_or(vehicle_upper_limit);
                 let mut allowed_types: HashMap<VehicleTypeIdx, Option<VehicleCount>> =
                     HashMap::new();
                 for allowed_type in &depot.allowed_types {
                     allowed_types.insert(
                         vehicle_type_lookup[&allowed_type.vehicle_type],
-                        allowed_type.capacity.map(|x| x as VehicleCount),
+                        allowed_type
+                            .capacity
+                            .map(|x| scale_capacity(x as VehicleCount, scale)),
                     );
                 }
-                ModelDepot::new(
-                    idx,
-                    depot.id.clone(),
-                    location,
+                let (capacity, allowed_types) = apply_fixed_fleet(
+                    json_input,
+                    &depot.id,
                     capacity,
-                    allowed_types.clone(),
-                )
+                    allowed_types,
+                    vehicle_type_lookup,
+                );
+                ModelDepot::new(idx, depot.id.clone(), location, capacity, allowed_types)
             })
             .collect(),
     }
 }
 
+/// Multiplies a finite depot capacity by the given scale, rounding up. A small tolerance avoids
+/// rounding up due to floating point errors (e.g., 10 * 1.1 must be 11, not 12).
+fn scale_capacity(capacity: VehicleCount, scale: f64) -> VehicleCount {
+    (capacity as f64 * scale - 1e-9).ceil().max(0.0) as VehicleCount
+}
+
+/// If a fixed fleet is given, the depot can only spawn the vehicles stationed there. So the
+/// capacity for each vehicle type is the fleet count and the total capacity is the size of the
+/// fleet at this depot (but never more than the depot's own capacity).
+fn apply_fixed_fleet(
+    json_input: &JsonInput,
+    depot_id: &IdType,
+    capacity: VehicleCount,
+    allowed_types: HashMap<VehicleTypeIdx, Option<VehicleCount>>,
+    vehicle_type_lookup: &HashMap<IdType, VehicleTypeIdx>,
+) -> (VehicleCount, HashMap<VehicleTypeIdx, Option<VehicleCount>>) {
+    let fixed_fleet = match &json_input.fixed_fleet {
+        None => return (capacity, allowed_types),
+        Some(fixed_fleet) => fixed_fleet,
+    };
+    let mut fleet: HashMap<VehicleTypeIdx, Option<VehicleCount>> = HashMap::new();
+    for entry in fixed_fleet.iter().filter(|entry| &entry.depot == depot_id) {
+        let vehicle_type = vehicle_type_lookup[&entry.vehicle_type];
+        if !allowed_types.contains_key(&vehicle_type) {
+            println!(
+                "\x1b[93mwarning:\x1b[0m Fixed fleet assigns vehicle type {} to depot {}, \
+                where it is not allowed. The vehicles are ignored.",
+                entry.vehicle_type, depot_id
+            );
+            continue;
+        }
+        let count = fleet
+            .entry(vehicle_type)
+            .or_insert(Some(0))
+            .get_or_insert(0);
+        *count += entry.count as VehicleCount;
+    }
+    let fleet_size: VehicleCount = fleet.values().map(|count| count.unwrap_or(0)).sum();
+    if fleet_size > capacity {
+        println!(
+            "\x1b[93mwarning:\x1b[0m Fixed fleet of depot {} ({} vehicles) exceeds its capacity \
+            ({}).",
+            depot_id, fleet_size, capacity
+        );
+    }
+    (VehicleCount::min(fleet_size, capacity), fleet)
+}
+
+/// Vehicle types that may operate the service trips of the route. Without allowedVehicleTypes
+/// and forbiddenVehicleTypes only the vehicle type of the route is allowed.
+fn allowed_vehicle_types(
+    route: &Route,
+    vehicle_types: &VehicleTypes,
+    vehicle_type_lookup: &HashMap<IdType, VehicleTypeIdx>,
+) -> Vec<VehicleTypeIdx> {
+    let lookup = |id: &IdType| {
+        *vehicle_type_lookup.get(id).unwrap_or_else(|| {
+            panic!(
+                "Invalid routes: unknown vehicle type {} in route {}.",
+                id, route.id
+            )
+        })
+    };
+    match (&route.allowed_vehicle_types, &route.forbidden_vehicle_types) {
+        (Some(_), Some(_)) => panic!(
+            "Invalid routes: route {} lists both allowedVehicleTypes and forbiddenVehicleTypes.",
+            route.id
+        ),
+        (Some(allowed), None) => allowed.iter().map(lookup).collect(),
+        (None, Some(forbidden)) => {
+            let forbidden: Vec<VehicleTypeIdx> = forbidden.iter().map(lookup).collect();
+            if forbidden.contains(&vehicle_type_lookup[&route.vehicle_type]) {
+                panic!(
+                    "Invalid routes: route {} forbids its own vehicle type {}.",
+                    route.id, route.vehicle_type
+                );
+            }
+            vehicle_types
+                .iter()
+                .filter(|vehicle_type| !forbidden.contains(vehicle_type))
+                .collect()
+        }
+        (None, None) => Vec::new(),
+    }
+}
+
 fn create_service_trips(
     json_input: &JsonInput,
     locations: &Locations,
     vehicle_types: &VehicleTypes,
     location_lookup: &HashMap<IdType, LocationIdx>,
     vehicle_type_lookup: &HashMap<IdType, VehicleTypeIdx>,
+    rounding: RoundingPolicy,
 ) -> HashMap<VehicleTypeIdx, Vec<ModelServiceTrip>> {
     let mut service_trips: HashMap<VehicleTypeIdx, Vec<ModelServiceTrip>> = HashMap::new();
     for vehicle_type in vehicle_types.iter() {
         service_trips.insert(vehicle_type, Vec::new());
     }
 
-    let mut warnings_printed = false;
-
-    for departure in json_input.departures.iter() {
-        let route = json_input
-            .routes
-            .iter()
-            .find(|route| route.id == departure.route)
-            .unwrap();
-        let vehicle_type = vehicle_type_lookup[&route.vehicle_type];
+    // service trips of each departure in parallel, assembled in order
+    let departures: Vec<(VehicleTypeIdx, Vec<ModelServiceTrip>, bool)> = json_input
+        .departures
+        .par_iter()
+        .map(|departure| {
+            let route = json_input
+                .routes
+                .iter()
+                .find(|route| route.id == departure.route)
+                .unwrap();
+            let vehicle_type = vehicle_type_lookup[&route.vehicle_type];
+            let allowed_vehicle_types =
+                allowed_vehicle_types(route, vehicle_types, vehicle_type_lookup);
+            let mut passengers_missing = false;
 
-        for departure_segment in departure.segments.iter() {
-            let route_segment = &route
+            let trips = departure
                 .segments
                 .iter()
-                .find(|segment| segment.id == departure_segment.route_segment)
-                .unwrap();
-            let id = departure_segment.id.clone();
-            let origin = locations
-                .get(location_lookup[&route_segment.origin])
-                .unwrap();
-            let destination = locations
-                .get(location_lookup[&route_segment.destination])
-                .unwrap();
-            let departure_time = DateTime::new(&departure_segment.departure);
-            let arrival_time = departure_time + Duration::from_seconds(route_segment.duration);
-            let distance = Distance::from_meter(route_segment.distance as Meter);
-            let mut passengers = departure_segment.passengers as PassengerCount;
-            let seated = departure_segment.seated as PassengerCount;
-
-            if passengers == 0 {
-                passengers = 1;
-                if !warnings_printed {
-                    println!(
-                    "\x1b[93mwarning:\x1b[0m Some service trips have no passengers. Setting passengers to 1, so that at least one vehicle is needed.",
-                );
-                    warnings_printed = true;
-                }
-            }
+                .map(|departure_segment| {
+                    let route_segment = &route
+                        .segments
+                        .iter()
+                        .find(|segment| segment.id == departure_segment.route_segment)
+                        .unwrap();
+                    let id = departure_segment.id.clone();
+                    let origin = locations
+                        .get(location_lookup[&route_segment.origin])
+                        .unwrap();
+                    let destination = locations
+                        .get(location_lookup[&route_segment.destination])
+                        .unwrap();
+                    let departure_time = DateTime::new(&departure_segment.departure);
+                    let arrival_time =
+                        departure_time + Duration::from_seconds(route_segment.duration);
+                    let distance = route_segment.distance.to_distance(rounding);
+                    let mut passengers = departure_segment.passengers as PassengerCount;
+                    let seated = departure_segment.seated as PassengerCount;
 
-            let maximal_formation_count = route_segment
-                .maximal_formation_count
-                .map(|x| x as VehicleCount);
+                    if passengers == 0 {
+                        passengers = 1;
+                        passengers_missing = true;
+                    }
 
-            let service_trip = Node::create_service_trip(
-                id,
-                vehicle_type,
-                origin,
-                destination,
-                departure_time,
-                arrival_time,
-                distance,
-                passengers,
-                seated,
-                maximal_formation_count,
-            );
-            service_trips
-                .get_mut(&vehicle_type)
-                .unwrap()
-                .push(service_trip);
-        }
+                    let maximal_formation_count = positive_formation_count(
+                        route_segment.maximal_formation_count,
+                        &route_segment.id,
+                    );
+                    let (origin_side, destination_side) =
+                        station_sides(route_segment.origin_side, route_segment.destination_side);
+
+                    Node::create_service_trip(
+                        id,
+                        vehicle_type,
+                        origin,
+                        destination,
+                        departure_time,
+                        arrival_time,
+                        distance,
+                        passengers,
+                        seated,
+                        maximal_formation_count,
+                        route.min_vehicles.map(|x| x as VehicleCount),
+                        departure_segment.preferred_successor.clone(),
+                    )
+                    .with_station_sides(origin_side, destination_side)
+                    .with_allowed_vehicle_types(allowed_vehicle_types.clone())
+                })
+                .collect();
+            (vehicle_type, trips, passengers_missing)
+        })
+        .collect();
+
+    if departures
+        .iter()
+        .any(|(_, _, passengers_missing)| *passengers_missing)
+    {
+        println!(
+            "\x1b[93mwarning:\x1b[0m Some service trips have no passengers. Setting passengers to 1, so that at least one vehicle is needed.",
+        );
+    }
+    for (vehicle_type, trips, _) in departures {
+        service_trips.get_mut(&vehicle_type).unwrap().extend(trips);
     }
     service_trips
 }
@@ -16,10 +16,17 @@ use std::{fs::File, io::Read};
 use rapid_time::{DateTime, Duration};
 
 use crate::{
-    base_types::{DepotIdx, Distance, Location, LocationIdx, NodeIdx, VehicleTypeIdx},
-    json_serialisation::load_rolling_stock_problem_instance_from_json,
+    base_types::{
+        DepotIdx, Distance, Location, LocationIdx, NodeIdx, StationSide, VehicleCount,
+        VehicleTypeIdx,
+    },
+    config::ConfigError,
+    json_serialisation::{
+        load_rolling_stock_problem_instance_from_json,
+        try_load_rolling_stock_problem_instance_from_json,
+    },
     locations::Locations,
-    network::nodes::Node,
+    network::{nodes::Node, Network},
     vehicle_types::VehicleType,
 };
 
@@ -34,13 +41,677 @@ fn test_load_from_json_with_null() {
     test_load_from_json("resources/small_test_input_with_null_values.json");
 }
 
-fn test_load_from_json(path: &str) {
+#[test]
+fn test_load_from_json_legacy_field_names() {
+    test_load_from_json("resources/small_test_input_legacy.json");
+}
+
+#[test]
+#[should_panic(expected = "Unknown schemaVersion 99")]
+fn test_load_from_json_unknown_schema_version() {
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["schemaVersion"] = serde_json::Value::from(99);
+
+    load_rolling_stock_problem_instance_from_json(input_data);
+}
+
+#[test]
+#[should_panic(expected = "Invalid parameters: Maximal number of dummy tours must be positive.")]
+fn test_load_from_json_invalid_parameters() {
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["parameters"]["maxDummyTours"] = serde_json::Value::from(0);
+
+    load_rolling_stock_problem_instance_from_json(input_data);
+}
+
+#[test]
+fn test_try_load_from_json_invalid_parameters() {
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["parameters"]["maxDummyTours"] = serde_json::Value::from(0);
+
+    let result = try_load_rolling_stock_problem_instance_from_json(input_data);
+
+    assert_eq!(
+        result.err().unwrap(),
+        ConfigError::InvalidLimit(String::from(
+            "Maximal number of dummy tours must be positive."
+        ))
+    );
+}
+
+#[test]
+#[should_panic(expected = "maximalFormationCount of IC must be positive")]
+fn test_load_from_json_zero_maximal_formation_count() {
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["vehicleTypes"][0]["maximalFormationCount"] = serde_json::Value::from(0);
+
+    load_rolling_stock_problem_instance_from_json(input_data);
+}
+
+#[test]
+fn test_time_horizon() {
+    // ARRANGE
+    let load = |path: &str| load_rolling_stock_problem_instance_from_json(load_test_input(path));
+
+    // ACT
+    let network = load("resources/small_test_input.json");
+    let network_without_maintenance = load("resources/small_test_input_without_maintenance.json");
+
+    // ASSERT
+    assert_eq!(
+        network.time_horizon(),
+        (
+            DateTime::new("2023-07-24T06:00:00"),
+            DateTime::new("2023-07-24T18:00:00")
+        )
+    );
+    assert_eq!(
+        network_without_maintenance.time_horizon(),
+        (
+            DateTime::new("2023-07-24T12:00:00"),
+            DateTime::new("2023-07-24T16:00:00")
+        )
+    );
+}
+
+#[test]
+fn test_unreachable_locations_warning() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    let connected_network = load_rolling_stock_problem_instance_from_json(input_data.clone());
+    // OL has no dead head trips to or from any other location
+    input_data["locations"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({"id": "OL"}));
+    input_data["routes"].as_array_mut().unwrap().push(
+        serde_json::json!({"id": "route_ol", "vehicleType": "IR", "segments": [
+            {"id": "route_ol_seg_0", "order": 0, "origin": "ZH", "destination": "OL",
+            "distance": 600, "duration": 1800}]}),
+    );
+    input_data["departures"].as_array_mut().unwrap().push(
+        serde_json::json!({"id": "trip_ol", "route": "route_ol", "segments": [
+            {"id": "trip_ol_seg_0", "routeSegment": "route_ol_seg_0",
+            "departure": "2023-07-24T13:00:00", "passengers": 50, "seated": 40}]}),
+    );
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ASSERT
+    assert!(connected_network.unreachable_locations_warning().is_none());
+    let unreachable = network.locations_unreachable_from_depots();
+    assert_eq!(unreachable.len(), 1);
+    assert_eq!(network.locations().get_id(unreachable[0]).unwrap(), "OL");
+    let affected_trips = network.service_trips_at_unreachable_locations();
+    assert_eq!(affected_trips.len(), 1);
+    assert_eq!(network.node(affected_trips[0]).id(), "trip_ol_seg_0");
+    let warning = network.unreachable_locations_warning().unwrap();
+    assert!(warning.contains("Locations OL are not connected"));
+    assert!(warning.contains("Affected service trips: trip_ol_seg_0"));
+}
+
+#[test]
+fn test_load_maintenance_slots() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    // OL has no dead head trips to or from any other location
+    input_data["locations"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({"id": "OL"}));
+    input_data["maintenanceSlots"] = serde_json::json!([
+        {"id": "maintenance_zh", "location": "ZH", "start": "2023-07-24T06:00:00",
+        "end": "2023-07-24T12:00:00", "trackCount": 2},
+        {"id": "maintenance_ol", "location": "OL", "start": "2023-07-24T14:00:00",
+        "end": "2023-07-24T18:00:00", "trackCount": 1}]);
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ASSERT
+    let maintenance_slots: Vec<NodeIdx> = network.maintenance_nodes().collect();
+    assert_eq!(maintenance_slots.len(), 2);
+    let maintenance_slot = |id: &str| {
+        *maintenance_slots
+            .iter()
+            .find(|&&node| network.node(node).id() == id)
+            .unwrap()
+    };
+    let (maintenance_zh, maintenance_ol) = (
+        maintenance_slot("maintenance_zh"),
+        maintenance_slot("maintenance_ol"),
+    );
+    assert_eq!(network.track_count_of_maintenance_slot(maintenance_zh), 2);
+    assert_eq!(network.track_count_of_maintenance_slot(maintenance_ol), 1);
+    assert_eq!(
+        network.maintenance_slots_at_unreachable_locations(),
+        vec![maintenance_ol]
+    );
+    let warning = network.unreachable_locations_warning().unwrap();
+    assert!(warning.contains("Affected maintenance slots: maintenance_ol"));
+}
+
+#[test]
+fn test_load_from_json_iso_durations_and_km_distances() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+
+    let to_iso = |value: &mut serde_json::Value| {
+        let seconds = value.as_u64().unwrap();
+        *value = serde_json::Value::from(format!(
+            "P{}DT{}H{}M{}S",
+            seconds / 86400,
+            seconds % 86400 / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        ));
+    };
+    let to_km = |value: &mut serde_json::Value| {
+        let meters = value.as_u64().unwrap();
+        *value = serde_json::Value::from(format!("{}km", meters as f64 / 1000.0));
+    };
+
+    for route in input_data["routes"].as_array_mut().unwrap() {
+        for segment in route["segments"].as_array_mut().unwrap() {
+            to_iso(&mut segment["duration"]);
+            to_km(&mut segment["distance"]);
+        }
+    }
+    let dead_head_trips = &mut input_data["deadHeadTrips"];
+    for row in dead_head_trips["durations"].as_array_mut().unwrap() {
+        row.as_array_mut().unwrap().iter_mut().for_each(to_iso);
+    }
+    for row in dead_head_trips["distances"].as_array_mut().unwrap() {
+        row.as_array_mut().unwrap().iter_mut().for_each(to_km);
+    }
+    let parameters = &mut input_data["parameters"];
+    to_iso(&mut parameters["dayLimitThreshold"]);
+    to_iso(&mut parameters["shunting"]["minimalDuration"]);
+    to_iso(&mut parameters["shunting"]["deadHeadTripDuration"]);
+    to_km(&mut parameters["maintenance"]["maximalDistance"]);
+
+    // ACT & ASSERT
+    assert_small_test_input(input_data);
+}
+
+#[test]
+fn test_load_km_distances_with_rounding_policy() {
+    // ARRANGE
+    let input_data = load_test_input("resources/small_test_input.json");
+    let maximal_distance = |rounding: Option<&str>, km: &str| {
+        let mut input_data = input_data.clone();
+        input_data["parameters"]["maintenance"]["maximalDistance"] = serde_json::Value::from(km);
+        if let Some(rounding) = rounding {
+            input_data["parameters"]["rounding"] = serde_json::Value::from(rounding);
+        }
+        load_rolling_stock_problem_instance_from_json(input_data)
+            .config()
+            .maintenance
+            .maximal_distance
+    };
+
+    // ACT & ASSERT
+    assert_eq!(
+        maximal_distance(Some("truncate"), "1.0006km"),
+        Distance::from_meter(1000)
+    );
+    assert_eq!(
+        maximal_distance(None, "1.0006km"),
+        Distance::from_meter(1001)
+    );
+    assert_eq!(
+        maximal_distance(None, "1.0004km"),
+        Distance::from_meter(1000)
+    );
+    assert_eq!(
+        maximal_distance(Some("ceil"), "1.0004km"),
+        Distance::from_meter(1001)
+    );
+}
+
+#[test]
+fn test_load_from_json_invalid_iso_duration() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["parameters"]["shunting"]["minimalDuration"] = serde_json::Value::from("PT2X");
+
+    // ACT
+    let result = serde_json::from_value::<super::JsonInput>(input_data);
+
+    // ASSERT
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_depot_capacity_scale() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["depots"][0] = serde_json::json!({"id": "depot_ZH", "location": "ZH",
+        "capacity": 10, "allowedTypes": [{"vehicleType": "IC", "capacity": 4},
+        {"vehicleType": "IR"}]});
+    input_data["parameters"]["depotCapacityScale"] = serde_json::Value::from(1.5);
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ASSERT
+    let depot = network
+        .depots_iter()
+        .map(|depot_idx| network.get_depot(depot_idx))
+        .find(|depot| depot.id() == "depot_ZH")
+        .unwrap();
+    let vehicle_type = |id: &str| {
+        network
+            .vehicle_types()
+            .iter_with_details()
+            .find(|vehicle_type| vehicle_type.id() == id)
+            .unwrap()
+            .idx()
+    };
+    assert_eq!(depot.total_capacity(), 15);
+    assert_eq!(depot.capacity_for(vehicle_type("IC")), 6);
+    // no vehicle type specific limit, so only the scaled total capacity applies
+    assert_eq!(depot.capacity_for(vehicle_type("IR")), 15);
+}
+
+#[test]
+fn test_depot_infinite_capacity() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["depots"][0] = serde_json::json!({"id": "depot_ZH", "location": "ZH",
+        "capacity": "Infinity", "allowedTypes": [{"vehicleType": "IC", "capacity": "Inf"},
+        {"vehicleType": "IR", "capacity": 3}]});
+    let mut invalid_input_data = input_data.clone();
+    invalid_input_data["depots"][0]["capacity"] = serde_json::Value::from("lots");
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let invalid_result = serde_json::from_value::<super::JsonInput>(invalid_input_data);
+
+    // ASSERT
+    let depot = network
+        .depots_iter()
+        .map(|depot_idx| network.get_depot(depot_idx))
+        .find(|depot| depot.id() == "depot_ZH")
+        .unwrap();
+    let vehicle_type = |id: &str| {
+        network
+            .vehicle_types()
+            .iter_with_details()
+            .find(|vehicle_type| vehicle_type.id() == id)
+            .unwrap()
+            .idx()
+    };
+    // an unlimited depot can hold one vehicle per service trip
+    let number_of_service_trips = network.number_of_service_nodes() as VehicleCount;
+    assert_eq!(depot.total_capacity(), number_of_service_trips);
+    assert!(depot.is_unlimited_for(vehicle_type("IC")));
+    assert_eq!(
+        depot.capacity_for(vehicle_type("IC")),
+        number_of_service_trips
+    );
+    assert_eq!(depot.capacity_for(vehicle_type("IR")), 3);
+    assert!(invalid_result.is_err());
+}
+
+#[test]
+fn test_depot_capacity_census() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["depots"][0] = serde_json::json!({"id": "depot_ZH", "location": "ZH",
+        "capacity": 10, "allowedTypes": [{"vehicleType": "IC", "capacity": 4},
+        {"vehicleType": "IR", "capacity": 3}]});
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ASSERT
+    let vehicle_type = |id: &str| {
+        network
+            .vehicle_types()
+            .iter_with_details()
+            .find(|vehicle_type| vehicle_type.id() == id)
+            .unwrap()
+            .idx()
+    };
+    // depot_ZH (capacity 10, IC: 4, IR: 3) and depot_BN (capacity 500, IC: unlimited)
+    assert_eq!(network.total_depot_capacity(), 510);
+    let capacity_by_type = network.capacity_by_type();
+    assert_eq!(capacity_by_type.len(), 2);
+    assert_eq!(capacity_by_type[&vehicle_type("IC")], None);
+    assert_eq!(capacity_by_type[&vehicle_type("IR")], Some(3));
+}
+
+#[test]
+fn test_load_from_json_frequency() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["departures"].as_array_mut().unwrap().push(
+        serde_json::json!({"id": "trip_f", "route": "route_1", "segments": [
+            {"id": "trip_f_seg_0", "routeSegment": "route_1_seg_0",
+            "departure": "2023-07-24T13:00:00", "passengers": 30, "seated": 30}],
+            "frequency": {"headway": "PT30M", "count": 3}}),
+    );
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ASSERT
+    assert_eq!(network.number_of_service_nodes(), 7);
+    let expanded_trips: Vec<_> = network
+        .all_service_nodes()
+        .filter(|&node| network.node(node).id().starts_with("trip_f"))
+        .map(|node| network.node(node))
+        .collect();
+    assert_eq!(expanded_trips.len(), 3);
+    for (k, (id, departure, arrival)) in [
+        (
+            "trip_f_seg_0_0",
+            "2023-07-24T13:00:00",
+            "2023-07-24T15:00:00",
+        ),
+        (
+            "trip_f_seg_0_1",
+            "2023-07-24T13:30:00",
+            "2023-07-24T15:30:00",
+        ),
+        (
+            "trip_f_seg_0_2",
+            "2023-07-24T14:00:00",
+            "2023-07-24T16:00:00",
+        ),
+    ]
+    .iter()
+    .enumerate()
+    {
+        assert_eq!(expanded_trips[k].id(), *id);
+        assert_eq!(expanded_trips[k].start_time(), DateTime::new(departure));
+        assert_eq!(expanded_trips[k].end_time(), DateTime::new(arrival));
+    }
+}
+
+#[test]
+fn test_min_turnaround() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    // trip_2 reverses at LU 10 minutes after trip_1a arrived there (shunting needs 2 minutes)
+    input_data["routes"].as_array_mut().unwrap().push(
+        serde_json::json!({"id": "route_2", "vehicleType": "IC", "segments": [
+            {"id": "route_2_seg_0", "order": 0, "origin": "LU", "destination": "BN",
+            "distance": 2000, "duration": 3600}]}),
+    );
+    input_data["departures"].as_array_mut().unwrap().push(
+        serde_json::json!({"id": "trip_2", "route": "route_2", "segments": [
+            {"id": "trip_2_seg_0", "routeSegment": "route_2_seg_0",
+            "departure": "2023-07-24T14:10:00", "passengers": 30, "seated": 30}]}),
+    );
+    let mut input_data_with_turnaround = input_data.clone();
+    input_data_with_turnaround["locations"][2]["minTurnaround"] = serde_json::json!("PT15M");
+    let find_trip = |network: &Network, id: &str| {
+        network
+            .all_service_nodes()
+            .find(|&node| network.node(node).id() == id)
+            .unwrap()
+    };
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let network_with_turnaround =
+        load_rolling_stock_problem_instance_from_json(input_data_with_turnaround);
+
+    // ASSERT
+    let (trip_1a, trip_2) = (
+        find_trip(&network, "trip_1a_seg_0"),
+        find_trip(&network, "trip_2_seg_0"),
+    );
+    assert!(network.can_reach(trip_1a, trip_2));
+    assert_eq!(
+        network.minimal_duration_between_nodes(trip_1a, trip_2),
+        Duration::from_seconds(120)
+    );
+
+    let (trip_1a, trip_2) = (
+        find_trip(&network_with_turnaround, "trip_1a_seg_0"),
+        find_trip(&network_with_turnaround, "trip_2_seg_0"),
+    );
+    assert_eq!(
+        network_with_turnaround
+            .locations()
+            .min_turnaround(Location::Station(LocationIdx::from(2))),
+        Duration::from_seconds(900)
+    );
+    assert!(!network_with_turnaround.can_reach(trip_1a, trip_2));
+    assert_eq!(
+        network_with_turnaround.minimal_duration_between_nodes(trip_1a, trip_2),
+        Duration::from_seconds(1020)
+    );
+}
+
+#[test]
+fn test_dead_head_time_bands() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    // trip_1a arrives at LU at 14:00, trip_3 departs at BN at 16:00 (dead head LU -> BN: 4000s)
+    input_data["departures"].as_array_mut().unwrap().push(
+        serde_json::json!({"id": "trip_3", "route": "route_1", "segments": [
+            {"id": "trip_3_seg_0", "routeSegment": "route_1_seg_0",
+            "departure": "2023-07-24T16:00:00", "passengers": 30, "seated": 30}]}),
+    );
+    let time_bands = |start: &str, end: &str| {
+        serde_json::json!([{"origin": "LU", "destination": "BN", "start": start, "end": end,
+            "duration": "PT2H30M"}])
+    };
+    let mut input_data_peak = input_data.clone();
+    input_data_peak["deadHeadTrips"]["timeBands"] =
+        time_bands("2023-07-24T13:00:00", "2023-07-24T15:00:00");
+    let mut input_data_off_peak = input_data;
+    input_data_off_peak["deadHeadTrips"]["timeBands"] =
+        time_bands("2023-07-24T17:00:00", "2023-07-24T19:00:00");
+    let find_trip = |network: &Network, id: &str| {
+        network
+            .all_service_nodes()
+            .find(|&node| network.node(node).id() == id)
+            .unwrap()
+    };
+
     // ACT
+    let network_peak = load_rolling_stock_problem_instance_from_json(input_data_peak);
+    let network_off_peak = load_rolling_stock_problem_instance_from_json(input_data_off_peak);
+
+    // ASSERT
+    let (lu, bn) = (
+        Location::Station(LocationIdx::from(2)),
+        Location::Station(LocationIdx::from(1)),
+    );
+    let locations = network_peak.locations();
+    assert_eq!(
+        locations.travel_time(lu, bn, DateTime::new("2023-07-24T14:00:00")),
+        Duration::from_seconds(9000)
+    );
+    assert_eq!(
+        locations.travel_time(lu, bn, DateTime::new("2023-07-24T15:00:00")),
+        Duration::from_seconds(4000)
+    );
+    assert_eq!(
+        locations.travel_time(bn, lu, DateTime::new("2023-07-24T14:00:00")),
+        Duration::from_seconds(400)
+    );
+    assert_eq!(
+        locations.base_travel_time(lu, bn),
+        Duration::from_seconds(4000)
+    );
+
+    let (trip_1a, trip_3) = (
+        find_trip(&network_peak, "trip_1a_seg_0"),
+        find_trip(&network_peak, "trip_3_seg_0"),
+    );
+    assert!(!network_peak.can_reach(trip_1a, trip_3));
+    assert_eq!(
+        network_peak.dead_head_time_between(trip_1a, trip_3),
+        Duration::from_seconds(9000)
+    );
+
+    let (trip_1a, trip_3) = (
+        find_trip(&network_off_peak, "trip_1a_seg_0"),
+        find_trip(&network_off_peak, "trip_3_seg_0"),
+    );
+    assert!(network_off_peak.can_reach(trip_1a, trip_3));
+    assert_eq!(
+        network_off_peak.dead_head_time_between(trip_1a, trip_3),
+        Duration::from_seconds(4000)
+    );
+}
+
+#[test]
+fn test_reachable_end_depots() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    // GE has no dead head trips, so depot_GE cannot be reached from anywhere
+    input_data["locations"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({"id": "GE"}));
+    input_data["depots"].as_array_mut().unwrap().push(
+        serde_json::json!({"id": "depot_GE", "location": "GE", "capacity": 10,
+            "allowedTypes": [{"vehicleType": "IC"}, {"vehicleType": "IR"}]}),
+    );
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let node = |id: &str| {
+        network
+            .all_nodes()
+            .find(|&node| network.node(node).id() == id)
+            .unwrap()
+    };
+    let vehicle_type = |id: &str| {
+        network
+            .vehicle_types()
+            .iter_with_details()
+            .find(|vehicle_type| vehicle_type.id() == id)
+            .unwrap()
+            .idx()
+    };
+    let overflow_end_depot = network.overflow_depot_idxs().2;
+
+    // ACT
+    let end_depots_ic = network.reachable_end_depots(node("trip_1a_seg_0"), vehicle_type("IC"));
+    let end_depots_ir = network.reachable_end_depots(node("trip_0_seg_0"), vehicle_type("IR"));
+
+    // ASSERT
+    // both trips end in LU, which is closer to ZH (5000m) than to BN (7000m)
+    assert_eq!(
+        end_depots_ic,
+        vec![node("e_depot_ZH"), node("e_depot_BN"), overflow_end_depot]
+    );
+    // depot_BN does not allow IR
+    assert_eq!(end_depots_ir, vec![node("e_depot_ZH"), overflow_end_depot]);
+}
+
+#[test]
+fn test_with_additional_service_trips() {
+    // ARRANGE
+    let network = load_rolling_stock_problem_instance_from_json(load_test_input(
+        "resources/small_test_input.json",
+    ));
+    let bn = network.locations().get(LocationIdx::from(1)).unwrap();
+    let lu = network.locations().get(LocationIdx::from(2)).unwrap();
+    let new_trip = Node::create_service_trip(
+        String::from("trip_new"),
+        VehicleTypeIdx::from(0),
+        lu,
+        bn,
+        DateTime::new("2023-07-24T16:30:00"),
+        DateTime::new("2023-07-24T17:00:00"),
+        Distance::from_meter(2000),
+        50,
+        40,
+        None,
+        None,
+        None,
+    );
+
+    // ACT
+    let extended_network = network.with_additional_service_trips(vec![new_trip]);
+
+    // ASSERT
+    for node in network.all_nodes() {
+        assert_eq!(extended_network.node(node), network.node(node));
+    }
+    assert_eq!(
+        extended_network.number_of_service_nodes(),
+        network.number_of_service_nodes() + 1
+    );
+    let new_node = NodeIdx::service_from(12);
+    assert_eq!(extended_network.node(new_node).id(), "trip_new");
+    assert_eq!(
+        extended_network
+            .service_nodes(VehicleTypeIdx::from(0))
+            .last(),
+        Some(new_node)
+    );
+    let trip_1a = NodeIdx::service_from(6);
+    let trip_1b = NodeIdx::service_from(7);
+    assert!(extended_network.can_reach(trip_1b, new_node));
+    assert!(!extended_network.can_reach(new_node, trip_1a));
+    assert_eq!(extended_network.time_horizon(), network.time_horizon());
+}
+
+#[test]
+fn test_with_scaled_demand() {
+    // ARRANGE
+    let network = load_rolling_stock_problem_instance_from_json(load_test_input(
+        "resources/small_test_input.json",
+    ));
+    let trip_0_seg_0 = network
+        .all_service_nodes()
+        .find(|node| network.node(*node).id() == "trip_0_seg_0")
+        .unwrap();
+    let vehicle_types = network.vehicle_types();
+
+    // ACT
+    let scaled_network = network.with_scaled_demand(2.0);
+    let slightly_scaled_network = network.with_scaled_demand(1.01);
+    let inexactly_scaled_network = network.with_scaled_demand(1.1);
+
+    // ASSERT
+    for node in network.all_service_nodes() {
+        assert_eq!(
+            scaled_network.passengers_of(node),
+            2 * network.passengers_of(node)
+        );
+        assert_eq!(
+            scaled_network.seated_passengers_of(node),
+            2 * network.seated_passengers_of(node)
+        );
+    }
+    assert_eq!(network.passengers_of(trip_0_seg_0), 50); // original is unchanged
+    assert_eq!(slightly_scaled_network.passengers_of(trip_0_seg_0), 51); // rounded up
+    assert_eq!(inexactly_scaled_network.passengers_of(trip_0_seg_0), 55); // 50 * 1.1 is not 56
+    assert_eq!(
+        vehicle_types.best_for(network.seated_passengers_of(trip_0_seg_0)),
+        VehicleTypeIdx::from(0) // IC with 50 seats covers 40 seated passengers
+    );
+    assert_eq!(
+        vehicle_types.best_for(scaled_network.seated_passengers_of(trip_0_seg_0)),
+        VehicleTypeIdx::from(1) // no type covers 80 seated passengers
+    );
+    assert_eq!(scaled_network.size(), network.size());
+    assert_eq!(scaled_network.time_horizon(), network.time_horizon());
+}
+
+fn load_test_input(path: &str) -> serde_json::Value {
     let mut file = File::open(path).unwrap();
     let mut input_data = String::new();
     file.read_to_string(&mut input_data).unwrap();
-    let input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    serde_json::from_str(&input_data).unwrap()
+}
+
+fn test_load_from_json(path: &str) {
+    assert_small_test_input(load_test_input(path));
+}
 
+fn assert_small_test_input(input_data: serde_json::Value) {
+    // ACT
     let network = load_rolling_stock_problem_instance_from_json(input_data);
     let vehicle_types = network.vehicle_types();
     let config = network.config();
@@ -56,11 +727,20 @@ fn test_load_from_json(path: &str) {
 
     assert_eq!(
         *vehicle_types.get(vt0).unwrap(),
-        VehicleType::new(vt0, String::from("IC"), 100, 50, Some(4),)
+        VehicleType::new(vt0, String::from("IC"), 100, 50, 0, Some(4), None, None)
     );
     assert_eq!(
         *vehicle_types.get(vt1).unwrap(),
-        VehicleType::new(vt1, String::from("IR"), 80, 40, None,)
+        VehicleType::new(
+            vt1,
+            String::from("IR"),
+            80,
+            40,
+            0,
+            None,
+            Some(vec![DepotIdx::from(0)]),
+            None
+        )
     );
 
     assert_eq!(loc0, Location::of(LocationIdx::from(0)));
@@ -126,6 +806,8 @@ fn test_load_from_json(path: &str) {
                 Distance::from_meter(2000),
                 80,
                 80,
+                None,
+                None,
                 None
             )
         )
@@ -145,6 +827,8 @@ fn test_load_from_json(path: &str) {
                 Distance::from_meter(2000),
                 1,
                 0,
+                None,
+                None,
                 None
             )
         )
@@ -164,7 +848,9 @@ fn test_load_from_json(path: &str) {
                 Distance::from_meter(600),
                 50,
                 40,
-                Some(1)
+                Some(1),
+                None,
+                None
             )
         )
     );
@@ -234,7 +920,7 @@ fn test_load_from_json(path: &str) {
 
 fn assert_travel_time(from: Location, to: Location, expected: u64, locations: &Locations) {
     assert_eq!(
-        locations.travel_time(from, to),
+        locations.base_travel_time(from, to),
         Duration::from_seconds(expected),
         "Travel time from {} to {} should be {}",
         from,
@@ -253,3 +939,141 @@ fn assert_travel_distance(from: Location, to: Location, expected: u64, locations
         expected
     );
 }
+
+#[test]
+fn test_parallel_loading_is_deterministic() {
+    // ARRANGE
+    let input_data = load_test_input("resources/small_test_input.json");
+    let sequential_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    // ACT
+    let sequential_network = sequential_pool
+        .install(|| load_rolling_stock_problem_instance_from_json(input_data.clone()));
+    let parallel_network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ASSERT
+    assert_eq!(parallel_network.size(), sequential_network.size());
+    for node in sequential_network.all_nodes() {
+        assert_eq!(parallel_network.node(node), sequential_network.node(node));
+    }
+    for vehicle_type in sequential_network.vehicle_types().iter() {
+        assert_eq!(
+            parallel_network
+                .service_nodes(vehicle_type)
+                .collect::<Vec<_>>(),
+            sequential_network
+                .service_nodes(vehicle_type)
+                .collect::<Vec<_>>()
+        );
+    }
+    let locations = sequential_network.locations();
+    for a in locations.iter() {
+        for b in locations.iter() {
+            assert_eq!(
+                parallel_network.locations().distance(a, b),
+                locations.distance(a, b)
+            );
+            assert_eq!(
+                parallel_network.locations().base_travel_time(a, b),
+                locations.base_travel_time(a, b)
+            );
+        }
+    }
+    assert_eq!(
+        parallel_network.time_horizon(),
+        sequential_network.time_horizon()
+    );
+    assert_eq!(parallel_network.to_string(), sequential_network.to_string());
+}
+
+#[test]
+fn test_blockable_pairs() {
+    // ARRANGE
+    let network = load_rolling_stock_problem_instance_from_json(load_test_input(
+        "resources/small_test_input.json",
+    ));
+    let node = |id: &str| {
+        network
+            .all_nodes()
+            .find(|&node| network.node(node).id() == id)
+            .unwrap()
+    };
+    let threshold = Distance::from_meter(5000);
+
+    // ACT
+    let pairs: Vec<(NodeIdx, NodeIdx)> = network.blockable_pairs(threshold).collect();
+
+    // ASSERT
+    let mut expected: Vec<(NodeIdx, NodeIdx)> = Vec::new();
+    for trip in network.all_service_nodes() {
+        for successor in network.all_service_nodes() {
+            if network.vehicle_type_for(trip) == network.vehicle_type_for(successor)
+                && network.can_reach(trip, successor)
+                && network.dead_head_distance_between(trip, successor) < threshold
+            {
+                expected.push((trip, successor));
+            }
+        }
+    }
+    let mut sorted_pairs = pairs.clone();
+    sorted_pairs.sort();
+    expected.sort();
+    assert!(!pairs.is_empty());
+    assert_eq!(sorted_pairs, expected);
+    let distances: Vec<Distance> = pairs
+        .iter()
+        .map(|&(trip, successor)| network.dead_head_distance_between(trip, successor))
+        .collect();
+    assert!(distances.windows(2).all(|window| window[0] <= window[1]));
+    // LU -> BN is 7000m
+    let (trip_1a, trip_1b) = (node("trip_1a_seg_0"), node("trip_1b_seg_0"));
+    assert!(network.can_reach(trip_1a, trip_1b));
+    assert!(!pairs.contains(&(trip_1a, trip_1b)));
+}
+
+#[test]
+fn test_station_sides() {
+    // ARRANGE
+    let mut input_data = load_test_input("resources/small_test_input.json");
+    input_data["routes"][0]["segments"][0]["destinationSide"] = serde_json::json!("back");
+    input_data["deadHeadTrips"]["originSide"] = serde_json::json!("front");
+
+    // ACT
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ASSERT
+    let service_trip = |id: &str| {
+        network
+            .all_service_nodes()
+            .find(|&node| network.node(node).id() == id)
+            .unwrap()
+    };
+    let trip_0_seg_0 = network.node(service_trip("trip_0_seg_0"));
+    assert_eq!(
+        trip_0_seg_0.as_service_trip().station_sides(),
+        (StationSide::Back, StationSide::Back)
+    );
+    // sides that are not given keep their default
+    assert_eq!(
+        network
+            .node(service_trip("trip_0_seg_1"))
+            .as_service_trip()
+            .station_sides(),
+        (StationSide::Back, StationSide::Front)
+    );
+    assert_eq!(
+        network
+            .locations()
+            .station_sides(trip_0_seg_0.start_location(), trip_0_seg_0.end_location()),
+        Some((StationSide::Front, StationSide::Front))
+    );
+    assert_eq!(
+        network
+            .locations()
+            .station_sides(trip_0_seg_0.start_location(), trip_0_seg_0.start_location()),
+        None
+    );
+}
@@ -0,0 +1,210 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Serde helpers that accept durations either as integer seconds or as ISO 8601 strings
+//! (e.g. "PT1H30M") and distances either as integer meters or as kilometer strings
+//! (e.g. "12.5" or "12.5km"). Internally, seconds and meters are used. Kilometers are converted
+//! to meters with the rounding policy of the parameters (see JsonDistance).
+//! Capacities are either integers or "Infinity" (also "Inf"), which is parsed as None.
+
+#[cfg(test)]
+mod tests;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::Integer;
+use crate::base_types::{Distance, RoundingPolicy};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SecondsOrIso {
+    Seconds(Integer),
+    Iso(String),
+}
+
+impl SecondsOrIso {
+    fn into_seconds(self) -> Result<Integer, String> {
+        match self {
+            SecondsOrIso::Seconds(seconds) => Ok(seconds),
+            SecondsOrIso::Iso(iso) => parse_iso_duration(&iso),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum MetersOrKm {
+    Meters(Integer),
+    Km(String),
+}
+
+/// A distance of the input, either in meters or in (possibly fractional) kilometers. The
+/// kilometers are only converted to meters when the rounding policy of the parameters is known.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(try_from = "MetersOrKm", into = "MetersOrKm")]
+pub(super) enum JsonDistance {
+    Meters(Integer),
+    Km(f64),
+}
+
+impl JsonDistance {
+    pub(super) fn to_distance(self, rounding: RoundingPolicy) -> Distance {
+        match self {
+            JsonDistance::Meters(meters) => Distance::from_meter(meters),
+            JsonDistance::Km(km) => Distance::from_km_rounded(km, rounding),
+        }
+    }
+}
+
+impl TryFrom<MetersOrKm> for JsonDistance {
+    type Error = String;
+
+    fn try_from(distance: MetersOrKm) -> Result<JsonDistance, String> {
+        match distance {
+            MetersOrKm::Meters(meters) => Ok(JsonDistance::Meters(meters)),
+            MetersOrKm::Km(km) => parse_km(&km).map(JsonDistance::Km),
+        }
+    }
+}
+
+impl From<JsonDistance> for MetersOrKm {
+    fn from(distance: JsonDistance) -> MetersOrKm {
+        match distance {
+            JsonDistance::Meters(meters) => MetersOrKm::Meters(meters),
+            JsonDistance::Km(km) => MetersOrKm::Km(format!("{}km", km)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CountOrInfinity {
+    Count(Integer),
+    Infinity(String),
+}
+
+impl CountOrInfinity {
+    fn into_count(self) -> Result<Option<Integer>, String> {
+        match self {
+            CountOrInfinity::Count(count) => Ok(Some(count)),
+            CountOrInfinity::Infinity(infinity) if infinity == "Infinity" || infinity == "Inf" => {
+                Ok(None)
+            }
+            CountOrInfinity::Infinity(other) => Err(format!(
+                "Invalid capacity '{}'. Expected an integer or \"Infinity\".",
+                other
+            )),
+        }
+    }
+}
+
+/// Parses ISO 8601 durations of the form "P[nD][T[nH][nM][nS]]" into seconds.
+fn parse_iso_duration(iso: &str) -> Result<Integer, String> {
+    let error = || format!("Invalid ISO 8601 duration '{}'.", iso);
+    let rest = iso.strip_prefix('P').ok_or_else(error)?;
+    let (date_part, time_part) = match rest.split_once('T') {
+        Some((date_part, time_part)) if !time_part.is_empty() => (date_part, Some(time_part)),
+        Some(_) => return Err(error()),
+        None => (rest, None),
+    };
+    if date_part.is_empty() && time_part.is_none() {
+        return Err(error());
+    }
+
+    let mut seconds: Integer = 0;
+    let mut add_components = |part: &str, units: &[(char, Integer)]| -> Result<(), String> {
+        let mut remaining_units = units.iter();
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            // units must appear in order and each at most once
+            let &(_, factor) = remaining_units
+                .find(|(unit, _)| *unit == c)
+                .ok_or_else(error)?;
+            let value: Integer = number.parse().map_err(|_| error())?;
+            seconds = value
+                .checked_mul(factor)
+                .and_then(|s| seconds.checked_add(s))
+                .ok_or_else(error)?;
+            number.clear();
+        }
+        if number.is_empty() {
+            Ok(())
+        } else {
+            Err(error())
+        }
+    };
+    add_components(date_part, &[('D', 86400)])?;
+    if let Some(time_part) = time_part {
+        add_components(time_part, &[('H', 3600), ('M', 60), ('S', 1)])?;
+    }
+    Ok(seconds)
+}
+
+/// Parses a (possibly fractional) kilometer string with optional "km" suffix.
+fn parse_km(km: &str) -> Result<f64, String> {
+    let number = km.trim().strip_suffix("km").unwrap_or(km.trim()).trim();
+    match number.parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+        _ => Err(format!("Invalid distance in km '{}'.", km)),
+    }
+}
+
+pub(super) fn seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Integer, D::Error> {
+    SecondsOrIso::deserialize(deserializer)?
+        .into_seconds()
+        .map_err(D::Error::custom)
+}
+
+pub(super) fn optional_seconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Integer>, D::Error> {
+    Option::<SecondsOrIso>::deserialize(deserializer)?
+        .map(SecondsOrIso::into_seconds)
+        .transpose()
+        .map_err(D::Error::custom)
+}
+
+pub(super) fn seconds_matrix<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Vec<Integer>>, D::Error> {
+    Vec::<Vec<SecondsOrIso>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|row| row.into_iter().map(SecondsOrIso::into_seconds).collect())
+        .collect::<Result<_, _>>()
+        .map_err(D::Error::custom)
+}
+
+/// Capacity that is either an integer or "Infinity" (None).
+pub(super) fn capacity<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Integer>, D::Error> {
+    CountOrInfinity::deserialize(deserializer)?
+        .into_count()
+        .map_err(D::Error::custom)
+}
+
+/// Optional capacity, where a missing value and "Infinity" are both None (no limit).
+pub(super) fn optional_capacity<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Integer>, D::Error> {
+    Ok(Option::<CountOrInfinity>::deserialize(deserializer)?
+        .map(CountOrInfinity::into_count)
+        .transpose()
+        .map_err(D::Error::custom)?
+        .flatten())
+}
@@ -0,0 +1,77 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::base_types::{Distance, RoundingPolicy};
+
+use super::{parse_iso_duration, parse_km, JsonDistance};
+
+#[test]
+fn parse_iso_duration_test() {
+    // ACT & ASSERT
+    assert_eq!(parse_iso_duration("PT1H30M"), Ok(5400));
+    assert_eq!(parse_iso_duration("P1DT2H"), Ok(93600));
+    assert_eq!(parse_iso_duration("PT45S"), Ok(45));
+    assert_eq!(parse_iso_duration("P2D"), Ok(172800));
+    assert_eq!(parse_iso_duration("PT0S"), Ok(0));
+    assert!(parse_iso_duration("1H").is_err());
+    assert!(parse_iso_duration("P").is_err());
+    assert!(parse_iso_duration("PT").is_err());
+    assert!(parse_iso_duration("PT30M1H").is_err());
+    assert!(parse_iso_duration("PT1H1H").is_err());
+    assert!(parse_iso_duration("PT1.5H").is_err());
+    assert!(parse_iso_duration("PT15").is_err());
+}
+
+#[test]
+fn parse_km_test() {
+    // ACT & ASSERT
+    assert_eq!(parse_km("12.5"), Ok(12.5));
+    assert_eq!(parse_km("12.5km"), Ok(12.5));
+    assert_eq!(parse_km(" 0.6 km"), Ok(0.6));
+    assert_eq!(parse_km("3"), Ok(3.0));
+    assert!(parse_km("km").is_err());
+    assert!(parse_km("-1").is_err());
+    assert!(parse_km("12.5 miles").is_err());
+}
+
+#[test]
+fn json_distance_test() {
+    // ARRANGE
+    let parse = |value: serde_json::Value| serde_json::from_value::<JsonDistance>(value);
+
+    // ACT
+    let meters = parse(serde_json::json!(1500)).unwrap();
+    let km = parse(serde_json::json!("0.0006km")).unwrap();
+    let invalid = parse(serde_json::json!("-1km"));
+
+    // ASSERT
+    assert_eq!(meters, JsonDistance::Meters(1500));
+    assert_eq!(km, JsonDistance::Km(0.0006));
+    assert!(invalid.is_err());
+    assert_eq!(
+        meters.to_distance(RoundingPolicy::Truncate),
+        Distance::from_meter(1500)
+    );
+    assert_eq!(
+        km.to_distance(RoundingPolicy::Truncate),
+        Distance::from_meter(0)
+    );
+    assert_eq!(
+        km.to_distance(RoundingPolicy::RoundHalfUp),
+        Distance::from_meter(1)
+    );
+    assert_eq!(
+        serde_json::to_value(km).unwrap(),
+        serde_json::json!("0.0006km")
+    );
+}
@@ -11,44 +11,113 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod distance_provider;
+#[cfg(test)]
+mod tests;
+
+pub use distance_provider::{CoordinateDistances, Coordinates, DistanceProvider, MatrixDistances};
+
 use std::collections::HashMap;
 
-use rapid_time::Duration;
+use rapid_time::{DateTime, Duration};
 
-use crate::base_types::VehicleCount;
 use crate::base_types::{Distance, Location, LocationIdx};
+use crate::base_types::{Meter, StationSide, VehicleCount};
+
+/// Distances may exceed the detour via another location by this much without being reported as a
+/// triangle-inequality violation (e.g., rounding of kilometers to meters).
+const TRIANGLE_INEQUALITY_TOLERANCE: Meter = 1;
 
 /// a type for storing the pair-wise distances and travel times between all stations.
-/// Distances are stored as a Vec<Vec<Distance>>-matrix.
+/// Distances are provided by a DistanceProvider, i.e., either stored as a matrix or computed from
+/// the coordinates of the stations.
 /// Travel times are stored as a Vec<Vec<Duration>>-matrix.
 /// The indices in the matrix equal the indices in the station vector equal the index stored in
 /// each station.
 /// The distance can be obtained by the dist function which has two &Location as input and provides
 /// a Distance.
-/// The travel time can be obtained by the tt function which has two &Location as input and
-/// provides a Duration.
+/// The travel time can be obtained by the travel_time function which has two &Location and the
+/// departure time as input and provides a Duration. A dead head trip can override its travel time
+/// within time bands (e.g., congestion during peak hours).
 ///
-/// Distances and travel times should satisfy the triangle-inequality. This is not asserted.
+/// Distances and travel times should satisfy the triangle-inequality. This is not asserted, but
+/// violations of the distances can be listed by triangle_inequality_violations (e.g., by the
+/// dry run of single_run).
+///
+/// If there is no dead head trip between two different stations (e.g., a station is missing in
+/// the dead head trip matrix), the distance and travel time are Infinity.
 ///
 /// A DeadHeadMetrics instance can only be created together with the Vec<Distance> of wrapped
 /// stations. Use loactions::create_locations for that. Hence, the indices should always be consistent.
 pub struct Locations {
     stations: HashMap<LocationIdx, (String, Option<VehicleCount>)>, // values: (id, daylimit)
     dead_head_trips: HashMap<LocationIdx, HashMap<LocationIdx, DeadHeadTrip>>,
+    distances: Box<dyn DistanceProvider>, // distances of the dead head trips
+    min_turnarounds: HashMap<LocationIdx, Duration>, // locations without entry need no turnaround
 }
 
 pub struct DeadHeadTrip {
-    distance: Distance,
     travel_time: Duration,
+    time_bands: Vec<TimeBand>, // overrides of the travel time, the first matching band applies
+    station_sides: (StationSide, StationSide), // (origin side, destination side)
 }
 
 impl DeadHeadTrip {
-    pub fn new(distance: Distance, travel_time: Duration) -> DeadHeadTrip {
+    pub fn new(travel_time: Duration) -> DeadHeadTrip {
+        DeadHeadTrip {
+            travel_time,
+            time_bands: Vec::new(),
+            station_sides: (
+                StationSide::DEFAULT_ORIGIN,
+                StationSide::DEFAULT_DESTINATION,
+            ),
+        }
+    }
+
+    pub fn with_time_bands(self, time_bands: Vec<TimeBand>) -> DeadHeadTrip {
+        DeadHeadTrip { time_bands, ..self }
+    }
+
+    pub fn with_station_sides(
+        self,
+        origin_side: StationSide,
+        destination_side: StationSide,
+    ) -> DeadHeadTrip {
         DeadHeadTrip {
-            distance,
+            station_sides: (origin_side, destination_side),
+            ..self
+        }
+    }
+
+    fn travel_time_at(&self, departure: DateTime) -> Duration {
+        self.time_bands
+            .iter()
+            .find(|band| band.contains(departure))
+            .map(|band| band.travel_time)
+            .unwrap_or(self.travel_time)
+    }
+}
+
+/// Travel time of a dead head trip departing within [start, end).
+#[derive(Clone, Copy, Debug)]
+pub struct TimeBand {
+    start: DateTime,
+    end: DateTime,
+    travel_time: Duration,
+}
+
+impl TimeBand {
+    pub fn new(start: DateTime, end: DateTime, travel_time: Duration) -> TimeBand {
+        TimeBand {
+            start,
+            end,
             travel_time,
         }
     }
+
+    fn contains(&self, departure: DateTime) -> bool {
+        self.start <= departure && departure < self.end
+    }
 }
 
 /////////////////////////////////////////////////////////////////////
@@ -60,10 +129,14 @@ impl Locations {
     pub fn new(
         stations: HashMap<LocationIdx, (String, Option<VehicleCount>)>,
         dead_head_trips: HashMap<LocationIdx, HashMap<LocationIdx, DeadHeadTrip>>,
+        distances: Box<dyn DistanceProvider>,
+        min_turnarounds: HashMap<LocationIdx, Duration>,
     ) -> Locations {
         Locations {
             stations,
             dead_head_trips,
+            distances,
+            min_turnarounds,
         }
     }
 }
@@ -94,46 +167,116 @@ impl Locations {
         }
     }
 
+    /// Minimal time a vehicle needs at the given location to reverse its direction, e.g., at a
+    /// terminal station. Zero if the location does not declare a turnaround.
+    pub fn min_turnaround(&self, location: Location) -> Duration {
+        match location {
+            Location::Nowhere => Duration::ZERO,
+            Location::Station(idx) => self
+                .min_turnarounds
+                .get(&idx)
+                .copied()
+                .unwrap_or(Duration::ZERO),
+        }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = Location> + '_ {
         self.stations.keys().map(|idx| Location::Station(*idx))
     }
 
+    /// Distance of the dead head trip from a to b. Staying at the same station is always free,
+    /// even if the dead head matrix has a non-zero diagonal (e.g., the length of a loop route).
     pub fn distance(&self, a: Location, b: Location) -> Distance {
+        if Locations::is_same_station(a, b) {
+            return Distance::ZERO;
+        }
         match self.get_dead_head_trip(a, b) {
-            Some(d) => d.distance,
-            None => {
-                if a == Location::Nowhere || b == Location::Nowhere {
-                    Distance::Infinity
-                } else {
-                    Distance::ZERO
-                }
-            }
+            Some(_) => self
+                .distances
+                .distance(a.idx(), b.idx())
+                .unwrap_or(Distance::Infinity),
+            None => Distance::Infinity,
+        }
+    }
+
+    /// Travel time of the dead head trip from a to b departing at the given time, i.e., the
+    /// travel time of the time band containing the departure or the base travel time otherwise.
+    pub fn travel_time(&self, a: Location, b: Location, departure: DateTime) -> Duration {
+        if Locations::is_same_station(a, b) {
+            return Duration::ZERO;
+        }
+        match self.get_dead_head_trip(a, b) {
+            Some(d) => d.travel_time_at(departure),
+            None => self.base_travel_time(a, b),
+        }
+    }
+
+    /// Side of a at which the dead head trip from a to b departs and side of b at which it
+    /// arrives. None if there is no dead head trip from a to b (or a and b are the same station).
+    pub fn station_sides(&self, a: Location, b: Location) -> Option<(StationSide, StationSide)> {
+        if Locations::is_same_station(a, b) {
+            return None;
         }
+        self.get_dead_head_trip(a, b).map(|d| d.station_sides)
     }
 
-    pub fn travel_time(&self, a: Location, b: Location) -> Duration {
+    /// Travel time of the dead head trip from a to b ignoring the time bands. Zero for the same
+    /// station (see distance).
+    pub fn base_travel_time(&self, a: Location, b: Location) -> Duration {
+        if Locations::is_same_station(a, b) {
+            return Duration::ZERO;
+        }
         match self.get_dead_head_trip(a, b) {
             Some(d) => d.travel_time,
-            None => {
-                if a == Location::Nowhere || b == Location::Nowhere {
-                    Duration::Infinity
-                } else {
-                    Duration::ZERO
+            None => Duration::Infinity,
+        }
+    }
+
+    /// All triples (a, b, c) of location ids for which the distance from a to c is longer than
+    /// the detour from a via b to c (beyond a small tolerance). Missing dead head trips are
+    /// ignored. The triples are sorted by the location indices.
+    /// Checks all triples (cubic in the number of locations), so it is not run when loading.
+    pub fn triangle_inequality_violations(&self) -> Vec<(String, String, String)> {
+        let mut locations: Vec<Location> = self.iter().collect();
+        locations.sort_by_key(|location| location.idx());
+        let meter = |a: Location, b: Location| self.distance(a, b).in_meter().ok();
+
+        let mut violations = Vec::new();
+        for &a in locations.iter() {
+            for &c in locations.iter() {
+                let Some(direct) = meter(a, c) else {
+                    continue;
+                };
+                for &b in locations.iter() {
+                    if b == a || b == c {
+                        continue;
+                    }
+                    if let (Some(first), Some(second)) = (meter(a, b), meter(b, c)) {
+                        if direct > first + second + TRIANGLE_INEQUALITY_TOLERANCE {
+                            violations.push((
+                                self.get_id(a).unwrap(),
+                                self.get_id(b).unwrap(),
+                                self.get_id(c).unwrap(),
+                            ));
+                        }
+                    }
                 }
             }
         }
+        violations
+    }
+
+    fn is_same_station(a: Location, b: Location) -> bool {
+        a == b && a != Location::Nowhere
     }
 
     fn get_dead_head_trip(&self, a: Location, b: Location) -> Option<&DeadHeadTrip> {
         match a {
             Location::Station(station_a) => match b {
-                Location::Station(station_b) => Some(
-                    self.dead_head_trips
-                        .get(&station_a)
-                        .unwrap()
-                        .get(&station_b)
-                        .unwrap(),
-                ),
+                Location::Station(station_b) => self
+                    .dead_head_trips
+                    .get(&station_a)
+                    .and_then(|destinations| destinations.get(&station_b)),
                 _ => None,
             },
             _ => None,
@@ -0,0 +1,94 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use crate::base_types::{Distance, LocationIdx, Meter};
+
+/// Mean earth radius used by the haversine formula.
+const EARTH_RADIUS: f64 = 6_371_000.0; // in meters
+
+/// Source of the dead head distances between two different stations. The Locations only ask for
+/// distances of existing dead head trips (the same station is always at distance zero).
+pub trait DistanceProvider: Send + Sync {
+    /// Distance from a to b, or None if the provider does not know the pair.
+    fn distance(&self, a: LocationIdx, b: LocationIdx) -> Option<Distance>;
+}
+
+/// Distances stored in a (possibly sparse) matrix, e.g., the distances of the dead head trip
+/// matrix of the input.
+pub struct MatrixDistances {
+    distances: HashMap<LocationIdx, HashMap<LocationIdx, Distance>>,
+}
+
+impl MatrixDistances {
+    pub fn new(distances: HashMap<LocationIdx, HashMap<LocationIdx, Distance>>) -> MatrixDistances {
+        MatrixDistances { distances }
+    }
+}
+
+impl DistanceProvider for MatrixDistances {
+    fn distance(&self, a: LocationIdx, b: LocationIdx) -> Option<Distance> {
+        self.distances
+            .get(&a)
+            .and_then(|destinations| destinations.get(&b))
+            .copied()
+    }
+}
+
+/// Great-circle distances (haversine formula) computed from the coordinates of the stations,
+/// rounded to meters. Stations without coordinates have no distance.
+pub struct CoordinateDistances {
+    coordinates: HashMap<LocationIdx, Coordinates>,
+}
+
+/// Latitude and longitude in degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coordinates {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl Coordinates {
+    pub fn new(latitude: f64, longitude: f64) -> Coordinates {
+        Coordinates {
+            latitude,
+            longitude,
+        }
+    }
+
+    fn haversine_distance(&self, other: &Coordinates) -> Distance {
+        let latitude_a = self.latitude.to_radians();
+        let latitude_b = other.latitude.to_radians();
+        let delta_latitude = latitude_b - latitude_a;
+        let delta_longitude = (other.longitude - self.longitude).to_radians();
+        let h = (delta_latitude / 2.0).sin().powi(2)
+            + latitude_a.cos() * latitude_b.cos() * (delta_longitude / 2.0).sin().powi(2);
+        let central_angle = 2.0 * h.sqrt().min(1.0).asin();
+        Distance::from_meter((EARTH_RADIUS * central_angle).round() as Meter)
+    }
+}
+
+impl CoordinateDistances {
+    pub fn new(coordinates: HashMap<LocationIdx, Coordinates>) -> CoordinateDistances {
+        CoordinateDistances { coordinates }
+    }
+}
+
+impl DistanceProvider for CoordinateDistances {
+    fn distance(&self, a: LocationIdx, b: LocationIdx) -> Option<Distance> {
+        let coordinates_a = self.coordinates.get(&a)?;
+        let coordinates_b = self.coordinates.get(&b)?;
+        Some(coordinates_a.haversine_distance(coordinates_b))
+    }
+}
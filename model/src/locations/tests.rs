@@ -0,0 +1,125 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use rapid_time::Duration;
+
+use crate::base_types::{Distance, Idx, Location, LocationIdx};
+
+use super::{CoordinateDistances, Coordinates, DeadHeadTrip, Locations, MatrixDistances};
+
+fn locations_with_distances(distances: [[u64; 3]; 3]) -> Locations {
+    let stations = (0..3)
+        .map(|idx| (LocationIdx::from(idx), (format!("loc{}", idx), None)))
+        .collect();
+    let distances = (0..3)
+        .map(|origin| {
+            let destinations = (0..3)
+                .map(|destination| {
+                    (
+                        LocationIdx::from(destination),
+                        Distance::from_meter(distances[origin as usize][destination as usize]),
+                    )
+                })
+                .collect();
+            (LocationIdx::from(origin), destinations)
+        })
+        .collect::<HashMap<_, _>>();
+    Locations::new(
+        stations,
+        dead_head_trips_of_one_minute(),
+        Box::new(MatrixDistances::new(distances)),
+        HashMap::new(),
+    )
+}
+
+fn dead_head_trips_of_one_minute() -> HashMap<LocationIdx, HashMap<LocationIdx, DeadHeadTrip>> {
+    (0..3)
+        .map(|origin| {
+            let destinations = (0..3)
+                .map(|destination| {
+                    (
+                        LocationIdx::from(destination),
+                        DeadHeadTrip::new(Duration::from_seconds(60)),
+                    )
+                })
+                .collect();
+            (LocationIdx::from(origin), destinations)
+        })
+        .collect()
+}
+
+#[test]
+fn triangle_inequality_violations_test() {
+    // ARRANGE
+    let clean_locations =
+        locations_with_distances([[0, 1000, 2000], [1000, 0, 1000], [2000, 1000, 0]]);
+    // loc0 -> loc2 directly is longer than loc0 -> loc1 -> loc2
+    let planted_locations =
+        locations_with_distances([[0, 1000, 5000], [1000, 0, 1000], [2000, 1000, 0]]);
+
+    // ACT
+    let clean_violations = clean_locations.triangle_inequality_violations();
+    let planted_violations = planted_locations.triangle_inequality_violations();
+
+    // ASSERT
+    assert!(clean_violations.is_empty());
+    assert_eq!(
+        planted_violations,
+        vec![(
+            String::from("loc0"),
+            String::from("loc1"),
+            String::from("loc2")
+        )]
+    );
+}
+
+#[test]
+fn coordinate_distances_test() {
+    // ARRANGE
+    let stations = (0..3)
+        .map(|idx| (LocationIdx::from(idx), (format!("loc{}", idx), None)))
+        .collect();
+    let coordinates = HashMap::from([
+        (LocationIdx::from(0), Coordinates::new(47.3769, 8.5417)), // Zurich
+        (LocationIdx::from(1), Coordinates::new(46.9480, 7.4474)), // Bern
+        (LocationIdx::from(2), Coordinates::new(46.2044, 6.1432)), // Geneva
+    ]);
+    let locations = Locations::new(
+        stations,
+        dead_head_trips_of_one_minute(),
+        Box::new(CoordinateDistances::new(coordinates)),
+        HashMap::new(),
+    );
+    let meter = |a: Idx, b: Idx| {
+        locations
+            .distance(Location::Station(a.into()), Location::Station(b.into()))
+            .in_meter()
+            .unwrap() as f64
+    };
+
+    // ACT
+    let zurich_bern = meter(0, 1);
+    let bern_zurich = meter(1, 0);
+    let zurich_geneva = meter(0, 2);
+    let zurich_zurich = meter(0, 0);
+
+    // ASSERT
+    // haversine distances with an earth radius of 6371km
+    assert!((zurich_bern - 95_494.0).abs() <= 1.0);
+    assert_eq!(zurich_bern, bern_zurich);
+    assert!((zurich_geneva - 224_351.0).abs() <= 1.0);
+    assert_eq!(zurich_zurich, 0.0);
+    assert!(locations.triangle_inequality_violations().is_empty());
+}
@@ -26,7 +26,7 @@ use crate::config::Config;
 use crate::locations::Locations;
 use crate::vehicle_types::VehicleTypes;
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 
 use std::iter::Iterator;
@@ -58,6 +58,15 @@ pub struct Network {
     // redundant information
     number_of_service_nodes: usize,
     planning_days: Duration, // planning duration as a multiple of days
+    time_horizon: (DateTime, DateTime),
+
+    // hints that a service trip should ideally be followed by another service trip on the same
+    // vehicle (only feasible hints are kept)
+    preferred_successors: HashMap<NodeIdx, NodeIdx>,
+
+    // end depots that are dominated by another end depot at the same location (dominated depot ->
+    // kept depot). Vehicles never despawn at a dominated depot but at the kept one instead.
+    dominated_end_depots: HashMap<DepotIdx, DepotIdx>,
 }
 
 // methods
@@ -74,6 +83,12 @@ impl Network {
         self.nodes.get(&idx).unwrap()
     }
 
+    /// returns the id of the node as given in the input, e.g., the id of the departure segment
+    /// of a service trip.
+    pub fn original_id_of(&self, idx: NodeIdx) -> &str {
+        self.node(idx).id()
+    }
+
     /// return the number of nodes in the network.
     pub fn size(&self) -> usize {
         self.nodes.len()
@@ -84,6 +99,11 @@ impl Network {
         self.planning_days
     }
 
+    /// return the earliest start and the latest end of all service trips and maintenance slots.
+    pub fn time_horizon(&self) -> (DateTime, DateTime) {
+        self.time_horizon
+    }
+
     pub fn service_nodes(
         &self,
         vehicle_type: VehicleTypeIdx,
@@ -136,6 +156,13 @@ impl Network {
         self.depots.keys().copied()
     }
 
+    /// same as depots_iter but in a deterministic order (sorted by depot id), e.g. for reports.
+    pub fn depots_iter_sorted(&self) -> impl Iterator<Item = DepotIdx> + '_ {
+        let mut depots: Vec<_> = self.depots.iter().collect();
+        depots.sort_by(|(_, (a, _, _)), (_, (b, _, _))| a.id().cmp(b.id()));
+        depots.into_iter().map(|(&depot_idx, _)| depot_idx)
+    }
+
     /// returns the depot_ids of the overflow depot and its start and end node
     pub fn overflow_depot_idxs(&self) -> (DepotIdx, NodeIdx, NodeIdx) {
         self.overflow_depot_idxs
@@ -162,17 +189,76 @@ impl Network {
         self.depots[&depot_idx].0.total_capacity()
     }
 
+    /// Sum of the total capacities of all depots (the overflow depot is ignored).
+    pub fn total_depot_capacity(&self) -> VehicleCount {
+        self.real_depots()
+            .map(|depot| depot.total_capacity())
+            .fold(0, VehicleCount::saturating_add)
+    }
+
+    /// Sum of the capacities (see Depot::capacity_for) of all depots for each vehicle type (the
+    /// overflow depot is ignored). None means that some depot has no vehicle specific limit for
+    /// that type.
+    pub fn capacity_by_type(&self) -> HashMap<VehicleTypeIdx, Option<VehicleCount>> {
+        self.vehicle_types
+            .iter()
+            .map(|vehicle_type| {
+                let capacity = if self
+                    .real_depots()
+                    .any(|depot| depot.is_unlimited_for(vehicle_type))
+                {
+                    None
+                } else {
+                    Some(
+                        self.real_depots()
+                            .map(|depot| depot.capacity_for(vehicle_type))
+                            .fold(0, VehicleCount::saturating_add),
+                    )
+                };
+                (vehicle_type, capacity)
+            })
+            .collect()
+    }
+
+    fn real_depots(&self) -> impl Iterator<Item = &Depot> + '_ {
+        let overflow_depot = self.overflow_depot_idxs.0;
+        self.depots
+            .iter()
+            .filter(move |(&depot_idx, _)| depot_idx != overflow_depot)
+            .map(|(_, (depot, _, _))| depot)
+    }
+
     pub fn vehicle_type_for(&self, service_trip: NodeIdx) -> VehicleTypeIdx {
         self.node(service_trip).as_service_trip().vehicle_type()
     }
 
+    /// Maximal distance between two maintenance visits for the given vehicle type. Falls back to
+    /// the global maximal distance of the config if the type does not declare its own range.
+    pub fn maximal_distance_between_maintenance(&self, vehicle_type: VehicleTypeIdx) -> Distance {
+        self.vehicle_types
+            .get(vehicle_type)
+            .unwrap()
+            .maintenance_range()
+            .unwrap_or(self.config.maintenance.maximal_distance)
+    }
+
+    /// Vehicle types that may operate the service trip (see allowedVehicleTypes and
+    /// forbiddenVehicleTypes of the route). Always contains vehicle_type_for(service_trip).
+    pub fn allowed_vehicle_types_for(&self, service_trip: NodeIdx) -> &[VehicleTypeIdx] {
+        self.node(service_trip)
+            .as_service_trip()
+            .allowed_vehicle_types()
+    }
+
+    /// A service trip is only compatible with the vehicle types allowed on its route, all other
+    /// nodes are compatible with every vehicle type.
     pub fn compatible_with_vehicle_type(
         &self,
         node: NodeIdx,
         vehicle_type: VehicleTypeIdx,
     ) -> bool {
         if self.node(node).is_service() {
-            self.vehicle_type_for(node) == vehicle_type
+            self.allowed_vehicle_types_for(node).contains(&vehicle_type)
         } else {
             true
         }
@@ -197,6 +283,7 @@ impl Network {
             .passengers()
             .div_ceil(vehicle_type.capacity())
             .max(service_trip.seated().div_ceil(vehicle_type.seats()))
+            .max(service_trip.min_vehicles().unwrap_or(0))
         // .max(1) // one vehicle is always required
     }
 
@@ -225,8 +312,29 @@ impl Network {
         self.depots.get(&depot_idx).unwrap().1
     }
 
+    /// returns the end node of the given depot. If the depot is a dominated end depot, the end
+    /// node of the depot it is consolidated into is returned instead.
     pub fn get_end_depot_node(&self, depot_idx: DepotIdx) -> NodeIdx {
-        self.depots.get(&depot_idx).unwrap().2
+        self.depots.get(&self.end_depot_for(depot_idx)).unwrap().2
+    }
+
+    /// returns the depot at which vehicles despawn instead of the given depot, i.e., the kept
+    /// depot if the given depot is a dominated end depot and the depot itself otherwise.
+    pub fn end_depot_for(&self, depot_idx: DepotIdx) -> DepotIdx {
+        self.dominated_end_depots
+            .get(&depot_idx)
+            .copied()
+            .unwrap_or(depot_idx)
+    }
+
+    /// dominated end depots together with the depot they are consolidated into, sorted by the id
+    /// of the dominated depot.
+    pub fn dominated_end_depots(&self) -> impl Iterator<Item = (DepotIdx, DepotIdx)> + '_ {
+        self.depots_iter_sorted().filter_map(|depot_idx| {
+            self.dominated_end_depots
+                .get(&depot_idx)
+                .map(|&kept_depot_idx| (depot_idx, kept_depot_idx))
+        })
     }
 
     pub fn idle_time_between(&self, node1: NodeIdx, node2: NodeIdx) -> Duration {
@@ -247,6 +355,7 @@ impl Network {
         self.locations.travel_time(
             self.node(node1).end_location(),
             self.node(node2).start_location(),
+            self.node(node1).end_time(),
         )
     }
 
@@ -285,6 +394,43 @@ impl Network {
         n1.end_time() + self.minimal_duration_between_nodes_as_ref(n1, n2) <= n2.start_time()
     }
 
+    /// same as can_reach but additionally checks that a formation consisting of vehicles of the
+    /// given types can operate node2 as a whole:
+    /// * for a service trip, all vehicles must be of a type allowed on the trip and the formation
+    ///   must not exceed the maximal formation count (of the trip's vehicle type or the trip),
+    /// * for a maintenance slot, the formation must fit onto its tracks.
+    pub fn can_reach_with_formation(
+        &self,
+        node1: NodeIdx,
+        node2: NodeIdx,
+        formation: &[VehicleTypeIdx],
+    ) -> bool {
+        if !self.can_reach(node1, node2) {
+            return false;
+        }
+        let formation_count = formation.len() as VehicleCount;
+        match self.node(node2) {
+            Node::Service((_, service_trip)) => {
+                let limit_of_type = self
+                    .vehicle_types
+                    .get(service_trip.vehicle_type())
+                    .unwrap()
+                    .maximal_formation_count();
+                formation
+                    .iter()
+                    .all(|vehicle_type| service_trip.allowed_vehicle_types().contains(vehicle_type))
+                    && [limit_of_type, service_trip.maximal_formation_count()]
+                        .iter()
+                        .flatten()
+                        .all(|&limit| formation_count <= limit)
+            }
+            Node::Maintenance((_, maintenance_slot)) => {
+                formation_count <= maintenance_slot.track_count()
+            }
+            _ => true,
+        }
+    }
+
     /// provides all nodes of the given vehicle_type that are can be reached by node
     pub fn successors(
         &self,
@@ -302,6 +448,34 @@ impl Network {
             })
     }
 
+    /// All pairs of service trips (of the same vehicle type) such that the second trip can be
+    /// reached from the first one with a dead head distance below the given threshold. Such pairs
+    /// form natural vehicle blocks. Sorted by the dead head distance (ties by the trips).
+    pub fn blockable_pairs(
+        &self,
+        max_dead_head_distance: Distance,
+    ) -> impl Iterator<Item = (NodeIdx, NodeIdx)> + '_ {
+        let mut pairs: Vec<(Distance, NodeIdx, NodeIdx)> = self
+            .all_service_nodes()
+            .flat_map(|trip| {
+                self.successors(self.vehicle_type_for(trip), trip)
+                    .filter(|&successor| self.node(successor).is_service())
+                    .map(move |successor| {
+                        (
+                            self.dead_head_distance_between(trip, successor),
+                            trip,
+                            successor,
+                        )
+                    })
+            })
+            .filter(|(distance, _, _)| *distance < max_dead_head_distance)
+            .collect();
+        pairs.sort();
+        pairs
+            .into_iter()
+            .map(|(_, trip, successor)| (trip, successor))
+    }
+
     /// provides all nodes of the given vehicle_type that are can reach node
     pub fn predecessors(
         &self,
@@ -331,10 +505,11 @@ impl Network {
         if n1.end_location() == n2.start_location() {
             // no dead_head_trip
             self.shunting_duration_between_activities_if_no_dead_head_trip(n1, n2)
+                + self.turnaround_duration_between(n1, n2)
         } else {
             // dead_head_trip
             self.locations
-                .travel_time(n1.end_location(), n2.start_location())
+                .travel_time(n1.end_location(), n2.start_location(), n1.end_time())
                 + self.shunting_duration_between_activities_if_dead_head_trip(n1, n2)
         }
     }
@@ -345,6 +520,9 @@ impl Network {
         n2: &Node,
     ) -> Duration {
         match (n1, n2) {
+            (Node::Service(_), Node::Service(_)) if self.config.shunting.zero_same_location => {
+                Duration::ZERO // through-running: the vehicle stays coupled
+            }
             (Node::Service(_), Node::Service(_)) => self.config.shunting.minimal,
             (Node::Service(_), Node::Maintenance(_)) => self.config.shunting.minimal,
             (Node::Maintenance(_), Node::Service(_)) => self.config.shunting.minimal,
@@ -353,6 +531,20 @@ impl Network {
         }
     }
 
+    /// A service trip followed by a service trip in the opposite direction at the same location
+    /// reverses the vehicle, which needs the minimal turnaround of that location. Loops have no
+    /// direction, so they never need a turnaround.
+    fn turnaround_duration_between(&self, n1: &Node, n2: &Node) -> Duration {
+        match (n1, n2) {
+            (Node::Service(_), Node::Service(_))
+                if !n1.is_loop() && n2.end_location() == n1.start_location() =>
+            {
+                self.locations.min_turnaround(n1.end_location())
+            }
+            _ => Duration::ZERO,
+        }
+    }
+
     fn shunting_duration_between_activities_if_dead_head_trip(
         &self,
         n1: &Node,
@@ -373,6 +565,72 @@ impl Network {
         previous + next
     }
 
+    /// Returns the service trip that should ideally follow the given service trip on the same
+    /// vehicle (if any).
+    pub fn preferred_successor_of(&self, service_trip: NodeIdx) -> Option<NodeIdx> {
+        self.preferred_successors.get(&service_trip).copied()
+    }
+
+    /// Iterates over all (service_trip, preferred_successor) pairs.
+    pub fn preferred_successors_iter(&self) -> impl Iterator<Item = (NodeIdx, NodeIdx)> + '_ {
+        self.preferred_successors
+            .iter()
+            .map(|(&service_trip, &successor)| (service_trip, successor))
+    }
+
+    /// Returns all locations that cannot be reached from any depot or from which no depot can be
+    /// reached, only using dead head trips.
+    pub fn locations_unreachable_from_depots(&self) -> Vec<Location> {
+        let depot_locations: Vec<Location> = self
+            .depots
+            .values()
+            .map(|(depot, _, _)| depot.location())
+            .filter(|&location| location != Location::Nowhere)
+            .collect();
+
+        let reachable_from_depots = self.reachable_locations(&depot_locations, |a, b| {
+            self.locations.base_travel_time(a, b)
+        });
+        let reaching_depots = self.reachable_locations(&depot_locations, |a, b| {
+            self.locations.base_travel_time(b, a)
+        });
+
+        let mut unreachable: Vec<Location> = self
+            .locations
+            .iter()
+            .filter(|location| {
+                !reachable_from_depots.contains(location) || !reaching_depots.contains(location)
+            })
+            .collect();
+        unreachable.sort_by_key(|location| location.idx());
+        unreachable
+    }
+
+    /// Returns all service trips that start or end at a location that is unreachable from the
+    /// depots (see locations_unreachable_from_depots).
+    pub fn service_trips_at_unreachable_locations(&self) -> Vec<NodeIdx> {
+        let unreachable = self.locations_unreachable_from_depots();
+        self.all_service_nodes()
+            .filter(|&service_trip| {
+                let node = self.node(service_trip);
+                unreachable.contains(&node.start_location())
+                    || unreachable.contains(&node.end_location())
+            })
+            .collect()
+    }
+
+    /// Returns all maintenance slots at a location that is unreachable from the depots (see
+    /// locations_unreachable_from_depots), e.g., a workshop without dead head trips. Such slots
+    /// can only be visited after a service trip ending at their location.
+    pub fn maintenance_slots_at_unreachable_locations(&self) -> Vec<NodeIdx> {
+        let unreachable = self.locations_unreachable_from_depots();
+        self.maintenance_nodes()
+            .filter(|&maintenance_slot| {
+                unreachable.contains(&self.node(maintenance_slot).start_location())
+            })
+            .collect()
+    }
+
     pub fn all_nodes(&self) -> impl Iterator<Item = NodeIdx> + '_ {
         self.nodes_sorted_by_start.values().copied()
     }
@@ -394,6 +652,31 @@ impl Network {
         });
         depots
     }
+
+    /// End depots at which a vehicle of the given type whose tour ends with last_node can
+    /// despawn, sorted by the distance from the end location of last_node. Only end depots that
+    /// allow the vehicle type and that can be reached by a dead head trip are returned. The
+    /// overflow depot (located nowhere) is always included as it takes any vehicle.
+    pub fn reachable_end_depots(
+        &self,
+        last_node: NodeIdx,
+        vehicle_type: VehicleTypeIdx,
+    ) -> Vec<NodeIdx> {
+        let end_location = self.node(last_node).end_location();
+        let overflow_end_depot = self.overflow_depot_idxs.2;
+        self.end_depots_sorted_by_distance_from(end_location)
+            .into_iter()
+            .filter(|&end_depot| {
+                end_depot == overflow_end_depot
+                    || (self.capacity_of(self.get_depot_idx(end_depot), vehicle_type) > 0
+                        && self.can_reach(last_node, end_depot)
+                        && self
+                            .locations
+                            .base_travel_time(end_location, self.node(end_depot).start_location())
+                            != Duration::Infinity)
+            })
+            .collect()
+    }
 }
 
 impl Network {
@@ -414,6 +697,7 @@ impl Network {
         let mut maintenance_nodes = Vec::new();
         let mut start_depot_nodes = Vec::new();
         let mut end_depot_nodes = Vec::new();
+        let mut all_end_depot_nodes = Vec::new(); // including the ones of dominated end depots
 
         let mut earliest_datetime = DateTime::Latest;
         let mut latest_datetime = DateTime::Earliest;
@@ -444,6 +728,8 @@ impl Network {
         );
         depots.push(overflow_depot);
 
+        let dominated_end_depots = Network::find_dominated_end_depots(&depots, &vehicle_types);
+
         let mut idx_counter: Idx = 0;
         for depot in depots {
             let depot_idx = depot.idx();
@@ -465,7 +751,10 @@ impl Network {
                 Node::create_end_depot_node(idx_counter, end_node_id, depot_idx, depot.location());
             let end_node_idx = end_node.idx();
             nodes.insert(end_node_idx, end_node); // PERF replace by Vec check that idx is correct
-            end_depot_nodes.push(end_node_idx);
+            all_end_depot_nodes.push(end_node_idx);
+            if !dominated_end_depots.contains_key(&depot_idx) {
+                end_depot_nodes.push(end_node_idx);
+            }
             idx_counter += 1;
 
             depots_lookup.insert(depot_idx, (depot, start_node_idx, end_node_idx));
@@ -563,7 +852,7 @@ impl Network {
                         .iter()
                         .chain(maintenance_nodes.iter())
                         .chain(start_depot_nodes.iter())
-                        .chain(end_depot_nodes.iter())
+                        .chain(all_end_depot_nodes.iter())
                         .map(|&n| {
                             let node = nodes.get(&n).unwrap();
                             ((node.start_time(), n), n)
@@ -580,7 +869,7 @@ impl Network {
                     .iter()
                     .chain(maintenance_nodes.iter())
                     .chain(start_depot_nodes.iter())
-                    .chain(end_depot_nodes.iter())
+                    .chain(all_end_depot_nodes.iter())
                     .map(|&n| {
                         let node = nodes.get(&n).unwrap();
                         ((node.end_time(), n), n)
@@ -619,7 +908,7 @@ impl Network {
             depots_lookup[&overflow_depot_id].2,
         );
 
-        Network {
+        let mut network = Network {
             nodes,
             depots: depots_lookup,
             overflow_depot_idxs: overflow_depot_ids,
@@ -635,7 +924,296 @@ impl Network {
             vehicle_types: Arc::new(vehicle_types),
             number_of_service_nodes,
             planning_days,
+            time_horizon: (earliest_datetime, latest_datetime),
+            preferred_successors: HashMap::new(),
+            dominated_end_depots,
+        };
+        network.preferred_successors = network.resolve_preferred_successors();
+        if let Some(warning) = network.unreachable_locations_warning() {
+            println!("\x1b[93mwarning:\x1b[0m {}", warning);
+        }
+        network
+    }
+
+    /// Creates a new network that contains all nodes of this network plus the given service
+    /// trips. Locations, vehicle types and config are shared with this network and the indices
+    /// of all existing nodes are unchanged; the new service trips get fresh indices after the
+    /// largest existing index.
+    pub fn with_additional_service_trips(&self, service_trips: Vec<ServiceTrip>) -> Arc<Network> {
+        let mut nodes = self.nodes.clone();
+        let mut service_nodes = self.service_nodes.clone();
+        let mut nodes_sorted_by_start = self.nodes_sorted_by_start.clone();
+        let mut vehicle_type_nodes_sorted_by_start =
+            self.vehicle_type_nodes_sorted_by_start.clone();
+        let mut vehicle_type_nodes_sorted_by_end = self.vehicle_type_nodes_sorted_by_end.clone();
+        let (mut earliest_datetime, mut latest_datetime) = self.time_horizon;
+
+        let first_new_idx: Idx = self
+            .nodes
+            .keys()
+            .map(|n| n.idx())
+            .max()
+            .map_or(0, |m| m + 1);
+        for (idx_counter, service_trip) in (first_new_idx..).zip(service_trips) {
+            let vehicle_type = service_trip.vehicle_type();
+            let service_trip_node = Node::create_service_trip_node(idx_counter, service_trip);
+            let node_idx = service_trip_node.idx();
+
+            earliest_datetime = earliest_datetime.min(service_trip_node.start_time());
+            latest_datetime = latest_datetime.max(service_trip_node.end_time());
+
+            let start_key = (service_trip_node.start_time(), node_idx);
+            let end_key = (service_trip_node.end_time(), node_idx);
+            nodes_sorted_by_start.insert(start_key, node_idx);
+            vehicle_type_nodes_sorted_by_start
+                .get_mut(&vehicle_type)
+                .unwrap()
+                .insert(start_key, node_idx);
+            vehicle_type_nodes_sorted_by_end
+                .get_mut(&vehicle_type)
+                .unwrap()
+                .insert(end_key, node_idx);
+            service_nodes.get_mut(&vehicle_type).unwrap().push(node_idx);
+            nodes.insert(node_idx, service_trip_node);
+        }
+
+        for trips in service_nodes.values_mut() {
+            trips.sort_by(|&n1, &n2| {
+                nodes
+                    .get(&n1)
+                    .unwrap()
+                    .cmp_start_time(nodes.get(&n2).unwrap())
+            });
+        }
+
+        let number_of_service_nodes = service_nodes.values().map(|v| v.len()).sum();
+
+        // the overflow depot must still be able to cover every service trip
+        let mut depots = self.depots.clone();
+        let (overflow_depot_idx, _, _) = self.overflow_depot_idxs;
+        let max_formation_count = self
+            .vehicle_types
+            .iter()
+            .map(|vt| {
+                self.vehicle_types
+                    .get(vt)
+                    .unwrap()
+                    .maximal_formation_count()
+                    .unwrap_or(1)
+            })
+            .max()
+            .unwrap_or(1);
+        let overflow_depot = &mut depots.get_mut(&overflow_depot_idx).unwrap().0;
+        *overflow_depot = Depot::new(
+            overflow_depot_idx,
+            overflow_depot.id().to_string(),
+            Location::Nowhere,
+            number_of_service_nodes as VehicleCount * max_formation_count,
+            self.vehicle_types.iter().map(|vt| (vt, None)).collect(),
+        );
+
+        let planning_days = Duration::from_seconds(
+            (latest_datetime - earliest_datetime)
+                .in_sec()
+                .unwrap()
+                .div_ceil(86400)
+                * 86400,
+        );
+
+        let mut network = Network {
+            nodes,
+            depots,
+            overflow_depot_idxs: self.overflow_depot_idxs,
+            service_nodes,
+            maintenance_nodes: self.maintenance_nodes.clone(),
+            start_depot_nodes: self.start_depot_nodes.clone(),
+            end_depot_nodes: self.end_depot_nodes.clone(),
+            nodes_sorted_by_start,
+            vehicle_type_nodes_sorted_by_start,
+            vehicle_type_nodes_sorted_by_end,
+            config: self.config.clone(),
+            locations: self.locations.clone(),
+            vehicle_types: self.vehicle_types.clone(),
+            number_of_service_nodes,
+            planning_days,
+            time_horizon: (earliest_datetime, latest_datetime),
+            preferred_successors: HashMap::new(),
+            dominated_end_depots: self.dominated_end_depots.clone(),
+        };
+        network.preferred_successors = network.resolve_preferred_successors();
+        Arc::new(network)
+    }
+
+    /// Creates a new network in which the passengers and seated passengers of each service trip
+    /// are multiplied by factor (rounded up). Everything else is the same as in this network.
+    /// Panics if factor is negative or not finite.
+    pub fn with_scaled_demand(&self, factor: f64) -> Arc<Network> {
+        assert!(
+            factor.is_finite() && factor >= 0.0,
+            "Demand factor must be non-negative and finite."
+        );
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|(&idx, node)| match node {
+                Node::Service((_, service_trip)) => (
+                    idx,
+                    Node::create_service_trip_node(
+                        idx.idx(),
+                        service_trip.with_scaled_demand(factor),
+                    ),
+                ),
+                _ => (idx, node.clone()),
+            })
+            .collect();
+
+        Arc::new(Network {
+            nodes,
+            depots: self.depots.clone(),
+            overflow_depot_idxs: self.overflow_depot_idxs,
+            service_nodes: self.service_nodes.clone(),
+            maintenance_nodes: self.maintenance_nodes.clone(),
+            start_depot_nodes: self.start_depot_nodes.clone(),
+            end_depot_nodes: self.end_depot_nodes.clone(),
+            nodes_sorted_by_start: self.nodes_sorted_by_start.clone(),
+            vehicle_type_nodes_sorted_by_start: self.vehicle_type_nodes_sorted_by_start.clone(),
+            vehicle_type_nodes_sorted_by_end: self.vehicle_type_nodes_sorted_by_end.clone(),
+            config: self.config.clone(),
+            locations: self.locations.clone(),
+            vehicle_types: self.vehicle_types.clone(),
+            number_of_service_nodes: self.number_of_service_nodes,
+            planning_days: self.planning_days,
+            time_horizon: self.time_horizon,
+            preferred_successors: self.preferred_successors.clone(),
+            dominated_end_depots: self.dominated_end_depots.clone(),
+        })
+    }
+
+    /// An end depot is dominated by another depot at the same location if the other depot has at
+    /// least the same total capacity and at least the same capacity for each vehicle type.
+    /// Among depots that dominate each other, the one with the smallest index is kept. Each
+    /// dominated depot is mapped to a non-dominated depot at the same location.
+    fn find_dominated_end_depots(
+        depots: &[Depot],
+        vehicle_types: &VehicleTypes,
+    ) -> HashMap<DepotIdx, DepotIdx> {
+        let covers = |kept: &Depot, dominated: &Depot| {
+            kept.idx() != dominated.idx()
+                && kept.location() == dominated.location()
+                && kept.location() != Location::Nowhere
+                && kept.total_capacity() >= dominated.total_capacity()
+                && vehicle_types
+                    .iter()
+                    .all(|vt| kept.capacity_for(vt) >= dominated.capacity_for(vt))
+        };
+        let dominates = |kept: &Depot, dominated: &Depot| {
+            covers(kept, dominated) && (!covers(dominated, kept) || kept.idx() < dominated.idx())
+        };
+        let non_dominated: Vec<&Depot> = depots
+            .iter()
+            .filter(|&depot| !depots.iter().any(|other| dominates(other, depot)))
+            .collect();
+        depots
+            .iter()
+            .filter_map(|depot| {
+                non_dominated
+                    .iter()
+                    .find(|kept| dominates(kept, depot))
+                    .map(|kept| (depot.idx(), kept.idx()))
+            })
+            .collect()
+    }
+
+    /// All locations that can be reached from the given start locations via dead head trips.
+    fn reachable_locations(
+        &self,
+        start_locations: &[Location],
+        travel_time: impl Fn(Location, Location) -> Duration,
+    ) -> HashSet<Location> {
+        let mut reachable: HashSet<Location> = start_locations.iter().copied().collect();
+        let mut stack: Vec<Location> = start_locations.to_vec();
+        while let Some(location) = stack.pop() {
+            for other in self.locations.iter() {
+                if !reachable.contains(&other) && travel_time(location, other) != Duration::Infinity
+                {
+                    reachable.insert(other);
+                    stack.push(other);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Warning listing the locations that are unreachable from the depots together with the
+    /// affected service trips and maintenance slots. None if all locations are reachable.
+    pub(crate) fn unreachable_locations_warning(&self) -> Option<String> {
+        let unreachable = self.locations_unreachable_from_depots();
+        if unreachable.is_empty() {
+            return None;
+        }
+        let ids_of = |nodes: Vec<NodeIdx>| {
+            nodes
+                .iter()
+                .map(|&node| self.node(node).id())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut warning = format!(
+            "Locations {} are not connected to any depot by dead head trips. Vehicles might get \
+            stranded. Affected service trips: {}",
+            unreachable
+                .iter()
+                .map(|&location| self.locations.get_id(location).unwrap())
+                .collect::<Vec<_>>()
+                .join(", "),
+            ids_of(self.service_trips_at_unreachable_locations())
+        );
+        let maintenance_slots = self.maintenance_slots_at_unreachable_locations();
+        if !maintenance_slots.is_empty() {
+            warning.push_str(&format!(
+                " Affected maintenance slots: {}",
+                ids_of(maintenance_slots)
+            ));
+        }
+        Some(warning)
+    }
+
+    /// Maps the preferred successor ids of the service trips to node indices. Hints to unknown
+    /// service trips, to service trips of other vehicle types or to unreachable service trips are
+    /// ignored.
+    fn resolve_preferred_successors(&self) -> HashMap<NodeIdx, NodeIdx> {
+        let service_trip_lookup: HashMap<&str, NodeIdx> = self
+            .all_service_nodes()
+            .map(|node| (self.node(node).id(), node))
+            .collect();
+
+        let mut preferred_successors = HashMap::new();
+        let mut ignored_hints = 0;
+        for service_trip in self.all_service_nodes() {
+            let successor_id = match self.node(service_trip) {
+                Node::Service((_, s)) => s.preferred_successor(),
+                _ => None,
+            };
+            if let Some(successor_id) = successor_id {
+                match service_trip_lookup.get(successor_id.as_str()) {
+                    Some(&successor)
+                        if self.vehicle_type_for(service_trip)
+                            == self.vehicle_type_for(successor)
+                            && self.can_reach(service_trip, successor) =>
+                    {
+                        preferred_successors.insert(service_trip, successor);
+                    }
+                    _ => ignored_hints += 1,
+                }
+            }
+        }
+        if ignored_hints > 0 {
+            println!(
+                "\x1b[93mwarning:\x1b[0m {} preferred successor hints are infeasible and are ignored.",
+                ignored_hints
+            );
         }
+        preferred_successors
     }
 }
 
@@ -15,6 +15,7 @@ use std::collections::HashMap;
 
 use crate::base_types::{DepotIdx, Location, VehicleCount, VehicleTypeIdx};
 
+#[derive(Clone)]
 pub struct Depot {
     idx: DepotIdx,
     id: String,
@@ -50,6 +51,12 @@ impl Depot {
             None => 0,                         // vehicle type not allowed
         }
     }
+
+    /// true if the vehicle type is allowed without a vehicle specific limit (only the total
+    /// capacity applies)
+    pub fn is_unlimited_for(&self, vehicle_type_idx: VehicleTypeIdx) -> bool {
+        matches!(self.allowed_types.get(&vehicle_type_idx), Some(None))
+    }
 }
 
 // static
@@ -14,14 +14,15 @@
 use rapid_time::{DateTime, Duration};
 
 use crate::base_types::{
-    DepotIdx, Distance, Idx, Location, NodeIdx, PassengerCount, VehicleCount, VehicleTypeIdx,
+    DepotIdx, Distance, Idx, Location, NodeIdx, PassengerCount, StationSide, VehicleCount,
+    VehicleTypeIdx,
 };
 
 use core::cmp::Ordering;
 
 use std::fmt;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Node {
     StartDepot((NodeIdx, DepotNode)),
     Service((NodeIdx, ServiceTrip)),
@@ -29,7 +30,7 @@ pub enum Node {
     EndDepot((NodeIdx, DepotNode)),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DepotNode {
     depot_idx: DepotIdx,
     location: Location,
@@ -42,10 +43,11 @@ impl DepotNode {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ServiceTrip {
     id: String,
     vehicle_type: VehicleTypeIdx,
+    allowed_vehicle_types: Vec<VehicleTypeIdx>, // sorted, always contains vehicle_type
     origin: Location,
     destination: Location,
     departure: DateTime,
@@ -54,6 +56,9 @@ pub struct ServiceTrip {
     passengers: PassengerCount,
     seated: PassengerCount,
     maximal_formation_count: Option<VehicleCount>,
+    min_vehicles: Option<VehicleCount>, // e.g., at least two units for redundancy
+    preferred_successor: Option<String>, // id of the service trip that should ideally follow
+    station_sides: (StationSide, StationSide), // (origin side, destination side)
 }
 
 impl ServiceTrip {
@@ -65,6 +70,12 @@ impl ServiceTrip {
         self.vehicle_type
     }
 
+    /// Vehicle types that may operate the trip (sorted). Contains at least the vehicle type of
+    /// the route.
+    pub fn allowed_vehicle_types(&self) -> &[VehicleTypeIdx] {
+        &self.allowed_vehicle_types
+    }
+
     pub fn passengers(&self) -> PassengerCount {
         self.passengers
     }
@@ -76,9 +87,61 @@ impl ServiceTrip {
     pub fn maximal_formation_count(&self) -> Option<VehicleCount> {
         self.maximal_formation_count
     }
+
+    /// Minimal number of vehicles that must operate the trip (independent of the demand).
+    pub fn min_vehicles(&self) -> Option<VehicleCount> {
+        self.min_vehicles
+    }
+
+    pub fn preferred_successor(&self) -> Option<&String> {
+        self.preferred_successor.as_ref()
+    }
+
+    /// Side of the origin station the trip departs at and side of the destination station it
+    /// arrives at.
+    pub fn station_sides(&self) -> (StationSide, StationSide) {
+        self.station_sides
+    }
+
+    /// The vehicle type of the trip is always allowed.
+    pub(crate) fn with_allowed_vehicle_types(
+        self,
+        mut allowed_vehicle_types: Vec<VehicleTypeIdx>,
+    ) -> ServiceTrip {
+        allowed_vehicle_types.push(self.vehicle_type);
+        allowed_vehicle_types.sort();
+        allowed_vehicle_types.dedup();
+        ServiceTrip {
+            allowed_vehicle_types,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_station_sides(
+        self,
+        origin_side: StationSide,
+        destination_side: StationSide,
+    ) -> ServiceTrip {
+        ServiceTrip {
+            station_sides: (origin_side, destination_side),
+            ..self
+        }
+    }
+
+    /// Copy of this service trip with passengers and seated passengers multiplied by factor
+    /// (rounded up, ignoring floating point noise so that e.g. 50 * 1.1 stays 55).
+    pub(crate) fn with_scaled_demand(&self, factor: f64) -> ServiceTrip {
+        let scale =
+            |count: PassengerCount| ((count as f64 * factor) - 1e-9).ceil() as PassengerCount;
+        ServiceTrip {
+            passengers: scale(self.passengers),
+            seated: scale(self.seated),
+            ..self.clone()
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MaintenanceSlot {
     id: String,
     location: Location,
@@ -103,6 +166,12 @@ impl Node {
         matches!(self, Node::Service(_))
     }
 
+    /// True for service trips that start and end at the same location (e.g., circular lines).
+    /// Their distance is the distance of the route, not a dead head trip.
+    pub fn is_loop(&self) -> bool {
+        self.is_service() && self.start_location() == self.end_location()
+    }
+
     pub fn is_maintenance(&self) -> bool {
         matches!(self, Node::Maintenance(_))
     }
@@ -267,10 +336,13 @@ impl Node {
         passengers: PassengerCount,
         seated: PassengerCount,
         maximal_formation_count: Option<VehicleCount>,
+        min_vehicles: Option<VehicleCount>,
+        preferred_successor: Option<String>,
     ) -> ServiceTrip {
         ServiceTrip {
             id,
             vehicle_type,
+            allowed_vehicle_types: vec![vehicle_type],
             origin,
             destination,
             departure,
@@ -279,6 +351,12 @@ impl Node {
             passengers,
             seated,
             maximal_formation_count,
+            min_vehicles,
+            preferred_successor,
+            station_sides: (
+                StationSide::DEFAULT_ORIGIN,
+                StationSide::DEFAULT_DESTINATION,
+            ),
         }
     }
 
@@ -11,13 +11,55 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+#[cfg(test)]
+mod tests;
+
 use std::{collections::HashMap, fmt, sync::Arc};
 
-use crate::base_types::{PassengerCount, VehicleCount, VehicleTypeIdx};
+use crate::base_types::{
+    DepotIdx, Distance, PassengerCount, TrainLength, VehicleCount, VehicleTypeIdx,
+};
 
 pub struct VehicleTypes {
     vehicle_types: HashMap<VehicleTypeIdx, Arc<VehicleType>>, // PERF use only Vec
     ids_sorted: Vec<VehicleTypeIdx>, // sorted by seat count, then capacity, then length, then id
+    roster: Vec<RosterUnit>,         // physical units deviating from their type (input order)
+}
+
+/// A physical unit of a vehicle type whose seats and capacity deviate from its type (e.g., seats
+/// removed for bike space). The unit is identified by its own id from the input, independent of
+/// the ids of the vehicles in a schedule. A spawned vehicle takes over a unit of its type that is
+/// not used by another vehicle and keeps it for its lifetime (also if its id is renamed).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RosterUnit {
+    id: String,
+    vehicle_type: VehicleTypeIdx,
+    seats: Option<PassengerCount>,    // None: seats of the vehicle type
+    capacity: Option<PassengerCount>, // None: capacity of the vehicle type
+}
+
+impl RosterUnit {
+    pub fn new(
+        id: String,
+        vehicle_type: VehicleTypeIdx,
+        seats: Option<PassengerCount>,
+        capacity: Option<PassengerCount>,
+    ) -> RosterUnit {
+        RosterUnit {
+            id,
+            vehicle_type,
+            seats,
+            capacity,
+        }
+    }
+
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn vehicle_type(&self) -> VehicleTypeIdx {
+        self.vehicle_type
+    }
 }
 
 impl VehicleTypes {
@@ -33,9 +75,45 @@ impl VehicleTypes {
         VehicleTypes {
             vehicle_types,
             ids_sorted: ids_sorted_by_idx,
+            roster: Vec::new(),
         }
     }
 
+    pub fn with_roster(self, roster: Vec<RosterUnit>) -> VehicleTypes {
+        VehicleTypes { roster, ..self }
+    }
+
+    /// Returns the roster units (indices into the roster) of the given vehicle type in input
+    /// order.
+    pub fn roster_units_of(
+        &self,
+        vehicle_type: VehicleTypeIdx,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.roster
+            .iter()
+            .enumerate()
+            .filter(move |(_, unit)| unit.vehicle_type == vehicle_type)
+            .map(|(idx, _)| idx)
+    }
+
+    pub fn roster_unit(&self, unit: usize) -> &RosterUnit {
+        &self.roster[unit]
+    }
+
+    /// Seats of the roster unit, falling back to the seats of its vehicle type.
+    pub fn seats_of(&self, unit: usize) -> PassengerCount {
+        let unit = &self.roster[unit];
+        unit.seats
+            .unwrap_or(self.vehicle_types[&unit.vehicle_type].seats())
+    }
+
+    /// Capacity of the roster unit, falling back to the capacity of its vehicle type.
+    pub fn capacity_of(&self, unit: usize) -> PassengerCount {
+        let unit = &self.roster[unit];
+        unit.capacity
+            .unwrap_or(self.vehicle_types[&unit.vehicle_type].capacity())
+    }
+
     pub fn get(&self, idx: VehicleTypeIdx) -> Option<Arc<VehicleType>> {
         self.vehicle_types.get(&idx).cloned()
     }
@@ -45,6 +123,46 @@ impl VehicleTypes {
         self.ids_sorted.iter().cloned()
     }
 
+    /// Same as iter but returns the vehicle types themselves (in the same order).
+    pub fn iter_with_details(&self) -> impl Iterator<Item = Arc<VehicleType>> + '_ {
+        self.ids_sorted
+            .iter()
+            .map(|idx| self.vehicle_types[idx].clone())
+    }
+
+    /// Returns an iterator over all vehicle types, sorted by id (e.g. for reports).
+    pub fn iter_sorted_by_id(&self) -> impl Iterator<Item = VehicleTypeIdx> + '_ {
+        let mut ids: Vec<_> = self.ids_sorted.clone();
+        ids.sort_by(|a, b| self.vehicle_types[a].id().cmp(self.vehicle_types[b].id()));
+        ids.into_iter()
+    }
+
+    /// Warning naming the vehicle types whose capacity is less than their number of seats (the
+    /// capacity should include seats plus standing room). None if all vehicle types are fine.
+    pub(crate) fn capacity_below_seats_warning(&self) -> Option<String> {
+        let invalid: Vec<String> = self
+            .iter_sorted_by_id()
+            .map(|vt| &self.vehicle_types[&vt])
+            .filter(|vt| vt.capacity() < vt.seats())
+            .map(|vt| {
+                format!(
+                    "{} (capacity {} < seats {})",
+                    vt.id(),
+                    vt.capacity(),
+                    vt.seats()
+                )
+            })
+            .collect();
+        if invalid.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Vehicle types {} have less capacity than seats. The capacity should include seats \
+            plus standing room.",
+            invalid.join(", ")
+        ))
+    }
+
     /// Returns best vehicle_type for demand.
     /// Take vehicle_type with the least number of seats such that all passengers are covered.
     /// if no vehicle_type can cover the demand take biggest vehicle (last in sorted list).
@@ -63,23 +181,33 @@ pub struct VehicleType {
     id: String,
     seats: PassengerCount,
     capacity: PassengerCount,
+    length: TrainLength, // in meter, 0 if unknown
     maximal_formation_count: Option<VehicleCount>,
+    home_depots: Option<Vec<DepotIdx>>, // None means every depot is a home depot
+    maintenance_range: Option<Distance>, // None means the global maximal distance is used
 }
 
 impl VehicleType {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         idx: VehicleTypeIdx,
         id: String,
         capacity_of_passengers: PassengerCount,
         number_of_seats: PassengerCount,
+        length: TrainLength,
         maximal_formation_count: Option<VehicleCount>,
+        home_depots: Option<Vec<DepotIdx>>,
+        maintenance_range: Option<Distance>,
     ) -> VehicleType {
         VehicleType {
             idx,
             id,
             seats: number_of_seats,
             capacity: capacity_of_passengers,
+            length,
             maximal_formation_count,
+            home_depots,
+            maintenance_range,
         }
     }
 
@@ -99,9 +227,27 @@ impl VehicleType {
         self.capacity
     }
 
+    pub fn length(&self) -> TrainLength {
+        self.length
+    }
+
     pub fn maximal_formation_count(&self) -> Option<VehicleCount> {
         self.maximal_formation_count
     }
+
+    /// Maximal distance a vehicle of this type may travel between two maintenance visits. None
+    /// if the type does not declare its own range.
+    pub fn maintenance_range(&self) -> Option<Distance> {
+        self.maintenance_range
+    }
+
+    /// Returns true if the depot is declared as home depot of this vehicle type or if no home
+    /// depots are declared at all.
+    pub fn is_home_depot(&self, depot: DepotIdx) -> bool {
+        self.home_depots
+            .as_ref()
+            .is_none_or(|home_depots| home_depots.contains(&depot))
+    }
 }
 
 impl fmt::Display for VehicleType {
@@ -0,0 +1,93 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::base_types::{Distance, VehicleTypeIdx};
+
+use super::{VehicleType, VehicleTypes};
+
+#[test]
+fn iter_with_details_test() {
+    // ARRANGE
+    let vehicle_type = |idx: u16, id: &str, seats: u32| {
+        VehicleType::new(
+            VehicleTypeIdx::from(idx),
+            String::from(id),
+            2 * seats,
+            seats,
+            100,
+            Some(3),
+            None,
+            Some(Distance::from_meter(1000 * seats as u64)),
+        )
+    };
+    let vehicle_types = VehicleTypes::new(vec![
+        vehicle_type(1, "vt_b", 40),
+        vehicle_type(0, "vt_a", 60),
+        vehicle_type(2, "vt_c", 20),
+    ]);
+
+    // ACT
+    let details: Vec<_> = vehicle_types.iter_with_details().collect();
+
+    // ASSERT
+    assert_eq!(
+        details.iter().map(|vt| vt.idx()).collect::<Vec<_>>(),
+        vehicle_types.iter().collect::<Vec<_>>()
+    );
+    for vt in details.iter() {
+        assert_eq!(**vt, *vehicle_types.get(vt.idx()).unwrap());
+    }
+    assert_eq!(details[0].id(), "vt_a");
+    assert_eq!(details[0].seats(), 60);
+    assert_eq!(details[0].capacity(), 120);
+    assert_eq!(details[1].id(), "vt_b");
+    assert_eq!(
+        details[2].maintenance_range(),
+        Some(Distance::from_meter(20000))
+    );
+}
+
+#[test]
+fn capacity_below_seats_warning_test() {
+    // ARRANGE
+    let vehicle_type = |idx: u16, id: &str, capacity: u32, seats: u32| {
+        VehicleType::new(
+            VehicleTypeIdx::from(idx),
+            String::from(id),
+            capacity,
+            seats,
+            100,
+            None,
+            None,
+            None,
+        )
+    };
+    let valid_vehicle_types = VehicleTypes::new(vec![
+        vehicle_type(0, "vt_a", 120, 60),
+        vehicle_type(1, "vt_b", 40, 40),
+    ]);
+    let invalid_vehicle_types = VehicleTypes::new(vec![
+        vehicle_type(0, "vt_a", 120, 60),
+        vehicle_type(1, "vt_b", 30, 40),
+    ]);
+
+    // ACT
+    let valid_warning = valid_vehicle_types.capacity_below_seats_warning();
+    let invalid_warning = invalid_vehicle_types.capacity_below_seats_warning();
+
+    // ASSERT
+    assert!(valid_warning.is_none());
+    let invalid_warning = invalid_warning.unwrap();
+    assert!(invalid_warning.contains("Vehicle types vt_b (capacity 30 < seats 40)"));
+    assert!(!invalid_warning.contains("vt_a"));
+}
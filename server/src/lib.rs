@@ -13,17 +13,23 @@
 
 use im::HashMap;
 use model::base_types::VehicleTypeIdx;
-use model::json_serialisation::load_rolling_stock_problem_instance_from_json;
+use model::config::ConfigError;
+use model::json_serialisation::try_load_rolling_stock_problem_instance_from_json;
 use rapid_solve::heuristics::Solver;
 use rapid_solve::objective::EvaluatedSolution;
 use rapid_solve::objective::Objective;
 use rapid_time::{DateTime, Duration};
-use solution::json_serialisation::schedule_to_json;
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::Serialize;
+use solution::json_serialisation::{
+    schedule_to_json, statistics_to_json, ScheduleJson, SummaryJson,
+};
 use solution::transition::Transition;
+use solver::dummy_reassignment::DummyReassignmentSolver;
 use solver::local_search::neighborhood::swaps::SwapInfo;
-use solver::local_search::ScheduleWithInfo;
-use solver::min_cost_flow_solver::MinCostFlowSolver;
+use solver::local_search::{MoveTally, ScheduleWithInfo};
 use solver::objective;
+use solver::start_solver::compute_start_schedule;
 use solver::transition_local_search::build_transition_local_search_solver;
 use solver::transition_local_search::TransitionWithInfo;
 
@@ -31,9 +37,10 @@ use gethostname::gethostname;
 use std::sync::Arc;
 use std::time as stdtime;
 
-pub fn solve_instance(input_data: serde_json::Value) -> serde_json::Value {
+/// Solves the instance and returns the output JSON. Fails if the parameters are invalid.
+pub fn solve_instance(input_data: serde_json::Value) -> Result<serde_json::Value, ConfigError> {
     let start_time = stdtime::Instant::now();
-    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let network = try_load_rolling_stock_problem_instance_from_json(input_data)?;
     println!(
         "Instance with {} vehicle types and {} trips loaded (elapsed time: {:0.2}sec)",
         network.vehicle_types().iter().count(),
@@ -41,23 +48,24 @@ pub fn solve_instance(input_data: serde_json::Value) -> serde_json::Value {
         start_time.elapsed().as_secs_f32()
     );
 
-    let objective = Arc::new(objective::build());
+    let objective = Arc::new(objective::build_for(&network));
 
-    println!("Solve with MinCostFlowSolver:");
-    let min_cost_flow_solver = MinCostFlowSolver::initialize(network.clone());
-    let start_schedule = min_cost_flow_solver.solve();
+    let start_solver = network.config().start_solver;
+    println!("Solve with {:?} start solver:", start_solver);
+    let start_schedule = compute_start_schedule(network.clone());
     println!(
-        "MinCostFlowSolver computed schedule (elapsed time: {:0.2}sec)",
+        "{:?} start solver computed schedule (elapsed time: {:0.2}sec)",
+        start_solver,
         start_time.elapsed().as_secs_f32()
     );
 
     let start_schedule_with_info = ScheduleWithInfo::new(
         start_schedule.improve_depots(None),
         SwapInfo::NoSwap,
-        "Result from min cost flow solver".to_string(),
+        format!("Result from {:?} start solver", start_solver),
     );
 
-    let solution = if network.maintenance_considered() {
+    let (solution, telemetry) = if network.maintenance_considered() {
         println!("\nStarting local search:\n");
         println!("Initial objective value:");
         objective.print_objective_value(
@@ -67,14 +75,28 @@ pub fn solve_instance(input_data: serde_json::Value) -> serde_json::Value {
         );
         println!();
 
-        let local_search_solver = solver::local_search::build_local_search_solver(network.clone());
+        // the local search stops as soon as the lower bound is reached
+        let target_objective = objective::lower_bound(&network);
 
-        local_search_solver.solve(start_schedule_with_info)
+        let local_search_solver = solver::local_search::build_local_search_solver(
+            network.clone(),
+            Some(target_objective),
+            None,
+        );
+
+        let solution = local_search_solver.solve(start_schedule_with_info);
+        (solution, local_search_solver.move_tally().to_json())
     } else {
-        println!("\nMaintenance is not considered, returning MinCostFlowSolver solution as final solution");
-        objective.evaluate(start_schedule_with_info.clone())
+        println!("\nMaintenance is not considered, returning start solution as final solution");
+        (
+            objective.evaluate(start_schedule_with_info.clone()),
+            MoveTally::default().to_json(),
+        )
     };
 
+    // cover remaining dummy tours if this improves the objective
+    let solution = DummyReassignmentSolver::initialize(objective.clone()).solve(solution);
+
     // optimize transitions
     println!("\nOptimizing transitions:");
     let start_time_transition_optimization = stdtime::Instant::now();
@@ -106,11 +128,13 @@ pub fn solve_instance(input_data: serde_json::Value) -> serde_json::Value {
     );
     schedule_with_optimized_transitions.print_next_day_transitions();
 
-    // reassign end depots to be consistent with transitions
+    // reassign end depots to be consistent with transitions and renumber the vehicles
+    // canonically, such that equivalent schedules give identical outputs
     let final_schedule = solution
         .solution()
         .get_schedule()
-        .reassign_end_depots_consistent_with_transitions();
+        .reassign_end_depots_consistent_with_transitions()
+        .compact_ids();
     let final_schedule_with_info = ScheduleWithInfo::new(
         final_schedule,
         SwapInfo::NoSwap,
@@ -140,15 +164,50 @@ pub fn solve_instance(input_data: serde_json::Value) -> serde_json::Value {
 
     println!("Running time: {:0.2}sec", runtime_duration.as_secs_f32());
 
-    create_output_json(&final_solution, &objective, runtime_duration)
+    Ok(create_output_json(
+        &final_solution,
+        &objective,
+        runtime_duration,
+        telemetry,
+    ))
+}
+
+/// Output of the solver as returned by create_output_json.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields, title = "RSSched output")]
+struct OutputJson {
+    info: InfoJson,
+    /// one entry per hierarchy level of the objective
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
+    objective_value: serde_json::Value,
+    #[schemars(with = "SummaryJson")]
+    summary: serde_json::Value,
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
+    telemetry: serde_json::Value,
+    #[schemars(with = "ScheduleJson")]
+    schedule: serde_json::Value,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct InfoJson {
+    running_time: String,
+    number_of_threads: usize,
+    #[serde(rename = "timestampUTC")]
+    timestamp_utc: String,
+    hostname: String,
 }
 
 pub fn create_output_json(
     final_solution: &EvaluatedSolution<ScheduleWithInfo>,
     objective: &Objective<ScheduleWithInfo>,
     runtime_duration: stdtime::Duration,
+    telemetry: serde_json::Value,
 ) -> serde_json::Value {
     let json_output = schedule_to_json(final_solution.solution().get_schedule());
+    let json_summary = statistics_to_json(&final_solution.solution().get_schedule().statistics());
     let json_objective_value = objective.objective_value_to_json(final_solution.objective_value());
     let today = DateTime::new("1970-01-01T00:00:00")
         + Duration::from_seconds(
@@ -157,14 +216,23 @@ pub fn create_output_json(
                 .unwrap()
                 .as_secs(),
         );
-    serde_json::json!({
-        "info": {
-            "runningTime": format!("{:0.2}sec", runtime_duration.as_secs_f32()),
-            "numberOfThreads": rayon::current_num_threads(),
-            "timestampUTC": today.as_iso(),
-            "hostname": gethostname().into_string().unwrap_or("unknown".to_string()),
+    let output = OutputJson {
+        info: InfoJson {
+            running_time: format!("{:0.2}sec", runtime_duration.as_secs_f32()),
+            number_of_threads: rayon::current_num_threads(),
+            timestamp_utc: today.as_iso(),
+            hostname: gethostname().into_string().unwrap_or("unknown".to_string()),
         },
-        "objectiveValue": json_objective_value,
-        "schedule": json_output,
-    })
+        objective_value: json_objective_value,
+        summary: json_summary,
+        telemetry,
+        schedule: json_output,
+    };
+    serde_json::to_value(output).unwrap()
+}
+
+/// JSON Schema of the output produced by create_output_json, such that downstream tools can
+/// validate the solver output.
+pub fn schema() -> RootSchema {
+    schema_for!(OutputJson)
 }
@@ -44,10 +44,20 @@ pub async fn healthy() -> &'static str {
     "Healthy"
 }
 
+/// Responds with the output JSON, or with 400 Bad Request and the error message if the
+/// parameters are invalid.
 pub async fn solve(
     axum::extract::Json(input_data): axum::extract::Json<serde_json::Value>,
-) -> axum::response::Json<serde_json::Value> {
+) -> Result<axum::response::Json<serde_json::Value>, (axum::http::StatusCode, String)> {
     println!("\n\n-------------------- New Request --------------------\n");
-    let output = server::solve_instance(input_data);
-    axum::response::Json(output)
+    match server::solve_instance(input_data) {
+        Ok(output) => Ok(axum::response::Json(output)),
+        Err(error) => {
+            println!("Error: invalid parameters: {}", error);
+            Err((
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("Invalid parameters: {}", error),
+            ))
+        }
+    }
 }
@@ -11,61 +11,115 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use itertools::Itertools;
 use model::{
-    base_types::{DepotIdx, NodeIdx, VehicleIdx, VehicleTypeIdx},
+    base_types::{DepotIdx, NodeIdx, VehicleIdx},
     network::{nodes::Node, Network},
+    vehicle_types::VehicleType,
 };
-use rapid_time::DateTime;
+use rapid_time::{DateTime, Duration};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::Schedule;
+use crate::{GanttActivity, Schedule, ScheduleStatistics, SimulationEventKind};
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Output of schedule_to_json.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
-struct ScheduleJson {
+#[schemars(deny_unknown_fields)]
+pub struct ScheduleJson {
     depot_loads: Vec<DepotLoad>,
+    consolidated_end_depots: Vec<ConsolidatedEndDepot>,
+    unserved_demand: Vec<UnservedDemand>,
     fleet: Vec<JsonFleet>,
     departure_segments: Vec<JsonDepartureSegmentWithFormation>,
     maintenance_slots: Vec<JsonFleetMaintenanceSlotWithFormation>,
     dead_head_trips: Vec<JsonFleetDeadHeadTripWithFormation>,
+    gantt: Vec<JsonGanttBar>,
+    simulation_events: Vec<JsonSimulationEvent>,
+    assignments: BTreeMap<String, Vec<JsonAssignedVehicle>>,
+    validity: JsonValidityReport,
+}
+
+/// Output of statistics_to_json.
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct SummaryJson {
+    number_of_vehicles: usize,
+    number_of_dummy_tours: usize,
+    total_distance_in_meter: u64,
+    service_distance_in_meter: u64,
+    dead_head_distance_in_meter: u64,
+    total_overhead_duration_in_sec: u64,
+    unserved_passengers: u32,
+    unserved_seated_passengers: u32,
+    depot_balance_violation: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct DepotLoad {
     depot: String,
     load: Vec<Load>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct ConsolidatedEndDepot {
+    depot: String,
+    replaced_by: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct UnservedDemand {
+    location: String,
+    bucket_start: String,
+    bucket_end: String,
+    unserved_passengers: u32,
+    unserved_seated_passengers: u32,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct Load {
     vehicle_type: String,
     spawn_count: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonFleet {
     vehicle_type: String,
     vehicles: Vec<JsonVehicle>,
     vehicle_cycles: Vec<Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonVehicle {
     id: String,
+    roster_unit: Option<String>,
     start_depot: String,
     end_depot: String,
+    repositioning_depot: Option<String>,
     departure_segments: Vec<JsonFleetDepartureSegment>,
     maintenance_slots: Vec<JsonFleetMaintenanceSlot>,
     dead_head_trips: Vec<JsonFleetDeadHeadTrip>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonFleetDepartureSegment {
     departure_segment: String,
     origin: String,
@@ -74,8 +128,9 @@ struct JsonFleetDepartureSegment {
     arrival: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonFleetMaintenanceSlot {
     maintenance_slot: String,
     location: String,
@@ -83,8 +138,9 @@ struct JsonFleetMaintenanceSlot {
     end: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonFleetDeadHeadTrip {
     id: String,
     origin: String,
@@ -93,8 +149,9 @@ struct JsonFleetDeadHeadTrip {
     arrival: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonDepartureSegmentWithFormation {
     departure_segment: String,
     origin: String,
@@ -105,8 +162,9 @@ struct JsonDepartureSegmentWithFormation {
     formation: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonFleetMaintenanceSlotWithFormation {
     maintenance_slot: String,
     location: String,
@@ -115,8 +173,9 @@ struct JsonFleetMaintenanceSlotWithFormation {
     formation: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
 struct JsonFleetDeadHeadTripWithFormation {
     id: String,
     origin: String,
@@ -126,26 +185,102 @@ struct JsonFleetDeadHeadTripWithFormation {
     formation: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct JsonGanttBar {
+    vehicle: String,
+    vehicle_type: String,
+    start: String,
+    end: String,
+    origin: String,
+    destination: String,
+    activity: String,
+    label: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct JsonSimulationEvent {
+    time: String,
+    vehicle: String,
+    vehicle_type: String,
+    event: String,
+    location: String,
+    label: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct JsonAssignedVehicle {
+    vehicle: String,
+    vehicle_type: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct JsonValidityReport {
+    feasible: bool,
+    hard_violations: Vec<JsonViolation>,
+    soft_violations: Vec<JsonViolation>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+struct JsonViolation {
+    kind: String,
+    count: usize,
+    ids: Vec<String>,
+}
+
 pub fn schedule_to_json(schedule: &Schedule) -> serde_json::Value {
     let mut dead_head_trips: Vec<JsonFleetDeadHeadTripWithFormation> = vec![];
     let mut fleet = vec![];
-    for vehicle_type in schedule.get_network().vehicle_types().iter() {
-        fleet.push(fleet_to_json(schedule, vehicle_type, &mut dead_head_trips));
+    for vehicle_type in schedule.get_network().vehicle_types().iter_with_details() {
+        fleet.push(fleet_to_json(schedule, &vehicle_type, &mut dead_head_trips));
     }
     let schedule_json = ScheduleJson {
         depot_loads: depots_usage_to_json(schedule),
+        consolidated_end_depots: consolidated_end_depots_to_json(schedule),
+        unserved_demand: unserved_demand_to_json(schedule),
         fleet,
         departure_segments: departure_segments_to_json(schedule),
         maintenance_slots: maintenance_slots_to_json(schedule),
         dead_head_trips,
+        gantt: gantt_to_json(schedule),
+        simulation_events: simulation_events_to_json(schedule),
+        assignments: assignments_to_json(schedule),
+        validity: validity_report_to_json(schedule),
     };
     serde_json::to_value(schedule_json).unwrap()
 }
 
+pub fn statistics_to_json(statistics: &ScheduleStatistics) -> serde_json::Value {
+    let summary_json = SummaryJson {
+        number_of_vehicles: statistics.number_of_vehicles,
+        number_of_dummy_tours: statistics.number_of_dummy_tours,
+        total_distance_in_meter: statistics.total_distance.in_meter().unwrap_or(u64::MAX),
+        service_distance_in_meter: statistics.service_distance.in_meter().unwrap_or(u64::MAX),
+        dead_head_distance_in_meter: statistics.dead_head_distance.in_meter().unwrap_or(u64::MAX),
+        total_overhead_duration_in_sec: statistics
+            .total_overhead_duration
+            .in_sec()
+            .unwrap_or(u64::MAX),
+        unserved_passengers: statistics.unserved_passengers.0,
+        unserved_seated_passengers: statistics.unserved_passengers.1,
+        depot_balance_violation: statistics.depot_balance_violation,
+    };
+    serde_json::to_value(summary_json).unwrap()
+}
+
 fn depots_usage_to_json(schedule: &Schedule) -> Vec<DepotLoad> {
     let mut depot_loads = vec![];
     let network = schedule.get_network();
-    for depot_idx in network.depots_iter() {
+    for depot_idx in network.depots_iter_sorted() {
         let depot = network.get_depot(depot_idx);
         depot_loads.push(DepotLoad {
             depot: depot.id().to_string(),
@@ -155,10 +290,55 @@ fn depots_usage_to_json(schedule: &Schedule) -> Vec<DepotLoad> {
     depot_loads
 }
 
+fn consolidated_end_depots_to_json(schedule: &Schedule) -> Vec<ConsolidatedEndDepot> {
+    let network = schedule.get_network();
+    network
+        .dominated_end_depots()
+        .map(|(depot_idx, kept_depot_idx)| ConsolidatedEndDepot {
+            depot: network.get_depot(depot_idx).id().to_string(),
+            replaced_by: network.get_depot(kept_depot_idx).id().to_string(),
+        })
+        .collect()
+}
+
+/// Groups the unserved (seated) passengers of all service trips by start location and time bucket.
+/// Buckets have the size given by the config and are aligned to the start of the time horizon.
+/// Groups without any unserved passengers are omitted.
+fn unserved_demand_to_json(schedule: &Schedule) -> Vec<UnservedDemand> {
+    let network = schedule.get_network();
+    let horizon_start = network.time_horizon().0;
+    let bucket_in_sec = network.config().unserved_demand_bucket.in_sec().unwrap();
+
+    let mut groups: BTreeMap<(String, DateTime), (u32, u32)> = BTreeMap::new();
+    for (node_idx, (unserved, unserved_seated)) in schedule.unserved_passengers_iter() {
+        let node = network.node(node_idx);
+        let offset_in_sec = (node.start_time() - horizon_start).in_sec().unwrap();
+        let bucket_start =
+            horizon_start + Duration::from_seconds(offset_in_sec / bucket_in_sec * bucket_in_sec);
+        let location = network.locations().get_id(node.start_location()).unwrap();
+        let entry = groups.entry((location, bucket_start)).or_insert((0, 0));
+        entry.0 += unserved;
+        entry.1 += unserved_seated;
+    }
+
+    groups
+        .into_iter()
+        .map(
+            |((location, bucket_start), (unserved, unserved_seated))| UnservedDemand {
+                location,
+                bucket_start: bucket_start.as_iso(),
+                bucket_end: (bucket_start + Duration::from_seconds(bucket_in_sec)).as_iso(),
+                unserved_passengers: unserved,
+                unserved_seated_passengers: unserved_seated,
+            },
+        )
+        .collect()
+}
+
 fn depot_usage_to_json(schedule: &Schedule, depot_idx: DepotIdx) -> Vec<Load> {
     let mut loads = vec![];
     let network = schedule.get_network();
-    for vehicle_type in schedule.get_vehicle_types().iter() {
+    for vehicle_type in schedule.get_vehicle_types().iter_sorted_by_id() {
         let spawn_count =
             schedule.number_of_vehicles_of_same_type_spawned_at(depot_idx, vehicle_type);
         if spawn_count > 0 {
@@ -178,11 +358,11 @@ fn depot_usage_to_json(schedule: &Schedule, depot_idx: DepotIdx) -> Vec<Load> {
 
 fn fleet_to_json(
     schedule: &Schedule,
-    vehicle_type: VehicleTypeIdx,
+    vehicle_type: &VehicleType,
     dead_head_trips_with_formation: &mut Vec<JsonFleetDeadHeadTripWithFormation>,
 ) -> JsonFleet {
     let mut vehicles = vec![];
-    for vehicle_idx in schedule.vehicles_iter(vehicle_type) {
+    for vehicle_idx in schedule.vehicles_iter(vehicle_type.idx()) {
         vehicles.push(vehicle_to_json(
             schedule,
             vehicle_idx,
@@ -190,7 +370,10 @@ fn fleet_to_json(
         ));
     }
     let mut vehicle_cycles = vec![];
-    for transtion_cylce in schedule.next_day_transition_of(vehicle_type).cycles_iter() {
+    for transtion_cylce in schedule
+        .next_day_transition_of(vehicle_type.idx())
+        .cycles_iter()
+    {
         vehicle_cycles.push(
             transtion_cylce
                 .iter()
@@ -199,13 +382,7 @@ fn fleet_to_json(
         );
     }
     JsonFleet {
-        vehicle_type: schedule
-            .get_network()
-            .vehicle_types()
-            .get(vehicle_type)
-            .unwrap()
-            .id()
-            .clone(),
+        vehicle_type: vehicle_type.id().clone(),
         vehicles,
         vehicle_cycles,
     }
@@ -280,10 +457,60 @@ fn vehicle_to_json(
             _ => {}
         }
     }
+    let repositioning_depot_node = schedule.tour_of(vehicle_idx).unwrap().repositioning();
+    if let Some(repositioning_depot_node) = repositioning_depot_node {
+        let last_non_depot_idx = schedule
+            .tour_of(vehicle_idx)
+            .unwrap()
+            .last_non_depot()
+            .unwrap();
+        let last_non_depot = network.node(last_non_depot_idx);
+        let departure_time =
+            if last_non_depot.end_location() != network.node(end_depot_node).start_location() {
+                schedule_dead_head_trip(last_non_depot_idx, end_depot_node, &network).1
+            } else {
+                last_non_depot.end_time()
+            };
+        let arrival_time = departure_time
+            + network.dead_head_time_between(end_depot_node, repositioning_depot_node);
+        let dead_head_trip = JsonFleetDeadHeadTrip {
+            id: "dht_".to_string() + &dead_head_trips_counter.to_string(),
+            origin: network
+                .locations()
+                .get_id(network.node(end_depot_node).end_location())
+                .unwrap(),
+            destination: network
+                .locations()
+                .get_id(network.node(repositioning_depot_node).start_location())
+                .unwrap(),
+            departure: departure_time.as_iso(),
+            arrival: arrival_time.as_iso(),
+        };
+        dead_head_trips_with_formation.push(JsonFleetDeadHeadTripWithFormation {
+            id: dead_head_trip.id.clone(),
+            origin: dead_head_trip.origin.clone(),
+            destination: dead_head_trip.destination.clone(),
+            departure: dead_head_trip.departure.clone(),
+            arrival: dead_head_trip.arrival.clone(),
+            formation: vec![vehicle_idx.to_string()],
+        });
+        dead_head_trips.push(dead_head_trip);
+    }
     JsonVehicle {
         id: vehicle_idx.to_string(),
+        roster_unit: schedule
+            .get_vehicle(vehicle_idx)
+            .unwrap()
+            .roster_unit()
+            .map(|unit| network.vehicle_types().roster_unit(unit).id().clone()),
         start_depot: start_depot.id().to_string(),
         end_depot: end_depot.id().to_string(),
+        repositioning_depot: repositioning_depot_node.map(|depot_node| {
+            network
+                .get_depot(network.get_depot_idx(depot_node))
+                .id()
+                .to_string()
+        }),
         departure_segments,
         maintenance_slots,
         dead_head_trips,
@@ -293,8 +520,8 @@ fn vehicle_to_json(
 fn departure_segments_to_json(schedule: &Schedule) -> Vec<JsonDepartureSegmentWithFormation> {
     let network = schedule.get_network();
     let mut departure_segments = vec![];
-    for vehicle_type in network.vehicle_types().iter() {
-        for service_trip_node_idx in network.service_nodes(vehicle_type) {
+    for vehicle_type in network.vehicle_types().iter_with_details() {
+        for service_trip_node_idx in network.service_nodes(vehicle_type.idx()) {
             let service_trip_node = network.node(service_trip_node_idx);
             let service_trip = service_trip_node.as_service_trip();
 
@@ -311,12 +538,7 @@ fn departure_segments_to_json(schedule: &Schedule) -> Vec<JsonDepartureSegmentWi
                     .unwrap(),
                 departure: service_trip_node.start_time().as_iso(),
                 arrival: service_trip_node.end_time().as_iso(),
-                vehicle_type: network
-                    .vehicle_types()
-                    .get(vehicle_type)
-                    .unwrap()
-                    .id()
-                    .clone(),
+                vehicle_type: vehicle_type.id().clone(),
                 formation: formation
                     .iter()
                     .map(|vehicle| vehicle.idx().to_string())
@@ -328,6 +550,75 @@ fn departure_segments_to_json(schedule: &Schedule) -> Vec<JsonDepartureSegmentWi
     departure_segments
 }
 
+/// Vehicles (with their types) assigned to each service trip, keyed by the original id of the
+/// service trip. Uncovered service trips map to an empty list.
+fn assignments_to_json(schedule: &Schedule) -> BTreeMap<String, Vec<JsonAssignedVehicle>> {
+    let network = schedule.get_network();
+    network
+        .all_service_nodes()
+        .map(|service_trip| {
+            let vehicles: Vec<JsonAssignedVehicle> = schedule
+                .train_formation_of(service_trip)
+                .iter()
+                .map(|vehicle| JsonAssignedVehicle {
+                    vehicle: vehicle.idx().to_string(),
+                    vehicle_type: network
+                        .vehicle_types()
+                        .get(vehicle.type_idx())
+                        .unwrap()
+                        .id()
+                        .clone(),
+                })
+                .collect();
+            (network.original_id_of(service_trip).to_string(), vehicles)
+        })
+        .collect()
+}
+
+/// Lists each kind of violation of the validity report (also if there is none), with the ids of
+/// the offending vehicles, depots or nodes as given in the output or input.
+fn validity_report_to_json(schedule: &Schedule) -> JsonValidityReport {
+    let network = schedule.get_network();
+    let report = schedule.validity_report();
+    let violation = |kind: &str, ids: Vec<String>| JsonViolation {
+        kind: kind.to_string(),
+        count: ids.len(),
+        ids,
+    };
+    let vehicle_ids = |vehicles: &[VehicleIdx]| -> Vec<String> {
+        vehicles.iter().map(|v| v.to_string()).collect()
+    };
+    let depot_ids = |depots: &[DepotIdx]| -> Vec<String> {
+        depots
+            .iter()
+            .map(|&depot| network.get_depot(depot).id().to_string())
+            .collect()
+    };
+    let node_ids = |nodes: &[NodeIdx]| -> Vec<String> {
+        nodes
+            .iter()
+            .map(|&node| network.original_id_of(node).to_string())
+            .collect()
+    };
+
+    JsonValidityReport {
+        feasible: report.is_feasible(),
+        hard_violations: vec![
+            violation("unreachableTour", vehicle_ids(&report.unreachable_tours)),
+            violation("overCapacityDepot", depot_ids(&report.over_capacity_depots)),
+            violation(
+                "overLengthFormation",
+                node_ids(&report.over_length_formations),
+            ),
+        ],
+        soft_violations: vec![
+            violation("depotBalance", depot_ids(&report.unbalanced_depots)),
+            violation("maintenance", vehicle_ids(&report.maintenance_violations)),
+            violation("idleVehicle", vehicle_ids(&report.idle_vehicles)),
+        ],
+    }
+}
+
 fn maintenance_slots_to_json(schedule: &Schedule) -> Vec<JsonFleetMaintenanceSlotWithFormation> {
     let network = schedule.get_network();
     let mut maintenance_slots = vec![];
@@ -353,6 +644,64 @@ fn maintenance_slots_to_json(schedule: &Schedule) -> Vec<JsonFleetMaintenanceSlo
     maintenance_slots
 }
 
+fn gantt_to_json(schedule: &Schedule) -> Vec<JsonGanttBar> {
+    let network = schedule.get_network();
+    schedule
+        .export_gantt_data()
+        .into_iter()
+        .map(|bar| JsonGanttBar {
+            vehicle: bar.vehicle.to_string(),
+            vehicle_type: network
+                .vehicle_types()
+                .get(schedule.vehicle_type_of(bar.vehicle).unwrap())
+                .unwrap()
+                .id()
+                .clone(),
+            start: bar.start.as_iso(),
+            end: bar.end.as_iso(),
+            origin: network.locations().get_id(bar.origin).unwrap(),
+            destination: network.locations().get_id(bar.destination).unwrap(),
+            activity: match bar.activity {
+                GanttActivity::Service => "service",
+                GanttActivity::DeadHead => "deadHead",
+                GanttActivity::Maintenance => "maintenance",
+                GanttActivity::Idle => "idle",
+            }
+            .to_string(),
+            label: bar.label,
+        })
+        .collect()
+}
+
+fn simulation_events_to_json(schedule: &Schedule) -> Vec<JsonSimulationEvent> {
+    let network = schedule.get_network();
+    schedule
+        .export_for_simulation()
+        .into_iter()
+        .map(|event| JsonSimulationEvent {
+            time: event.time.as_iso(),
+            vehicle: event.vehicle.to_string(),
+            vehicle_type: network
+                .vehicle_types()
+                .get(schedule.vehicle_type_of(event.vehicle).unwrap())
+                .unwrap()
+                .id()
+                .clone(),
+            event: match event.kind {
+                SimulationEventKind::Arrive => "arrive",
+                SimulationEventKind::Decouple => "decouple",
+                SimulationEventKind::EndMaintenance => "endMaintenance",
+                SimulationEventKind::StartMaintenance => "startMaintenance",
+                SimulationEventKind::Couple => "couple",
+                SimulationEventKind::Depart => "depart",
+            }
+            .to_string(),
+            location: network.locations().get_id(event.location).unwrap(),
+            label: event.label,
+        })
+        .collect()
+}
+
 fn schedule_dead_head_trip(
     node1_idx: NodeIdx,
     node2_idx: NodeIdx,
@@ -21,4 +21,7 @@ mod train_formation;
 pub mod transition;
 mod vehicle;
 
-pub use schedule::Schedule;
+pub use schedule::{
+    GanttActivity, GanttBar, Schedule, ScheduleError, ScheduleStatistics, SimulationEvent,
+    SimulationEventKind, ValidityReport,
+};
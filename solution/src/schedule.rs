@@ -11,24 +11,35 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod gantt;
 mod modifications;
+mod perturbation;
+mod schedule_error;
+mod simulation;
+mod statistics;
 #[cfg(test)]
 mod tests;
+mod validity_report;
 
 use itertools::Itertools;
 use model::base_types::Cost;
 use model::base_types::DepotIdx;
 use model::base_types::Distance;
 use model::base_types::MaintenanceCounter;
+use model::base_types::Meter;
 use model::base_types::NodeIdx;
 use model::base_types::PassengerCount;
 use model::base_types::VehicleCount;
 use model::base_types::VehicleIdx;
 use model::base_types::VehicleTypeIdx;
+use model::config::ReferenceAssignment;
 use model::network::nodes::Node;
 use model::network::Network;
 use model::vehicle_types::VehicleTypes;
+use rapid_time::{DateTime, Duration};
 
+use crate::path::Path;
+use crate::segment::Segment;
 use crate::tour::Tour;
 use crate::train_formation::TrainFormation;
 use crate::transition::Transition;
@@ -37,9 +48,19 @@ use crate::vehicle::Vehicle;
 use im::HashMap;
 use im::HashSet;
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::collections::HashMap as StdHashMap;
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+pub use gantt::{GanttActivity, GanttBar};
+pub use schedule_error::ScheduleError;
+pub use simulation::{SimulationEvent, SimulationEventKind};
+pub use statistics::ScheduleStatistics;
+pub use validity_report::ValidityReport;
+
 type DepotUsage = HashMap<(DepotIdx, VehicleTypeIdx), (HashSet<VehicleIdx>, HashSet<VehicleIdx>)>;
 
 // this represents a solution to the rolling stock problem.
@@ -157,6 +178,30 @@ impl Schedule {
         self.maintenance_violation
     }
 
+    /// Summed distance the real vehicles travel beyond their maximal distance between maintenance
+    /// within their own tour. Tours visiting a maintenance slot contribute their positive
+    /// maintenance counter, all other tours the part of their total distance exceeding the range of
+    /// their vehicle type. Unlike maintenance_violation, no distance is carried over to the next
+    /// period via the transitions.
+    pub fn total_maintenance_distance_violation(&self) -> Distance {
+        self.tours
+            .iter()
+            .map(|(&vehicle, tour)| self.maintenance_distance_violation_of(vehicle, tour))
+            .sum()
+    }
+
+    /// Returns the number of vehicles whose tour does not visit any maintenance slot, if a daily
+    /// maintenance visit is mandatory (see config). Otherwise 0.
+    pub fn missing_mandatory_maintenance(&self) -> VehicleCount {
+        if !self.network.config().maintenance.mandatory_daily_visit {
+            return 0;
+        }
+        self.tours
+            .values()
+            .filter(|tour| !tour.visits_maintenance())
+            .count() as VehicleCount
+    }
+
     pub fn next_day_transition_of(&self, vehicle_type: VehicleTypeIdx) -> &Transition {
         self.next_period_transitions.get(&vehicle_type).unwrap()
     }
@@ -170,6 +215,85 @@ impl Schedule {
         new_schedule
     }
 
+    /// Replaces the next-day transition of the vehicle type of the given transition, e.g., with
+    /// the improved cycles of the transition optimizer, and materializes it: for each cycle that
+    /// still violates the maintenance limit, one of its vehicles without maintenance is routed
+    /// through a maintenance slot with a free track (the slot that reduces the maintenance
+    /// violation the most). Only insertions that do not displace other nodes of the tour are
+    /// considered.
+    /// Fails if the transition does not contain each vehicle of one vehicle type exactly once, or
+    /// if a violating cycle cannot be routed through any maintenance slot without exceeding its
+    /// track count.
+    pub fn apply_transition(&self, transition: &Transition) -> Result<Schedule, String> {
+        let vehicles: Vec<VehicleIdx> = transition
+            .cycles_iter()
+            .flat_map(|cycle| cycle.iter())
+            .collect();
+        let first_vehicle = vehicles
+            .first()
+            .ok_or_else(|| String::from("Transition contains no vehicle."))?;
+        let vehicle_type = self.vehicle_type_of(*first_vehicle)?;
+        let vehicles_of_type: HashSet<VehicleIdx> = self.vehicles_iter(vehicle_type).collect();
+        let vehicles_of_transition: HashSet<VehicleIdx> = vehicles.iter().copied().collect();
+        if vehicles_of_transition.len() != vehicles.len()
+            || vehicles_of_transition != vehicles_of_type
+        {
+            return Err(format!(
+                "Transition does not contain each vehicle of type {} exactly once.",
+                self.network.vehicle_types().get(vehicle_type).unwrap()
+            ));
+        }
+
+        let mut new_schedule = self.clone();
+        new_schedule.maintenance_violation += transition.maintenance_violation()
+            - self
+                .next_day_transition_of(vehicle_type)
+                .maintenance_violation();
+        new_schedule
+            .next_period_transitions
+            .insert(vehicle_type, transition.clone());
+
+        // the cycle indices stay the same when tours of vehicles are updated
+        for cycle_idx in 0..transition.number_of_cycles() {
+            let cycle = new_schedule
+                .next_day_transition_of(vehicle_type)
+                .get_cycle(cycle_idx)
+                .clone();
+            if cycle.maintenance_counter() <= 0 {
+                continue;
+            }
+            let free_maintenance_slots: Vec<NodeIdx> = self
+                .network
+                .maintenance_nodes()
+                .filter(|&m| {
+                    new_schedule.train_formation_of(m).vehicle_count()
+                        < self.network.track_count_of_maintenance_slot(m)
+                })
+                .collect();
+            new_schedule = cycle
+                .iter()
+                .filter(|&vehicle| !new_schedule.tour_of(vehicle).unwrap().visits_maintenance())
+                .cartesian_product(free_maintenance_slots)
+                .filter_map(|(vehicle, maintenance_slot)| {
+                    match new_schedule.add_path_to_vehicle_tour(
+                        vehicle,
+                        Path::new_from_single_node(maintenance_slot, self.network.clone()),
+                    ) {
+                        Ok((schedule, None)) => Some(schedule),
+                        _ => None,
+                    }
+                })
+                .min_by_key(|schedule| schedule.maintenance_violation())
+                .ok_or_else(|| {
+                    format!(
+                        "{} cannot be routed through a maintenance slot with a free track.",
+                        cycle
+                    )
+                })?;
+        }
+        Ok(new_schedule)
+    }
+
     pub fn train_formation_of(&self, node: NodeIdx) -> &TrainFormation {
         self.train_formations.get(&node).unwrap()
     }
@@ -194,18 +318,65 @@ impl Schedule {
     /// Returns the number of vehicles of the given type that are spawned at the given depot - the
     /// number of vehicles of the given type that despawn at the given depot.
     /// Hence, negative values mean that there are more vehicles despawning than spawning.
+    /// Dominated end depots are consolidated with the depot they despawn at, i.e., the balance
+    /// covers the spawns at all depots sharing the same end depot.
     pub fn depot_balance(&self, depot: DepotIdx, vehicle_type: VehicleTypeIdx) -> i32 {
-        self.depot_usage
-            .get(&(depot, vehicle_type))
-            .map(|(spawned, despawned)| (spawned.len() as i32 - despawned.len() as i32))
-            .unwrap_or(0)
+        let end_depot = self.network.end_depot_for(depot);
+        let spawned: usize = self
+            .network
+            .depots_iter()
+            .filter(|&d| self.network.end_depot_for(d) == end_depot)
+            .filter_map(|d| self.depot_usage.get(&(d, vehicle_type)))
+            .map(|(spawned, _)| spawned.len())
+            .sum();
+        let despawned = self
+            .depot_usage
+            .get(&(end_depot, vehicle_type))
+            .map(|(_, despawned)| despawned.len())
+            .unwrap_or(0);
+        spawned as i32 - despawned as i32
+    }
+
+    /// Number of vehicles stabled at the given depot over time as a step function. Each entry
+    /// is a point in time and the number of vehicles at the depot from then on until the next
+    /// entry (the first entry is at DateTime::Earliest). Vehicles spawned at the depot are there
+    /// until they leave for their first node, vehicles despawned at the depot from their
+    /// arrival on.
+    pub fn vehicles_at_depot_over_time(&self, depot: DepotIdx) -> Vec<(DateTime, VehicleCount)> {
+        let mut count: i64 = 0;
+        let mut changes: BTreeMap<DateTime, i64> = BTreeMap::new();
+        for vehicle in self.vehicles_iter_all() {
+            let tour = &self.tours[&vehicle];
+            if self.network.get_depot_idx(tour.start_depot().unwrap()) == depot {
+                count += 1;
+                *changes.entry(tour.start_time()).or_default() -= 1;
+            }
+            if self.network.get_depot_idx(tour.end_depot().unwrap()) == depot {
+                *changes.entry(tour.end_time()).or_default() += 1;
+            }
+        }
+
+        let mut timeline = vec![(DateTime::Earliest, count as VehicleCount)];
+        for (time, change) in changes.into_iter().filter(|(_, change)| *change != 0) {
+            count += change;
+            match timeline.last_mut() {
+                Some((last_time, last_count)) if *last_time == time => {
+                    *last_count = count as VehicleCount
+                }
+                _ => timeline.push((time, count as VehicleCount)),
+            }
+        }
+        timeline
     }
 
     pub fn total_depot_balance_violation(&self) -> VehicleCount {
         self.depot_usage
             .keys()
+            .map(|(depot, vehicle_type)| (self.network.end_depot_for(*depot), *vehicle_type))
+            .collect::<HashSet<_>>()
+            .into_iter()
             .map(|(depot, vehicle_type)| {
-                self.depot_balance(*depot, *vehicle_type).unsigned_abs() as VehicleCount
+                self.depot_balance(depot, vehicle_type).unsigned_abs() as VehicleCount
             })
             .sum()
     }
@@ -218,6 +389,57 @@ impl Schedule {
         self.can_depot_spawn_vehicle_custom_usage(start_depot, vehicle_type, &self.depot_usage)
     }
 
+    /// Returns the number of vehicles that do not start and end at the same home depot, i.e.,
+    /// whose start depot differs from their end depot or whose depot is not a home depot of their
+    /// vehicle type (if home depots are declared).
+    pub fn non_home_spawn_penalty(&self) -> VehicleCount {
+        let vehicle_types = self.network.vehicle_types();
+        self.vehicles
+            .values()
+            .filter(|vehicle| {
+                let tour = self.tours.get(&vehicle.idx()).unwrap();
+                let start_depot = self.network.get_depot_idx(tour.start_depot().unwrap());
+                let end_depot = self.network.get_depot_idx(tour.end_depot().unwrap());
+                self.network.end_depot_for(start_depot) != end_depot
+                    || !vehicle_types
+                        .get(vehicle.type_idx())
+                        .unwrap()
+                        .is_home_depot(start_depot)
+            })
+            .count() as VehicleCount
+    }
+
+    /// Returns the number of vehicles whose rotation is open, i.e., that do not end at the depot
+    /// they started from.
+    pub fn closed_rotation_violation(&self) -> VehicleCount {
+        self.interday_repositioning().len() as VehicleCount
+    }
+
+    /// Lists the repositioning needed to close the open rotations: for each real vehicle that
+    /// does not end at the depot it started from, the end depot, the start depot and the dead head
+    /// distance from the end depot back to the start depot. Sorted by vehicle.
+    pub fn interday_repositioning(&self) -> Vec<(VehicleIdx, DepotIdx, DepotIdx, Distance)> {
+        self.tours
+            .iter()
+            .filter_map(|(&vehicle, tour)| {
+                let start_depot_node = tour.start_depot().unwrap();
+                let end_depot_node = tour.end_depot().unwrap();
+                let start_depot = self.network.get_depot_idx(start_depot_node);
+                if self.network.get_end_depot_node(start_depot) == end_depot_node {
+                    return None;
+                }
+                Some((
+                    vehicle,
+                    self.network.get_depot_idx(end_depot_node),
+                    start_depot,
+                    self.network
+                        .dead_head_distance_between(end_depot_node, start_depot_node),
+                ))
+            })
+            .sorted_by_key(|(vehicle, _, _, _)| *vehicle)
+            .collect()
+    }
+
     pub fn reduces_spawning_at_depot_violation(
         &self,
         vehicle_type: VehicleTypeIdx,
@@ -250,10 +472,313 @@ impl Schedule {
         self.unserved_passengers
     }
 
+    /// Number of preferred successor hints of the network that are not honored, i.e., no vehicle
+    /// covering the service trip continues directly with its preferred successor.
+    pub fn unhonored_preferred_successors(&self) -> usize {
+        self.network
+            .preferred_successors_iter()
+            .filter(|&(service_trip, successor)| {
+                !self
+                    .train_formation_of(service_trip)
+                    .ids()
+                    .iter()
+                    .any(|&vehicle| {
+                        self.tour_of(vehicle).unwrap().successor_of(service_trip) == Some(successor)
+                    })
+            })
+            .count()
+    }
+
+    /// Number of service trips that are operated by fewer vehicles than their required minimum
+    /// (see ServiceTrip::min_vehicles). Independent of the seat coverage.
+    pub fn min_vehicles_violation(&self) -> usize {
+        self.network
+            .all_service_nodes()
+            .filter(|&service_trip| {
+                self.network
+                    .node(service_trip)
+                    .as_service_trip()
+                    .min_vehicles()
+                    .is_some_and(|min_vehicles| {
+                        self.train_formation_of(service_trip).vehicle_count() < min_vehicles
+                    })
+            })
+            .count()
+    }
+
+    /// Returns all vehicles (sorted) whose tour contains no service trip, e.g., a vehicle that
+    /// only visits a maintenance slot. Such vehicles serve no passengers but cause costs.
+    /// (Vehicles that never leave their depot cannot exist, as a tour needs a non-depot node.)
+    pub fn idle_vehicles(&self) -> Vec<VehicleIdx> {
+        self.vehicles_iter_all()
+            .filter(|vehicle| {
+                !self.tours[vehicle]
+                    .all_non_depot_nodes_iter()
+                    .any(|node| self.network.node(node).is_service())
+            })
+            .sorted()
+            .collect()
+    }
+
     pub fn is_fully_covered(&self, service_trip: NodeIdx) -> bool {
         self.unserved_passengers_at(service_trip) == (0, 0)
     }
 
+    /// Iterates over all service trips (sorted by start time) that are fully covered.
+    pub fn covered_service_trips_iter(&self) -> impl Iterator<Item = NodeIdx> + '_ {
+        self.network
+            .all_service_nodes()
+            .filter(|&service_trip| self.is_fully_covered(service_trip))
+    }
+
+    /// Iterates over all service trips (sorted by start time) that are not fully covered.
+    pub fn unserved_service_trips(&self) -> impl Iterator<Item = NodeIdx> + '_ {
+        self.network
+            .all_service_nodes()
+            .filter(|&service_trip| !self.is_fully_covered(service_trip))
+    }
+
+    /// Iterates over all service trips (sorted by start time) that are not fully covered together
+    /// with their deficit, i.e., the passengers that do not fit (first entry) and the seated
+    /// passengers that cannot sit (second entry), as in unserved_passengers_at.
+    pub fn unserved_passengers_iter(
+        &self,
+    ) -> impl Iterator<Item = (NodeIdx, (PassengerCount, PassengerCount))> + '_ {
+        self.unserved_service_trips()
+            .map(|service_trip| (service_trip, self.unserved_passengers_at(service_trip)))
+    }
+
+    /// Iterates over all service trips (sorted by start time) that are covered by at least one
+    /// vehicle but not fully, together with their deficit, i.e., the larger of the passengers
+    /// that do not fit and the seated passengers that cannot sit (see unserved_passengers_at).
+    pub fn partially_served_service_trips(
+        &self,
+    ) -> impl Iterator<Item = (NodeIdx, PassengerCount)> + '_ {
+        self.unserved_passengers_iter()
+            .filter(|&(service_trip, _)| self.train_formation_of(service_trip).vehicle_count() > 0)
+            .map(|(service_trip, (unfit, unseated))| (service_trip, unfit.max(unseated)))
+    }
+
+    /// Number of additional vehicles needed concurrently to fully cover the uncovered service
+    /// trips over time as a step function (same format as vehicles_at_depot_over_time). Each
+    /// uncovered service trip needs enough additional vehicles of its vehicle type for its unfit
+    /// and unseated passengers from its start until its end time. The entry with the largest
+    /// count is the period in which the fleet shortfall is binding.
+    pub fn shortfall_timeline(&self) -> Vec<(DateTime, VehicleCount)> {
+        let vehicles_needed = |unserved: PassengerCount, per_vehicle: PassengerCount| {
+            if per_vehicle == 0 {
+                0
+            } else {
+                unserved.div_ceil(per_vehicle) as i64
+            }
+        };
+        let mut changes: BTreeMap<DateTime, i64> = BTreeMap::new();
+        for (service_trip, (unfit, unseated)) in self.unserved_passengers_iter() {
+            let vehicle_type = self
+                .network
+                .vehicle_types()
+                .get(self.network.vehicle_type_for(service_trip))
+                .unwrap();
+            let additional_vehicles = vehicles_needed(unfit, vehicle_type.capacity())
+                .max(vehicles_needed(unseated, vehicle_type.seats()));
+            let node = self.network.node(service_trip);
+            *changes.entry(node.start_time()).or_default() += additional_vehicles;
+            *changes.entry(node.end_time()).or_default() -= additional_vehicles;
+        }
+
+        let mut count: i64 = 0;
+        let mut timeline = vec![(DateTime::Earliest, 0)];
+        for (time, change) in changes.into_iter().filter(|(_, change)| *change != 0) {
+            count += change;
+            timeline.push((time, count as VehicleCount));
+        }
+        timeline
+    }
+
+    /// Returns all vehicles whose tour passes through the given node, i.e., the tour either
+    /// covers the node or has two consecutive nodes such that the node could be inserted in
+    /// between without conflicts. For service trips only vehicles of an allowed type are
+    /// considered. Dummy tours are ignored.
+    pub fn vehicles_passing_through(&self, node: NodeIdx) -> Vec<VehicleIdx> {
+        self.vehicles_iter_all()
+            .filter(|&vehicle| {
+                self.network
+                    .compatible_with_vehicle_type(node, self.vehicle_type_of(vehicle).unwrap())
+            })
+            .filter(|vehicle| {
+                let tour = self.tours.get(vehicle).unwrap();
+                match tour.latest_not_reaching_node(node) {
+                    None => false,
+                    Some(pos) => {
+                        let next = tour.nth_node(pos).unwrap();
+                        next == node || (pos > 0 && self.network.can_reach(node, next))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Number of service trips whose vehicles (matched by id) differ from the ones in the
+    /// reference schedule, e.g., the previous plan of a rolling re-optimization.
+    /// Dummy tours are ignored, i.e., an unserved trip is only counted if it is served in the
+    /// other schedule.
+    pub fn assignment_distance(&self, reference: &Schedule) -> usize {
+        self.network
+            .all_service_nodes()
+            .filter(|&service_trip| {
+                let vehicles: HashSet<VehicleIdx> = self
+                    .train_formation_of(service_trip)
+                    .ids()
+                    .into_iter()
+                    .collect();
+                let reference_vehicles: HashSet<VehicleIdx> = reference
+                    .train_formations
+                    .get(&service_trip)
+                    .map(|formation| formation.ids().into_iter().collect())
+                    .unwrap_or_default();
+                vehicles != reference_vehicles
+            })
+            .count()
+    }
+
+    /// Same as assignment_distance, but the reference is given as the ids of the vehicles per
+    /// service trip id (see Config::reference_assignment).
+    pub fn assignment_distance_to(&self, reference: &ReferenceAssignment) -> usize {
+        self.network
+            .all_service_nodes()
+            .filter(|&service_trip| {
+                let vehicles: HashSet<String> = self
+                    .train_formation_of(service_trip)
+                    .ids()
+                    .iter()
+                    .map(|vehicle| vehicle.to_string())
+                    .collect();
+                let reference_vehicles: HashSet<String> = reference
+                    .get(self.network.node(service_trip).as_service_trip().id())
+                    .map(|vehicles| vehicles.iter().cloned().collect())
+                    .unwrap_or_default();
+                vehicles != reference_vehicles
+            })
+            .count()
+    }
+
+    /// The ids of the vehicles operating each service trip (by id), e.g., as reference
+    /// for the next run of a rolling re-optimization.
+    pub fn reference_assignment(&self) -> ReferenceAssignment {
+        self.network
+            .all_service_nodes()
+            .filter(|&service_trip| self.train_formation_of(service_trip).vehicle_count() > 0)
+            .map(|service_trip| {
+                (
+                    self.network
+                        .node(service_trip)
+                        .as_service_trip()
+                        .id()
+                        .clone(),
+                    self.train_formation_of(service_trip)
+                        .ids()
+                        .iter()
+                        .map(|vehicle| vehicle.to_string())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// True if both schedules have the same vehicles (ids and types) with the same tours and the
+    /// same train formations on all nodes. In contrast to eq, dummy tours are ignored.
+    pub fn same_real_assignment(&self, other: &Schedule) -> bool {
+        self.vehicles_iter_all().eq(other.vehicles_iter_all())
+            && self.vehicles_iter_all().all(|vehicle| {
+                self.vehicle_type_of(vehicle) == other.vehicle_type_of(vehicle)
+                    && self.tour_of(vehicle) == other.tour_of(vehicle)
+            })
+            && self.train_formations.len() == other.train_formations.len()
+            && self.train_formations.iter().all(|(node, formation)| {
+                other
+                    .train_formations
+                    .get(node)
+                    .is_some_and(|other_formation| other_formation.ids() == formation.ids())
+            })
+    }
+
+    /// Hash of the vehicles (ids, types and next period successors), their tours and the dummy
+    /// tours. Identical schedules (see is_identical_to) have the same fingerprint, but different
+    /// schedules might collide. The nodes of a tour are hashed only once when the tour is created
+    /// (see Tour::nodes_hash), so for a neighbor only the changed tours are hashed.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for vehicle in self.vehicles_iter_all() {
+            let vehicle_type = self.vehicles[&vehicle].type_idx();
+            vehicle.hash(&mut hasher);
+            vehicle_type.hash(&mut hasher);
+            self.next_day_transition_of(vehicle_type)
+                .get_successor_of(vehicle)
+                .hash(&mut hasher);
+            self.tours[&vehicle].nodes_hash().hash(&mut hasher);
+        }
+        for dummy in self.dummy_ids_sorted.iter() {
+            dummy.hash(&mut hasher);
+            self.dummy_tours[dummy].nodes_hash().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// True if both schedules have the same vehicles (ids, types and next period successors) and
+    /// dummy tours (ids) with exactly the same nodes. (In contrast, eq ignores the vehicle ids
+    /// and compares the nodes of the tours only by their start times.)
+    pub fn is_identical_to(&self, other: &Schedule) -> bool {
+        let same_nodes =
+            |tour: &Tour, other_tour: &Tour| tour.all_nodes_iter().eq(other_tour.all_nodes_iter());
+        self.vehicles_iter_all().eq(other.vehicles_iter_all())
+            && self.vehicles_iter_all().all(|vehicle| {
+                let vehicle_type = self.vehicles[&vehicle].type_idx();
+                vehicle_type == other.vehicles[&vehicle].type_idx()
+                    && self
+                        .next_day_transition_of(vehicle_type)
+                        .get_successor_of(vehicle)
+                        == other
+                            .next_day_transition_of(vehicle_type)
+                            .get_successor_of(vehicle)
+                    && same_nodes(&self.tours[&vehicle], &other.tours[&vehicle])
+            })
+            && self.dummy_ids_sorted == other.dummy_ids_sorted
+            && self
+                .dummy_ids_sorted
+                .iter()
+                .all(|dummy| same_nodes(&self.dummy_tours[dummy], &other.dummy_tours[dummy]))
+    }
+
+    /// Returns all maintenance slots that could be added to the tour of the given vehicle
+    /// without removing any of its nodes. A slot qualifies if it is compatible with the
+    /// vehicle type, still has a free track, and fits between two consecutive nodes of the tour
+    /// (including the depots). Slots already visited by the vehicle are excluded.
+    /// The slots are returned sorted by start time.
+    pub fn maintenance_options_for(&self, vehicle: VehicleIdx) -> Vec<NodeIdx> {
+        let (Ok(tour), Ok(vehicle_type)) = (self.tour_of(vehicle), self.vehicle_type_of(vehicle))
+        else {
+            return Vec::new();
+        };
+        self.network
+            .maintenance_nodes()
+            .filter(|&maintenance| {
+                self.network
+                    .compatible_with_vehicle_type(maintenance, vehicle_type)
+            })
+            .filter(|&maintenance| {
+                self.train_formation_of(maintenance).vehicle_count()
+                    < self.network.track_count_of_maintenance_slot(maintenance)
+            })
+            .filter(|&maintenance| {
+                !tour.all_nodes_iter().contains(&maintenance)
+                    && tour
+                        .conflict(Segment::new(maintenance, maintenance))
+                        .is_none()
+            })
+            .sorted_by(|&a, &b| self.network.node(a).cmp_start_time(self.network.node(b)))
+            .collect()
+    }
+
     pub fn costs(&self) -> Cost {
         self.costs
     }
@@ -281,40 +806,129 @@ impl Schedule {
             .sum()
     }
 
+    pub fn total_service_distance(&self) -> Distance {
+        self.tours
+            .values()
+            .map(|tour| tour.service_distance())
+            .sum()
+    }
+
+    pub fn total_distance(&self) -> Distance {
+        self.tours.values().map(|tour| tour.total_distance()).sum()
+    }
+
+    pub fn total_overhead_duration(&self) -> Duration {
+        self.tours
+            .values()
+            .map(|tour| tour.total_overhead_duration())
+            .sum()
+    }
+
+    /// Summed time the vehicles are parked at depots, i.e., from the start of the time horizon
+    /// until the vehicle leaves its start depot and from its arrival at the end depot until the
+    /// end of the time horizon (from despawn to the next spawn). Idle time within tours is not
+    /// included.
+    pub fn total_idle_time_at_depots(&self) -> Duration {
+        let (horizon_start, horizon_end) = self.network.time_horizon();
+        self.tours
+            .values()
+            .map(|tour| {
+                let before_spawn = if tour.start_time() > horizon_start {
+                    tour.start_time() - horizon_start
+                } else {
+                    Duration::ZERO
+                };
+                let after_despawn = if tour.end_time() < horizon_end {
+                    horizon_end - tour.end_time()
+                } else {
+                    Duration::ZERO
+                };
+                before_spawn + after_despawn
+            })
+            .sum()
+    }
+
     pub fn print_tours(&self) {
+        print!("{}", self.format_tours());
+    }
+
+    /// Returns the tours of all vehicles and dummies, one per line, as printed by
+    /// [`Schedule::print_tours`].
+    pub fn format_tours(&self) -> String {
+        let mut output = String::new();
         for vehicle in self.vehicles_iter_all() {
-            println!(
+            writeln!(
+                output,
                 "{}: {}",
                 self.get_vehicle(vehicle).unwrap(),
                 self.tours.get(&vehicle).unwrap()
-            );
+            )
+            .unwrap();
         }
         for dummy in self.dummy_iter() {
-            println!("{}: {}", dummy, self.dummy_tours.get(&dummy).unwrap());
+            writeln!(
+                output,
+                "{}: {}",
+                dummy,
+                self.dummy_tours.get(&dummy).unwrap()
+            )
+            .unwrap();
         }
+        output
     }
 
     pub fn print_depot_balances(&self) {
-        for depot in self.network.depots_iter() {
-            for vehicle_type in self.network.vehicle_types().iter() {
-                println!(
+        print!("{}", self.format_depot_balances());
+    }
+
+    /// Returns the depot balance of each depot and vehicle type followed by the total
+    /// violation, as printed by [`Schedule::print_depot_balances`].
+    pub fn format_depot_balances(&self) -> String {
+        let mut output = String::new();
+        // dominated end depots are part of the balance of the depot they are consolidated into
+        for depot in self
+            .network
+            .depots_iter_sorted()
+            .filter(|&depot| self.network.end_depot_for(depot) == depot)
+        {
+            for vehicle_type in self.network.vehicle_types().iter_sorted_by_id() {
+                writeln!(
+                    output,
                     "  depot {}, vehicle type {}: {}",
                     depot,
                     vehicle_type,
                     self.depot_balance(depot, vehicle_type)
-                );
+                )
+                .unwrap();
             }
         }
-        println!(
+        writeln!(
+            output,
             "  total depot balance violation: {}",
             self.total_depot_balance_violation()
-        );
+        )
+        .unwrap();
+        output
     }
 
     pub fn print_train_formations(&self) {
+        print!("{}", self.format_train_formations());
+    }
+
+    /// Returns the train formation of each coverable node, one per line, as printed by
+    /// [`Schedule::print_train_formations`].
+    pub fn format_train_formations(&self) -> String {
+        let mut output = String::new();
         for node in self.network.coverable_nodes() {
-            println!("{}: {}", node, self.train_formations.get(&node).unwrap());
+            writeln!(
+                output,
+                "{}: {}",
+                node,
+                self.train_formations.get(&node).unwrap()
+            )
+            .unwrap();
         }
+        output
     }
 
     pub fn print_next_day_transitions(&self) {
@@ -360,9 +974,9 @@ impl Schedule {
 
         assert_eq!(vehicles, vehicles_from_tours);
         assert_eq!(vehicles, vehicles_from_sorted);
-        assert_eq!(vehicles, vehicles_from_train_formations); // we do not allow tours to not cover
-                                                              // anything, so each vehicle must be
-                                                              // in at least one formation
+        // tours without non-depot nodes are rejected by Tour::new (so each vehicle leaves its
+        // depot), hence each vehicle must be in at least one formation
+        assert_eq!(vehicles, vehicles_from_train_formations);
         assert_eq!(vehicles, vehicles_from_depot_usage);
 
         // check if vehicles are sorted
@@ -391,15 +1005,13 @@ impl Schedule {
             let tour = self.tours.get(vehicle).unwrap();
 
             assert!(!tour.is_dummy());
+            assert!(tour.all_non_depot_nodes_iter().next().is_some());
 
-            // check that vehicle type of service trips matches vehicle type of vehicle
+            // check that the vehicle type of the vehicle is allowed on its service trips
             for node in tour.all_non_depot_nodes_iter() {
-                if self.network.node(node).is_service() {
-                    assert_eq!(
-                        self.vehicle_type_of(*vehicle).unwrap(),
-                        self.network.vehicle_type_for(node)
-                    );
-                }
+                assert!(self
+                    .network
+                    .compatible_with_vehicle_type(node, self.vehicle_type_of(*vehicle).unwrap()));
             }
 
             // check that all nodes are covered by a train_formation
@@ -442,6 +1054,9 @@ impl Schedule {
             tour.verify_consistency();
         }
 
+        // vehicles that only visit maintenance slots are consistent; they are reported by
+        // idle_vehicles (and the validity report) and can be pruned with remove_idle_vehicles
+
         // check next_period_transition
         self.next_period_transitions
             .iter()
@@ -456,15 +1071,7 @@ impl Schedule {
         // check unserved passengers
         assert_eq!(
             self.unserved_passengers,
-            self.network
-                .all_service_nodes()
-                .map(|node| self.unserved_passengers_at(node))
-                .fold(
-                    (0, 0),
-                    |(unserved, seated), (unserved_node, seated_node)| {
-                        (unserved + unserved_node, seated + seated_node)
-                    }
-                )
+            Schedule::compute_unserved_passengers(&self.network, &self.train_formations)
         );
 
         // check maintenance violation
@@ -655,6 +1262,8 @@ impl Schedule {
     /// type.
     /// If a tour violates the depot constraints an message is printed and another depot is used
     /// instead.
+    /// Fails if more dummy tours are needed than allowed by the config, or if a tour cannot be
+    /// spawned (e.g., the fixed fleet of its depot is exhausted).
     pub fn from_tours(
         tours: StdHashMap<VehicleTypeIdx, Vec<Vec<NodeIdx>>>,
         network: Arc<Network>,
@@ -663,9 +1272,21 @@ impl Schedule {
 
         for (vehicle_type, tours) in tours {
             for tour in tours {
-                let result = schedule.spawn_vehicle_for_path(vehicle_type, tour);
+                // with a fixed fleet, the service trips of tours of the overflow depot stay
+                // uncovered (tours without service trips are dropped)
+                if schedule.network.config().fixed_fleet
+                    && tour.first() == Some(&schedule.network.overflow_depot_idxs().1)
+                {
+                    if tour
+                        .iter()
+                        .any(|&node| schedule.network.node(node).is_service())
+                    {
+                        schedule = schedule.add_dummy_for_path(tour)?;
+                    }
+                    continue;
+                }
 
-                schedule = result.unwrap().0;
+                schedule = schedule.spawn_vehicle_for_path(vehicle_type, tour)?.0;
             }
         }
 
@@ -688,6 +1309,12 @@ impl Schedule {
         costs: Cost,
         network: Arc<Network>,
     ) -> Schedule {
+        // the running total is updated incrementally by each modification
+        debug_assert_eq!(
+            unserved_passengers,
+            Schedule::compute_unserved_passengers(&network, &train_formations),
+            "incrementally updated unserved passengers differ from a full recomputation"
+        );
         Schedule {
             vehicles,
             tours,
@@ -708,6 +1335,19 @@ impl Schedule {
 
 // private methods
 impl Schedule {
+    /// Distance the tour of the given vehicle travels beyond its maximal distance between
+    /// maintenance (see total_maintenance_distance_violation).
+    fn maintenance_distance_violation_of(&self, vehicle: VehicleIdx, tour: &Tour) -> Distance {
+        if tour.visits_maintenance() {
+            Distance::from_meter(tour.maintenance_counter().max(0) as Meter)
+        } else {
+            tour.total_distance().sub_max_zero(
+                self.network
+                    .maximal_distance_between_maintenance(self.vehicle_type_of(vehicle).unwrap()),
+            )
+        }
+    }
+
     fn can_depot_spawn_vehicle_custom_usage(
         &self,
         start_depot: NodeIdx,
@@ -715,6 +1355,12 @@ impl Schedule {
         depot_usage: &DepotUsage,
     ) -> bool {
         let depot = self.network.get_depot_idx(start_depot);
+
+        // with a fixed fleet there are no additional vehicles in the overflow depot
+        if self.network.config().fixed_fleet && depot == self.network.overflow_depot_idxs().0 {
+            return false;
+        }
+
         let capacity_for_type = self.network.capacity_of(depot, vehicle_type);
 
         if capacity_for_type == 0 {
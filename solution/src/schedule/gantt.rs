@@ -0,0 +1,113 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use itertools::Itertools;
+use model::base_types::{Location, VehicleIdx};
+use rapid_time::DateTime;
+
+use super::Schedule;
+
+/// Activity of a vehicle during a [`GanttBar`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GanttActivity {
+    Service,
+    DeadHead,
+    Maintenance,
+    Idle,
+}
+
+/// A time segment of a vehicle's tour, e.g., for rendering a Gantt chart of the schedule.
+/// For idle and maintenance bars origin and destination coincide.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GanttBar {
+    pub vehicle: VehicleIdx,
+    pub start: DateTime,
+    pub end: DateTime,
+    pub origin: Location,
+    pub destination: Location,
+    pub activity: GanttActivity,
+    pub label: String,
+}
+
+impl Schedule {
+    /// Flattens the tours of all real vehicles into consecutive bars. The bars of a tour are
+    /// contiguous and cover its full span, i.e., from the departure at the start depot until the
+    /// arrival at the end depot. Dead head trips depart as soon as the previous activity ends,
+    /// waiting time (including shunting) is reported as idle at the destination.
+    pub fn export_gantt_data(&self) -> Vec<GanttBar> {
+        let mut bars = Vec::new();
+        for vehicle in self.vehicles_iter_all() {
+            let tour = self.tour_of(vehicle).unwrap();
+            let mut time = tour.start_time();
+            for (node1_idx, node2_idx) in tour.all_nodes_iter().tuple_windows() {
+                let node1 = self.network.node(node1_idx);
+                let node2 = self.network.node(node2_idx);
+
+                if node1.end_location() != node2.start_location() {
+                    let arrival = time + self.network.dead_head_time_between(node1_idx, node2_idx);
+                    bars.push(GanttBar {
+                        vehicle,
+                        start: time,
+                        end: arrival,
+                        origin: node1.end_location(),
+                        destination: node2.start_location(),
+                        activity: GanttActivity::DeadHead,
+                        label: format!(
+                            "dead head trip {}-{}",
+                            self.location_id(node1.end_location()),
+                            self.location_id(node2.start_location())
+                        ),
+                    });
+                    time = arrival;
+                }
+
+                if node2.is_end_depot() {
+                    break;
+                }
+
+                if node2.start_time() > time {
+                    bars.push(GanttBar {
+                        vehicle,
+                        start: time,
+                        end: node2.start_time(),
+                        origin: node2.start_location(),
+                        destination: node2.start_location(),
+                        activity: GanttActivity::Idle,
+                        label: format!("idle at {}", self.location_id(node2.start_location())),
+                    });
+                }
+
+                let activity = if node2.is_service() {
+                    GanttActivity::Service
+                } else {
+                    GanttActivity::Maintenance
+                };
+                bars.push(GanttBar {
+                    vehicle,
+                    start: node2.start_time(),
+                    end: node2.end_time(),
+                    origin: node2.start_location(),
+                    destination: node2.end_location(),
+                    activity,
+                    label: node2.id().to_string(),
+                });
+                time = node2.end_time();
+            }
+        }
+        bars
+    }
+
+    pub(super) fn location_id(&self, location: Location) -> String {
+        self.network.locations().get_id(location).unwrap()
+    }
+}
@@ -14,29 +14,31 @@
 use im::{HashMap, HashSet};
 use itertools::Itertools;
 use model::base_types::{
-    Cost, Idx, MaintenanceCounter, NodeIdx, PassengerCount, VehicleIdx, VehicleTypeIdx,
+    Cost, DepotIdx, Distance, Idx, MaintenanceCounter, NodeIdx, PassengerCount, VehicleIdx,
+    VehicleTypeIdx,
 };
+use model::config::RotationClosure;
 
 use crate::{
     path::Path, segment::Segment, tour::Tour, train_formation::TrainFormation,
     transition::Transition, vehicle::Vehicle, Schedule,
 };
 
-use super::DepotUsage;
+use super::{DepotUsage, ScheduleError};
 
 impl Schedule {
     pub fn spawn_vehicle_to_replace_dummy_tour(
         &self,
         dummy_idx: VehicleIdx,
         vehicle_type_idx: VehicleTypeIdx,
-    ) -> Result<(Schedule, VehicleIdx), String> {
+    ) -> Result<(Schedule, VehicleIdx), ScheduleError> {
         let nodes: Vec<NodeIdx> = self
             .dummy_tours
             .get(&dummy_idx)
-            .ok_or(format!(
+            .ok_or(ScheduleError::NotADummy(format!(
                 "Cannot spawn vehicle to replace dummy tour {}. Dummy tour does not exist.",
                 dummy_idx
-            ))?
+            )))?
             .all_nodes_iter()
             .collect();
         if nodes.iter().any(|n| {
@@ -44,10 +46,10 @@ impl Schedule {
                 .network
                 .compatible_with_vehicle_type(*n, vehicle_type_idx)
         }) {
-            return Err(format!(
+            return Err(ScheduleError::IncompatibleVehicleType(format!(
                 "Cannot spawn vehicle to replace dummy tour {}. Nodes are not compatible with vehicle type {}.",
                 dummy_idx, vehicle_type_idx,
-            ));
+            )));
         }
         let intermediate_schedule = self.delete_dummy(dummy_idx)?;
         intermediate_schedule.spawn_vehicle_for_path(vehicle_type_idx, nodes)
@@ -60,26 +62,30 @@ impl Schedule {
     /// Similarly, if path does not end with a depot the vehicle is spawned to the nearest depot
     /// (from the end location of the last trip).
     /// If the depot given in the path is not available, spawn vehicle from overflow depot instead.
+    /// If rotations must be closed strictly, the end depot is always the start depot (even if the
+    /// path ends with another depot).
     ///
     /// # Errors
-    /// If no depot is available, an error is returned (should not be possible as there is always
-    /// the overflow depot).
+    /// If no depot is available, an error is returned (only possible if the fleet is fixed, as
+    /// otherwise there is always the overflow depot).
+    /// If rotations must be closed strictly and the start depot cannot be reached as end depot, an
+    /// error is returned.
     /// If some node on the path is not compatible with the vehicle type an error is returned.
     /// If a train formation of some node on the path is full, an error is returned.
     pub fn spawn_vehicle_for_path(
         &self,
         vehicle_type_idx: VehicleTypeIdx,
         path_as_vec: Vec<NodeIdx>,
-    ) -> Result<(Schedule, VehicleIdx), String> {
+    ) -> Result<(Schedule, VehicleIdx), ScheduleError> {
         if path_as_vec.iter().any(|n| {
             !self
                 .network
                 .compatible_with_vehicle_type(*n, vehicle_type_idx)
         }) {
-            return Err(format!(
+            return Err(ScheduleError::IncompatibleVehicleType(format!(
                 "Cannot spawn vehicle for path {:?}. Nodes are not compatible with vehicle type {}.",
                 path_as_vec, vehicle_type_idx,
-            ));
+            )));
         }
 
         let nodes = self.add_suitable_start_and_end_depot_to_path(vehicle_type_idx, path_as_vec)?;
@@ -95,8 +101,14 @@ impl Schedule {
         let mut costs = self.costs;
 
         let vehicle_id = VehicleIdx::vehicle_from(self.vehicle_counter as Idx);
-        let tour = Tour::new(nodes, self.network.clone())?;
-        let vehicle = Vehicle::new(vehicle_id, vehicle_type_idx, self.network.vehicle_types());
+        let tour = Tour::new(nodes, self.network.clone())
+            .map_err(|error| ScheduleError::InvalidTour(error.to_string()))?;
+        let vehicle_types = self.network.vehicle_types();
+        let vehicle = match self.free_roster_unit(vehicle_type_idx, &vehicles) {
+            Some(unit) => Vehicle::new(vehicle_id, vehicle_type_idx, vehicle_types.clone())
+                .with_roster_unit(unit, &vehicle_types),
+            None => Vehicle::new(vehicle_id, vehicle_type_idx, vehicle_types),
+        };
 
         vehicles.insert(vehicle_id, vehicle.clone());
 
@@ -150,15 +162,20 @@ impl Schedule {
     /// Delete vehicle (and its tour) from schedule.
     /// # Errors
     /// If the vehicle is not a real vehicle an error is returned.
-    pub fn replace_vehicle_by_dummy(&self, vehicle_idx: VehicleIdx) -> Result<Schedule, String> {
+    pub fn replace_vehicle_by_dummy(
+        &self,
+        vehicle_idx: VehicleIdx,
+    ) -> Result<Schedule, ScheduleError> {
         if !self.is_vehicle(vehicle_idx) {
-            return Err(format!(
+            return Err(ScheduleError::NotAVehicle(format!(
                 "Cannot delete vehicle {} from schedule.",
                 vehicle_idx
-            ));
+            )));
         }
 
-        let vehicle_type_id = self.vehicle_type_of(vehicle_idx)?;
+        let vehicle_type_id = self
+            .vehicle_type_of(vehicle_idx)
+            .map_err(ScheduleError::NotAVehicle)?;
 
         let mut vehicles = self.vehicles.clone();
         let mut tours = self.tours.clone();
@@ -195,7 +212,8 @@ impl Schedule {
         costs -= tour.costs();
 
         if let Ok(dummy_tour) = Tour::new_dummy(
-            tour.sub_path(Segment::new(tour.first_node(), tour.last_node()))?,
+            tour.sub_path(Segment::new(tour.first_node(), tour.last_node()))
+                .map_err(ScheduleError::InvalidTour)?,
             self.network.clone(),
         ) {
             self.add_dummy_tour(
@@ -203,7 +221,7 @@ impl Schedule {
                 &mut dummy_ids_sorted,
                 VehicleIdx::dummy_from(self.vehicle_counter as Idx),
                 dummy_tour,
-            );
+            )?;
             vehicle_counter += 1;
         }
 
@@ -232,6 +250,52 @@ impl Schedule {
         ))
     }
 
+    /// Deletes all idle vehicles, i.e., vehicles that cover no service trip (see idle_vehicles).
+    /// As no service trip gets uncovered, no dummy tours are created.
+    pub fn remove_idle_vehicles(&self) -> Schedule {
+        self.idle_vehicles()
+            .into_iter()
+            .fold(self.clone(), |schedule, vehicle| {
+                schedule.replace_vehicle_by_dummy(vehicle).unwrap()
+            })
+    }
+
+    /// Replaces the vehicle by one new vehicle (of the same type) per non-depot node of its tour.
+    /// Useful to perturb a schedule, e.g., for restarts of the local search.
+    /// # Errors
+    /// If the vehicle is not a real vehicle an error is returned.
+    /// If no depot is available for one of the new vehicles (fixed fleet) an error is returned.
+    pub fn split_tour_into_single_node_tours(
+        &self,
+        vehicle_idx: VehicleIdx,
+    ) -> Result<Schedule, ScheduleError> {
+        if !self.is_vehicle(vehicle_idx) {
+            return Err(ScheduleError::NotAVehicle(format!(
+                "Cannot split tour of vehicle {}. Vehicle is not a real vehicle.",
+                vehicle_idx
+            )));
+        }
+        let vehicle_type = self
+            .vehicle_type_of(vehicle_idx)
+            .map_err(ScheduleError::NotAVehicle)?;
+        let nodes: Vec<NodeIdx> = self
+            .tour_of(vehicle_idx)
+            .map_err(ScheduleError::NotAVehicle)?
+            .all_non_depot_nodes_iter()
+            .collect();
+
+        let mut schedule = self.replace_vehicle_by_dummy(vehicle_idx)?;
+        // replace_vehicle_by_dummy creates a dummy tour for the service trips of the vehicle
+        let dummy = VehicleIdx::dummy_from(self.vehicle_counter as Idx);
+        if schedule.is_dummy(dummy) {
+            schedule = schedule.delete_dummy(dummy)?;
+        }
+        for node in nodes {
+            schedule = schedule.spawn_vehicle_for_path(vehicle_type, vec![node])?.0;
+        }
+        Ok(schedule)
+    }
+
     /// Add a path to the tour of a vehicle (dummy or real). If the path causes conflicts, the conflicting nodes of
     /// the old tour are removed. Return the new schedule and the removed path as Option.
     /// # Errors
@@ -241,17 +305,17 @@ impl Schedule {
         &self,
         vehicle_idx: VehicleIdx,
         path: Path,
-    ) -> Result<(Schedule, Option<Path>), String> {
+    ) -> Result<(Schedule, Option<Path>), ScheduleError> {
         if let Ok(vehicle_type_id) = self.vehicle_type_of(vehicle_idx) {
             if path.iter().any(|n| {
                 !self
                     .network
                     .compatible_with_vehicle_type(n, vehicle_type_id)
             }) {
-                return Err(format!(
+                return Err(ScheduleError::IncompatibleVehicleType(format!(
                 "Cannot add path {} to vehicle tour {}. Nodes are not compatible with vehicle type {}.",
                 path, vehicle_idx, vehicle_type_id,
-            ));
+            )));
             }
         }
         if self.network.node(path.first()).is_depot() {
@@ -267,10 +331,10 @@ impl Schedule {
                         .expect("Vehicle must be real, as it starts with a depot"),
                 )
             {
-                return Err(format!(
+                return Err(ScheduleError::DepotUnavailable(format!(
                     "Cannot add path {} to vehicle tour {}. New start depot has no capacity available.",
                     path, vehicle_idx
-                ));
+                )));
             }
         }
         let mut tours = self.tours.clone();
@@ -347,12 +411,12 @@ impl Schedule {
         &self,
         segment: Segment,
         vehicle_idx: VehicleIdx,
-    ) -> Result<Schedule, String> {
+    ) -> Result<Schedule, ScheduleError> {
         if !self.is_vehicle(vehicle_idx) {
-            return Err(format!(
+            return Err(ScheduleError::NotAVehicle(format!(
                 "Cannot remove segment {} from vehicle {}. Vehicle is not a real vehicle.",
                 segment, vehicle_idx,
-            ));
+            )));
         }
         let vehicles = self.vehicles.clone();
         let mut tours = self.tours.clone();
@@ -368,7 +432,8 @@ impl Schedule {
         let mut vehicle_counter = self.vehicle_counter;
 
         let tour = self.tour_of(vehicle_idx).unwrap();
-        let (shrinked_tour, removed_path) = tour.remove(segment)?;
+        let (shrinked_tour, removed_path) =
+            tour.remove(segment).map_err(ScheduleError::InvalidTour)?;
 
         match shrinked_tour {
             None => self.replace_vehicle_by_dummy(vehicle_idx), // segment was the whole tour (except for depots)
@@ -397,7 +462,7 @@ impl Schedule {
                         &mut dummy_ids_sorted,
                         VehicleIdx::dummy_from(self.vehicle_counter as Idx),
                         new_dummy_tour,
-                    );
+                    )?;
                     vehicle_counter += 1;
                 }
 
@@ -440,12 +505,12 @@ impl Schedule {
         segment: Segment,
         provider: VehicleIdx,
         receiver: VehicleIdx,
-    ) -> Result<Schedule, String> {
+    ) -> Result<Schedule, ScheduleError> {
         if !self.check_receiver_type_compatibility(provider, receiver, segment) {
-            return Err(format!(
+            return Err(ScheduleError::IncompatibleVehicleType(format!(
                 "Cannot fit_reassign segment {} from vehicle {} to vehicle {}. Vehicle types do not match and segment contains service trip.",
                 segment, provider, receiver,
-            ));
+            )));
         }
         let mut vehicles = self.vehicles.clone();
         let mut tours = self.tours.clone();
@@ -460,7 +525,10 @@ impl Schedule {
         let mut dummy_ids_sorted = self.dummy_ids_sorted.clone();
 
         let (new_tour_provider, new_tour_receiver, moved_nodes) = self.fit_path_into_tour(
-            self.tour_of(provider).unwrap().sub_path(segment)?,
+            self.tour_of(provider)
+                .unwrap()
+                .sub_path(segment)
+                .map_err(ScheduleError::InvalidTour)?,
             provider,
             receiver,
         );
@@ -522,12 +590,12 @@ impl Schedule {
         segment: Segment,
         provider: VehicleIdx,
         receiver: VehicleIdx,
-    ) -> Result<(Schedule, Option<VehicleIdx>), String> {
+    ) -> Result<(Schedule, Option<VehicleIdx>), ScheduleError> {
         if !self.check_receiver_type_compatibility(provider, receiver, segment) {
-            return Err(format!(
+            return Err(ScheduleError::IncompatibleVehicleType(format!(
                 "Cannot override_reassign segment {} from vehicle {} to vehicle {}. Vehicle types do not match and segment contains service trip.",
                 segment, provider, receiver,
-            ));
+            )));
         }
         let mut vehicles = self.vehicles.clone();
         let mut tours = self.tours.clone();
@@ -546,7 +614,9 @@ impl Schedule {
         let tour_receiver = self.tour_of(receiver).unwrap();
 
         // remove segment for provider
-        let (shrinked_tour_provider, path) = tour_provider.remove(segment)?;
+        let (shrinked_tour_provider, path) = tour_provider
+            .remove(segment)
+            .map_err(ScheduleError::InvalidTour)?;
 
         let moved_nodes: Vec<NodeIdx> = path.iter().collect();
 
@@ -597,7 +667,7 @@ impl Schedule {
                     &mut dummy_ids_sorted,
                     new_dummy,
                     new_dummy_tour,
-                );
+                )?;
             }
         }
         self.update_transitions_and_violation_fast(
@@ -732,7 +802,9 @@ impl Schedule {
     }
 
     /// Reassigns the end depots of all vehicles greedily. Capacties of depots are ignored.
-    pub fn reassign_end_depots_greedily(&self) -> Result<Schedule, String> {
+    /// If rotations must be closed strictly, each vehicle ends at its start depot (an error is
+    /// returned if this depot cannot be reached).
+    pub fn reassign_end_depots_greedily(&self) -> Result<Schedule, ScheduleError> {
         let mut tours = self.tours.clone();
         let mut next_period_transitions = self.next_period_transitions.clone();
         let mut depot_usage = self.depot_usage.clone();
@@ -741,16 +813,24 @@ impl Schedule {
 
         for vehicle_id in self.vehicles_iter_all() {
             let tour = self.tour_of(vehicle_id).unwrap();
-            let last_node_location = self
-                .network
-                .node(tour.last_non_depot().unwrap())
-                .end_location();
-            let new_end_depot_node = self
-                .network
-                .end_depots_sorted_by_distance_from(last_node_location)
-                .first()
-                .copied()
-                .ok_or(format!("Cannot find end depot for vehicle {}.", vehicle_id))?;
+            let vehicle_type = self.vehicle_type_of(vehicle_id).unwrap();
+            let new_end_depot_node =
+                if self.network.config().rotation_closure == RotationClosure::Strict {
+                    self.closing_end_depot(
+                        vehicle_type,
+                        tour.start_depot().unwrap(),
+                        tour.last_non_depot().unwrap(),
+                    )?
+                } else {
+                    self.network
+                        .reachable_end_depots(tour.last_non_depot().unwrap(), vehicle_type)
+                        .first()
+                        .copied()
+                        .ok_or(ScheduleError::DepotUnavailable(format!(
+                            "Cannot find end depot for vehicle {}.",
+                            vehicle_id
+                        )))?
+                };
 
             let new_tour = tour.replace_end_depot(new_end_depot_node).unwrap();
 
@@ -786,6 +866,154 @@ impl Schedule {
         ))
     }
 
+    /// Adds an explicit (empty) repositioning leg to the tour of the vehicle: after reaching its
+    /// end depot the vehicle moves to the given depot (e.g., overnight). The leg is counted as
+    /// dead-head distance and costs and the next period starts from the given depot. The depot
+    /// usage is still accounted to the end depot of the tour.
+    pub fn add_repositioning_leg(
+        &self,
+        vehicle_id: VehicleIdx,
+        depot: DepotIdx,
+    ) -> Result<Schedule, ScheduleError> {
+        let tour = self
+            .tour_of(vehicle_id)
+            .map_err(ScheduleError::NotAVehicle)?;
+        let vehicle_type = self
+            .vehicle_type_of(vehicle_id)
+            .map_err(ScheduleError::NotAVehicle)?;
+        let new_tour = tour
+            .add_repositioning(self.network.get_end_depot_node(depot))
+            .map_err(ScheduleError::InvalidTour)?;
+
+        let mut tours = self.tours.clone();
+        let mut next_period_transitions = self.next_period_transitions.clone();
+        let mut maintenance_violation = self.maintenance_violation;
+        let costs = (self.costs + new_tour.costs()) - tour.costs();
+        tours.insert(vehicle_id, new_tour);
+
+        self.recompute_transitions_and_violation_fast(
+            &mut next_period_transitions,
+            &mut maintenance_violation,
+            &self.vehicle_ids_grouped_and_sorted,
+            &tours,
+            vec![vehicle_type],
+        );
+
+        Ok(Schedule::new(
+            self.vehicles.clone(),
+            tours,
+            next_period_transitions,
+            self.train_formations.clone(),
+            self.depot_usage.clone(),
+            self.dummy_tours.clone(),
+            self.vehicle_counter,
+            self.vehicle_ids_grouped_and_sorted.clone(),
+            self.dummy_ids_sorted.clone(),
+            self.unserved_passengers,
+            maintenance_violation,
+            costs,
+            self.network.clone(),
+        ))
+    }
+
+    /// Reassigns both the start and the end depot of the vehicle to the given depot, such that its
+    /// rotation starts and ends at this depot. Fails if the depot cannot reach the first node or
+    /// cannot be reached from the last node of the tour, or if the depot has no capacity left to
+    /// spawn the vehicle.
+    pub fn home_vehicle_at(
+        &self,
+        vehicle_id: VehicleIdx,
+        depot: DepotIdx,
+    ) -> Result<Schedule, ScheduleError> {
+        let tour = self
+            .tour_of(vehicle_id)
+            .map_err(ScheduleError::NotAVehicle)?;
+        let vehicle_type = self
+            .vehicle_type_of(vehicle_id)
+            .map_err(ScheduleError::NotAVehicle)?;
+        let new_start_depot = self.network.get_start_depot_node(depot);
+        let new_end_depot = self.network.get_end_depot_node(depot);
+
+        if self
+            .network
+            .dead_head_distance_between(new_start_depot, tour.first_non_depot().unwrap())
+            == Distance::Infinity
+        {
+            return Err(ScheduleError::DepotUnavailable(format!(
+                "{} cannot reach the first node of {}.",
+                self.network.node(new_start_depot),
+                vehicle_id
+            )));
+        }
+        if !self
+            .network
+            .reachable_end_depots(tour.last_non_depot().unwrap(), vehicle_type)
+            .contains(&new_end_depot)
+        {
+            return Err(ScheduleError::DepotUnavailable(format!(
+                "{} cannot be reached from the last node of {}.",
+                self.network.node(new_end_depot),
+                vehicle_id
+            )));
+        }
+
+        let mut depot_usage = self.depot_usage.clone();
+        let vehicle = self.vehicles.get(&vehicle_id).unwrap().clone();
+        // remove the vehicle first, such that it does not block its own depot
+        self.update_depot_usage_assuming_no_dummies(&mut depot_usage, vehicle, None);
+        if !self.can_depot_spawn_vehicle_custom_usage(new_start_depot, vehicle_type, &depot_usage) {
+            return Err(ScheduleError::DepotUnavailable(format!(
+                "Cannot spawn vehicle of type {} at {}.",
+                vehicle_type,
+                self.network.node(new_start_depot)
+            )));
+        }
+
+        let mut new_tour = tour.clone();
+        if new_start_depot != tour.start_depot().unwrap() {
+            new_tour = new_tour
+                .replace_start_depot(new_start_depot)
+                .map_err(ScheduleError::InvalidTour)?;
+        }
+        if new_end_depot != tour.end_depot().unwrap() {
+            new_tour = new_tour
+                .replace_end_depot(new_end_depot)
+                .map_err(ScheduleError::InvalidTour)?;
+        }
+        let costs = (self.costs + new_tour.costs()) - tour.costs();
+
+        let mut tours = self.tours.clone();
+        tours.insert(vehicle_id, new_tour);
+        let mut depot_usage = self.depot_usage.clone();
+        self.update_depot_usage(&mut depot_usage, &self.vehicles, &tours, vehicle_id);
+
+        let mut next_period_transitions = self.next_period_transitions.clone();
+        let mut maintenance_violation = self.maintenance_violation;
+        self.recompute_transitions_and_violation_fast(
+            &mut next_period_transitions,
+            &mut maintenance_violation,
+            &self.vehicle_ids_grouped_and_sorted,
+            &tours,
+            vec![vehicle_type],
+        );
+
+        Ok(Schedule::new(
+            self.vehicles.clone(),
+            tours,
+            next_period_transitions,
+            self.train_formations.clone(),
+            depot_usage,
+            self.dummy_tours.clone(),
+            self.vehicle_counter,
+            self.vehicle_ids_grouped_and_sorted.clone(),
+            self.dummy_ids_sorted.clone(),
+            self.unserved_passengers,
+            maintenance_violation,
+            costs,
+            self.network.clone(),
+        ))
+    }
+
     // TEST this
     pub fn recompute_transitions_for(
         &self,
@@ -820,6 +1048,7 @@ impl Schedule {
     }
 
     /// Reassign the end depots such that they are consistent with the transition.
+    /// If rotations must be closed strictly, the end depots stay at the start depots instead.
     pub fn reassign_end_depots_consistent_with_transitions(&self) -> Schedule {
         let mut tours = self.tours.clone();
         let mut next_day_transitions = self.next_period_transitions.clone();
@@ -838,7 +1067,18 @@ impl Schedule {
             let start_depot_of_next_vehicle =
                 self.tour_of(next_vehicle).unwrap().start_depot().unwrap();
             let depot_idx = self.network.get_depot_idx(start_depot_of_next_vehicle);
-            let new_end_depot = self.network.get_end_depot_node(depot_idx);
+            let new_end_depot = if self.network.config().rotation_closure == RotationClosure::Strict
+            {
+                // closed rotations keep ending at their start depot (if reachable)
+                self.closing_end_depot(
+                    vehicle_type,
+                    tour.start_depot().unwrap(),
+                    tour.last_non_depot().unwrap(),
+                )
+                .unwrap_or(tour.end_depot().unwrap())
+            } else {
+                self.network.get_end_depot_node(depot_idx)
+            };
 
             let new_tour = tour.replace_end_depot(new_end_depot).unwrap();
 
@@ -873,17 +1113,288 @@ impl Schedule {
             self.network.clone(),
         )
     }
+
+    /// Renumbers vehicles and dummy tours in a canonical order such that equivalent schedules
+    /// get identical ids, independent of the order in which the vehicles have been spawned.
+    /// Vehicles are ordered by the start of their first non-depot node, their start depot, their
+    /// type and finally their whole tour. Dummy tours get the ids after the vehicles and are
+    /// ordered by their start and their nodes. Train formations are ordered by the new ids and
+    /// the next period transitions are relabeled (cycles are kept).
+    pub fn compact_ids(&self) -> Schedule {
+        let mut old_vehicles: Vec<VehicleIdx> = self.vehicles_iter_all().collect();
+        old_vehicles.sort_by_cached_key(|&vehicle| {
+            let tour = &self.tours[&vehicle];
+            (
+                self.network
+                    .node(tour.first_non_depot().unwrap())
+                    .start_time(),
+                self.network.get_depot_idx(tour.start_depot().unwrap()),
+                self.vehicles[&vehicle].type_idx(),
+                tour.all_nodes_iter().collect::<Vec<_>>(),
+            )
+        });
+        let mut old_dummies: Vec<VehicleIdx> = self.dummy_iter().collect();
+        old_dummies.sort_by_cached_key(|dummy| {
+            let tour = &self.dummy_tours[dummy];
+            (tour.start_time(), tour.all_nodes_iter().collect::<Vec<_>>())
+        });
+
+        let number_of_vehicles = old_vehicles.len();
+        let renaming: HashMap<VehicleIdx, VehicleIdx> = old_vehicles
+            .iter()
+            .enumerate()
+            .map(|(idx, &old)| (old, VehicleIdx::vehicle_from(idx as Idx)))
+            .chain(old_dummies.iter().enumerate().map(|(idx, &old)| {
+                (
+                    old,
+                    VehicleIdx::dummy_from((number_of_vehicles + idx) as Idx),
+                )
+            }))
+            .collect();
+        self.with_renamed_ids(|vehicle| renaming[&vehicle], renaming.len(), true)
+    }
+
+    /// Exchanges the ids of the two vehicles. Each vehicle keeps its type and tour (and its
+    /// position in the train formations), only the ids are swapped.
+    ///
+    /// # Errors
+    /// If one of the vehicles is not a real vehicle an error is returned.
+    pub fn swap_vehicle_ids(
+        &self,
+        vehicle_a: VehicleIdx,
+        vehicle_b: VehicleIdx,
+    ) -> Result<Schedule, ScheduleError> {
+        for vehicle in [vehicle_a, vehicle_b] {
+            if !self.is_vehicle(vehicle) {
+                return Err(ScheduleError::NotAVehicle(format!(
+                    "Cannot swap the id of {}.",
+                    vehicle
+                )));
+            }
+        }
+        Ok(self.with_renamed_ids(
+            |vehicle| {
+                if vehicle == vehicle_a {
+                    vehicle_b
+                } else if vehicle == vehicle_b {
+                    vehicle_a
+                } else {
+                    vehicle
+                }
+            },
+            self.vehicle_counter,
+            false,
+        ))
+    }
+
+    /// Renames all vehicles and dummies by the given bijection. If sort_formations is true, the
+    /// train formations are ordered by the new ids, otherwise each vehicle keeps its position.
+    /// Each vehicle keeps its roster unit, seats and capacity, hence the cached unserved
+    /// passengers stay valid.
+    fn with_renamed_ids(
+        &self,
+        rename: impl Fn(VehicleIdx) -> VehicleIdx,
+        vehicle_counter: usize,
+        sort_formations: bool,
+    ) -> Schedule {
+        let vehicles: HashMap<VehicleIdx, Vehicle> = self
+            .vehicles
+            .values()
+            .map(|vehicle| {
+                let new_id = rename(vehicle.idx());
+                (new_id, vehicle.renamed(new_id))
+            })
+            .collect();
+        let tours: HashMap<VehicleIdx, Tour> = self
+            .tours
+            .iter()
+            .map(|(&vehicle, tour)| (rename(vehicle), tour.clone()))
+            .collect();
+        let dummy_tours: HashMap<VehicleIdx, Tour> = self
+            .dummy_tours
+            .iter()
+            .map(|(&dummy, tour)| (rename(dummy), tour.clone()))
+            .collect();
+
+        let train_formations: HashMap<NodeIdx, TrainFormation> = self
+            .train_formations
+            .iter()
+            .map(|(&node, formation)| {
+                let mut new_ids: Vec<VehicleIdx> =
+                    formation.ids().into_iter().map(&rename).collect();
+                if sort_formations {
+                    new_ids.sort();
+                }
+                let new_formation = new_ids
+                    .into_iter()
+                    .map(|vehicle| vehicles[&vehicle].clone())
+                    .collect();
+                (node, TrainFormation::new(new_formation))
+            })
+            .collect();
+
+        let depot_usage: DepotUsage = self
+            .depot_usage
+            .iter()
+            .map(|(&key, (spawned, despawned))| {
+                (
+                    key,
+                    (
+                        spawned.iter().map(|&v| rename(v)).collect(),
+                        despawned.iter().map(|&v| rename(v)).collect(),
+                    ),
+                )
+            })
+            .collect();
+
+        let vehicle_ids_grouped_and_sorted: HashMap<VehicleTypeIdx, Vec<VehicleIdx>> = self
+            .vehicle_ids_grouped_and_sorted
+            .iter()
+            .map(|(&vehicle_type, vehicle_ids)| {
+                (
+                    vehicle_type,
+                    vehicle_ids.iter().map(|&v| rename(v)).sorted().collect(),
+                )
+            })
+            .collect();
+        let dummy_ids_sorted: Vec<VehicleIdx> = self
+            .dummy_ids_sorted
+            .iter()
+            .map(|&d| rename(d))
+            .sorted()
+            .collect();
+
+        let next_period_transitions: HashMap<VehicleTypeIdx, Transition> = self
+            .next_period_transitions
+            .iter()
+            .map(|(&vehicle_type, transition)| (vehicle_type, transition.renamed(&rename)))
+            .collect();
+
+        Schedule::new(
+            vehicles,
+            tours,
+            next_period_transitions,
+            train_formations,
+            depot_usage,
+            dummy_tours,
+            vehicle_counter,
+            vehicle_ids_grouped_and_sorted,
+            dummy_ids_sorted,
+            self.unserved_passengers,
+            self.maintenance_violation,
+            self.costs,
+            self.network.clone(),
+        )
+    }
+
+    /// Greedily chains dummy tours of the same vehicle type into longer dummy tours.
+    /// Dummy tours are considered by their start time and each is appended to the chain whose
+    /// last node ends latest while still reaching the dummy tour. Each chain keeps the id of its
+    /// first dummy tour.
+    /// As dummy tours are never part of a train formation, the coverage is not affected.
+    pub fn merge_compatible_dummies(&self) -> Schedule {
+        let mut dummies: Vec<VehicleIdx> = self.dummy_iter().collect();
+        dummies.sort_by_key(|dummy| (self.dummy_tours[dummy].start_time(), *dummy));
+
+        // (first dummy of the chain, vehicle type, nodes of the chain)
+        let mut chains: Vec<(VehicleIdx, VehicleTypeIdx, Vec<NodeIdx>)> = Vec::new();
+        for dummy in dummies {
+            let tour = &self.dummy_tours[&dummy];
+            let first_node = tour.first_node();
+            let vehicle_type = self.network.vehicle_type_for(first_node);
+            let best_chain = chains
+                .iter_mut()
+                .filter(|(_, chain_type, nodes)| {
+                    *chain_type == vehicle_type
+                        && self.network.can_reach(*nodes.last().unwrap(), first_node)
+                })
+                .max_by_key(|(chain_dummy, _, nodes)| {
+                    (
+                        self.network.node(*nodes.last().unwrap()).end_time(),
+                        std::cmp::Reverse(*chain_dummy),
+                    )
+                });
+            match best_chain {
+                Some((_, _, nodes)) => nodes.extend(tour.all_nodes_iter()),
+                None => chains.push((dummy, vehicle_type, tour.all_nodes_iter().collect())),
+            }
+        }
+
+        let mut dummy_tours = HashMap::new();
+        let mut dummy_ids_sorted = Vec::new();
+        for (dummy, _, nodes) in chains {
+            let path = Path::new_trusted(nodes, self.network.clone()).unwrap();
+            dummy_tours.insert(dummy, Tour::new_dummy(path, self.network.clone()).unwrap());
+            dummy_ids_sorted.push(dummy);
+        }
+        dummy_ids_sorted.sort();
+
+        Schedule::new(
+            self.vehicles.clone(),
+            self.tours.clone(),
+            self.next_period_transitions.clone(),
+            self.train_formations.clone(),
+            self.depot_usage.clone(),
+            dummy_tours,
+            self.vehicle_counter,
+            self.vehicle_ids_grouped_and_sorted.clone(),
+            dummy_ids_sorted,
+            self.unserved_passengers,
+            self.maintenance_violation,
+            self.costs,
+            self.network.clone(),
+        )
+    }
 }
 
 // private methods
 impl Schedule {
+    /// Adds the service trips of the given path as a dummy tour, i.e., they stay uncovered.
+    pub(super) fn add_dummy_for_path(
+        &self,
+        path_as_vec: Vec<NodeIdx>,
+    ) -> Result<Schedule, ScheduleError> {
+        let path = Path::new_trusted(path_as_vec, self.network.clone()).ok_or(
+            ScheduleError::InvalidTour(String::from(
+                "Cannot add dummy for a path without non-depot nodes.",
+            )),
+        )?;
+        let dummy_tour =
+            Tour::new_dummy(path, self.network.clone()).map_err(ScheduleError::InvalidTour)?;
+
+        let mut dummy_tours = self.dummy_tours.clone();
+        let mut dummy_ids_sorted = self.dummy_ids_sorted.clone();
+        self.add_dummy_tour(
+            &mut dummy_tours,
+            &mut dummy_ids_sorted,
+            VehicleIdx::dummy_from(self.vehicle_counter as Idx),
+            dummy_tour,
+        )?;
+
+        Ok(Schedule::new(
+            self.vehicles.clone(),
+            self.tours.clone(),
+            self.next_period_transitions.clone(),
+            self.train_formations.clone(),
+            self.depot_usage.clone(),
+            dummy_tours,
+            self.vehicle_counter + 1,
+            self.vehicle_ids_grouped_and_sorted.clone(),
+            dummy_ids_sorted,
+            self.unserved_passengers,
+            self.maintenance_violation,
+            self.costs,
+            self.network.clone(),
+        ))
+    }
+
     /// Delete dummy vehicle (and its tour) from schedule.
-    fn delete_dummy(&self, dummy: VehicleIdx) -> Result<Schedule, String> {
+    fn delete_dummy(&self, dummy: VehicleIdx) -> Result<Schedule, ScheduleError> {
         if !self.is_dummy(dummy) {
-            return Err(format!(
+            return Err(ScheduleError::NotADummy(format!(
                 "Cannot delete vehicle {} from schedule. It is not a dummy vehicle.",
                 dummy
-            ));
+            )));
         }
         let mut dummy_tours = self.dummy_tours.clone();
         let mut dummy_ids_sorted = self.dummy_ids_sorted.clone();
@@ -960,7 +1471,7 @@ impl Schedule {
         receiver: VehicleIdx,
         new_tour_receiver: Tour,
         moved_nodes: impl Iterator<Item = NodeIdx>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ScheduleError> {
         if let Some(provider_id) = provider {
             // update tour of the provider
             match new_tour_provider {
@@ -1030,7 +1541,7 @@ impl Schedule {
         provider: Option<VehicleIdx>,      // None: only add receiver
         receiver_vehicle: Option<Vehicle>, // None: only delete provider
         moved_nodes: impl Iterator<Item = NodeIdx>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ScheduleError> {
         for node in moved_nodes {
             if self.network.node(node).is_depot() {
                 continue;
@@ -1074,7 +1585,7 @@ impl Schedule {
         provider: Option<VehicleIdx>,
         receiver_vehicle: Option<Vehicle>,
         node: NodeIdx,
-    ) -> Result<TrainFormation, String> {
+    ) -> Result<TrainFormation, ScheduleError> {
         let old_formation = train_formations
             .get(&node)
             .unwrap_or_else(|| panic!("Node {} has no train formations.", node));
@@ -1084,7 +1595,9 @@ impl Schedule {
                 match provider {
                     Some(prov) if !self.is_dummy(prov) => {
                         // both are real vehicles
-                        old_formation.replace(prov, receiver_vh)
+                        old_formation
+                            .replace(prov, receiver_vh)
+                            .map_err(ScheduleError::NotInFormation)
                     }
                     _ => {
                         // provider is None or dummy
@@ -1092,21 +1605,21 @@ impl Schedule {
                             && old_formation.vehicle_count()
                                 >= self.network.track_count_of_maintenance_slot(node)
                         {
-                            return Err(format!(
+                            return Err(ScheduleError::CapacityExceeded(format!(
                                 "Cannot add vehicle {} to maintenance node {}. Maintenance slot is already full.",
                                 receiver_vh.idx(),
                                 node
-                            ));
+                            )));
                         }
                         if self.network.node(node).is_service() {
                             if let Some(max_length) = self.network.maximal_formation_count_for(node)
                             {
                                 if old_formation.vehicle_count() >= max_length {
-                                    return Err(format!(
+                                    return Err(ScheduleError::FormationTooLong(format!(
                                         "Cannot add vehicle {} to node {}. Formation is full.",
                                         receiver_vh.idx(),
                                         node
-                                    ));
+                                    )));
                                 }
                             }
                         }
@@ -1119,7 +1632,9 @@ impl Schedule {
                 match provider {
                     Some(prov) if !self.is_dummy(prov) => {
                         // provider is real vehicle
-                        old_formation.remove(prov)
+                        old_formation
+                            .remove(prov)
+                            .map_err(ScheduleError::NotInFormation)
                     }
                     _ => {
                         // provider is None or dummy
@@ -1322,13 +1837,28 @@ impl Schedule {
         }
     }
 
+    /// Fails if the number of dummy tours would exceed the maximum given by the config.
     fn add_dummy_tour(
         &self,
         dummy_tours: &mut HashMap<VehicleIdx, Tour>,
         dummy_ids_sorted: &mut Vec<VehicleIdx>,
         new_dummy_idx: VehicleIdx,
         new_dummy_tour: Tour,
-    ) {
+    ) -> Result<(), ScheduleError> {
+        let max_dummy_tours = self.network.config().max_dummy_tours;
+        if dummy_tours.len() >= max_dummy_tours {
+            let uncovered_service_trips = dummy_tours
+                .values()
+                .chain(std::iter::once(&new_dummy_tour))
+                .flat_map(|tour| tour.all_nodes_iter())
+                .filter(|node| self.network.node(*node).is_service())
+                .unique()
+                .count();
+            return Err(ScheduleError::Infeasible(format!(
+                "Instance seems to be infeasible: more than {} dummy tours needed, {} service trips remain uncovered.",
+                max_dummy_tours, uncovered_service_trips
+            )));
+        }
         dummy_tours.insert(new_dummy_idx, new_dummy_tour);
         dummy_ids_sorted.insert(
             dummy_ids_sorted
@@ -1336,6 +1866,7 @@ impl Schedule {
                 .unwrap_or_else(|e| e),
             new_dummy_idx,
         );
+        Ok(())
     }
 
     /// go through the path that should be inserted without causing conflcits.
@@ -1430,8 +1961,9 @@ impl Schedule {
         depot_usage: &DepotUsage,
     ) -> Tour {
         let first_non_depot = tour.first_non_depot().unwrap();
-        let new_start_depot =
-            self.find_best_start_depot_for_spawning(vehicle_type_idx, first_non_depot, depot_usage);
+        let new_start_depot = self
+            .find_best_start_depot_for_spawning(vehicle_type_idx, first_non_depot, depot_usage)
+            .unwrap_or(tour.start_depot().unwrap());
         let intermediate_tour = if new_start_depot != tour.start_depot().unwrap() {
             tour.replace_start_depot(new_start_depot).unwrap()
         } else {
@@ -1439,9 +1971,15 @@ impl Schedule {
         };
 
         let last_non_depot = intermediate_tour.last_non_depot().unwrap();
-        let new_end_depot = self
-            .find_best_end_depot_for_despawning(vehicle_type_idx, last_non_depot)
-            .unwrap();
+        let new_end_depot = match self.find_end_depot_for_rotation(
+            vehicle_type_idx,
+            new_start_depot,
+            last_non_depot,
+        ) {
+            Ok(depot) => depot,
+            // the rotation cannot be closed at the new start depot, so the depots are kept
+            Err(_) => return tour.clone(),
+        };
         if new_end_depot != intermediate_tour.end_depot().unwrap() {
             intermediate_tour.replace_end_depot(new_end_depot).unwrap()
         } else {
@@ -1449,11 +1987,28 @@ impl Schedule {
         }
     }
 
+    /// First unit of the vehicle roster of the given type (in input order) that is not used by
+    /// any of the vehicles. None if all units of the type are in use (or there are none).
+    fn free_roster_unit(
+        &self,
+        vehicle_type: VehicleTypeIdx,
+        vehicles: &HashMap<VehicleIdx, Vehicle>,
+    ) -> Option<usize> {
+        self.network
+            .vehicle_types()
+            .roster_units_of(vehicle_type)
+            .find(|&unit| {
+                !vehicles
+                    .values()
+                    .any(|vehicle| vehicle.roster_unit() == Some(unit))
+            })
+    }
+
     fn add_suitable_start_and_end_depot_to_path(
         &self,
         vehicle_type_idx: VehicleTypeIdx,
         mut nodes: Vec<NodeIdx>,
-    ) -> Result<Vec<NodeIdx>, String> {
+    ) -> Result<Vec<NodeIdx>, ScheduleError> {
         let first_node = *nodes.first().unwrap();
         let last_node = *nodes.last().unwrap();
 
@@ -1461,6 +2016,12 @@ impl Schedule {
         if self.network.node(first_node).is_depot()
             && !self.can_depot_spawn_vehicle(first_node, vehicle_type_idx)
         {
+            if self.network.config().fixed_fleet {
+                return Err(ScheduleError::DepotUnavailable(format!(
+                    "Cannot spawn vehicle of type {} at {}. Fleet is fixed.",
+                    vehicle_type_idx, first_node
+                )));
+            }
             // if given depot is not available, use overflow depot
             let overflow_depot_ids = self.network.overflow_depot_idxs();
             nodes[0] = overflow_depot_ids.1;
@@ -1482,10 +2043,21 @@ impl Schedule {
                 vehicle_type_idx,
                 first_node,
                 &self.depot_usage,
-            );
+            )?;
             nodes.insert(0, new_start_depot);
         }
 
+        // closed rotations end at the start depot, regardless of the end depot of the path
+        if self.network.config().rotation_closure == RotationClosure::Strict {
+            if self.network.node(last_node).is_depot() {
+                nodes.pop();
+            }
+            let end_depot =
+                self.closing_end_depot(vehicle_type_idx, nodes[0], *nodes.last().unwrap())?;
+            nodes.push(end_depot);
+            return Ok(nodes);
+        }
+
         // if path does not end with a depot, insert the nearest available end_depot
         if !self.network.node(last_node).is_depot() {
             match self.find_best_end_depot_for_despawning(vehicle_type_idx, last_node) {
@@ -1502,8 +2074,9 @@ impl Schedule {
         vehicle_type_idx: VehicleTypeIdx,
         first_node: NodeIdx,
         depot_usage: &DepotUsage,
-    ) -> NodeIdx {
+    ) -> Result<NodeIdx, ScheduleError> {
         let start_location = self.network.node(first_node).start_location();
+        // without a fixed fleet, there is always at least the overflow depot available
         let start_depot = self
             .network
             .start_depots_sorted_by_distance_to(start_location)
@@ -1512,7 +2085,12 @@ impl Schedule {
             .find(|depot| {
                 self.can_depot_spawn_vehicle_custom_usage(*depot, vehicle_type_idx, depot_usage)
             })
-            .expect("There should be at least the overflow depot available.");
+            .ok_or_else(|| {
+                ScheduleError::DepotUnavailable(format!(
+                    "Cannot spawn vehicle of type {} for start_node {}. No start_depot available.",
+                    vehicle_type_idx, first_node
+                ))
+            })?;
         /* if start_depot == self.network.overflow_depot_ids().1 {
             println!(
                 "\x1b[93mwarning:\x1b[0m Tour for vehicle_type {} violates depot constraints at {}. Using overflow depot instead.",
@@ -1520,26 +2098,68 @@ impl Schedule {
                 self.network.node(first_node)
             );
         } */
-        start_depot
+        Ok(start_depot)
     }
 
     fn find_best_end_depot_for_despawning(
         &self,
         vehicle_type_idx: VehicleTypeIdx,
         last_node: NodeIdx,
-    ) -> Result<NodeIdx, String> {
-        let end_location = self.network.node(last_node).end_location();
+    ) -> Result<NodeIdx, ScheduleError> {
         let end_depot = self
             .network
-            .end_depots_sorted_by_distance_from(end_location)
+            .reachable_end_depots(last_node, vehicle_type_idx)
             .first()
             .copied();
         match end_depot {
             Some(depot) => Ok(depot),
-            None => Err(format!(
+            None => Err(ScheduleError::DepotUnavailable(format!(
                 "Cannot de-spawn vehicle of type {} for end_node {}. No end_depot available.",
                 vehicle_type_idx, last_node,
-            )),
+            ))),
+        }
+    }
+
+    /// Returns the end depot for a rotation from the given start depot to the given last node:
+    /// the start depot itself if rotations must be closed strictly, otherwise the nearest
+    /// reachable end depot.
+    fn find_end_depot_for_rotation(
+        &self,
+        vehicle_type_idx: VehicleTypeIdx,
+        start_depot: NodeIdx,
+        last_node: NodeIdx,
+    ) -> Result<NodeIdx, ScheduleError> {
+        if self.network.config().rotation_closure == RotationClosure::Strict {
+            self.closing_end_depot(vehicle_type_idx, start_depot, last_node)
+        } else {
+            self.find_best_end_depot_for_despawning(vehicle_type_idx, last_node)
+        }
+    }
+
+    /// Returns the end depot node of the given start depot, if it can be reached from the last
+    /// node.
+    fn closing_end_depot(
+        &self,
+        vehicle_type_idx: VehicleTypeIdx,
+        start_depot: NodeIdx,
+        last_node: NodeIdx,
+    ) -> Result<NodeIdx, ScheduleError> {
+        let end_depot = self
+            .network
+            .get_end_depot_node(self.network.get_depot_idx(start_depot));
+        if self
+            .network
+            .reachable_end_depots(last_node, vehicle_type_idx)
+            .contains(&end_depot)
+        {
+            Ok(end_depot)
+        } else {
+            Err(ScheduleError::DepotUnavailable(format!(
+                "Cannot close rotation of vehicle type {} at {}. It cannot be reached from end_node {}.",
+                vehicle_type_idx,
+                self.network.node(end_depot),
+                last_node,
+            )))
         }
     }
 }
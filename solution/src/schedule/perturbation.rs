@@ -0,0 +1,127 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use itertools::Itertools;
+use model::base_types::{NodeIdx, VehicleIdx};
+
+use crate::segment::Segment;
+
+use super::Schedule;
+
+// random moves tried per requested move before the perturbation gives up
+const MAX_ATTEMPTS_PER_MOVE: usize = 10;
+
+impl Schedule {
+    /// Applies strength random moves to the schedule, reproducibly for the same seed. A move
+    /// either reassigns a random segment of a vehicle's tour to another vehicle of the same type
+    /// or swaps it with the nodes of the other vehicle it conflicts with.
+    /// Only moves that create no dummy tour, do not increase the unserved passengers and add no
+    /// hard violation (see validity_report) are applied, so the perturbed schedule is as feasible
+    /// as this schedule. Gives up after strength * MAX_ATTEMPTS_PER_MOVE attempts, hence fewer
+    /// moves might be applied, e.g., if no vehicles share a type.
+    pub fn perturb(&self, strength: usize, seed: u64) -> Schedule {
+        let mut random = SplitMix64(seed);
+        let mut schedule = self.clone();
+        let mut hard_violations = schedule.validity_report().hard_violation_count();
+        let mut moves = 0;
+        for _ in 0..strength * MAX_ATTEMPTS_PER_MOVE {
+            if moves == strength {
+                break;
+            }
+            let swap = random.next_u64() % 2 == 0;
+            let Some(perturbed) = schedule.random_move(&mut random, swap) else {
+                continue;
+            };
+            if perturbed.number_of_dummy_tours() > schedule.number_of_dummy_tours()
+                || perturbed.unserved_passengers().0 > schedule.unserved_passengers().0
+                || perturbed.unserved_passengers().1 > schedule.unserved_passengers().1
+            {
+                continue;
+            }
+            // the validity report is the most expensive check, so it comes last
+            let perturbed_hard_violations = perturbed.validity_report().hard_violation_count();
+            if perturbed_hard_violations <= hard_violations {
+                schedule = perturbed;
+                hard_violations = perturbed_hard_violations;
+                moves += 1;
+            }
+        }
+        schedule
+    }
+
+    /// Moves a random segment of a random vehicle to another vehicle of the same type. Returns
+    /// None if the move is not possible or creates a dummy tour. For a swap, the nodes the
+    /// receiver loses are moved back to the provider.
+    fn random_move(&self, random: &mut SplitMix64, swap: bool) -> Option<Schedule> {
+        let vehicles: Vec<VehicleIdx> = self.vehicles_iter_all().sorted().collect();
+        if vehicles.is_empty() {
+            return None;
+        }
+        let provider = vehicles[random.below(vehicles.len())];
+        let nodes: Vec<NodeIdx> = self
+            .tour_of(provider)
+            .ok()?
+            .all_non_depot_nodes_iter()
+            .collect();
+        let start = random.below(nodes.len());
+        let end = start + random.below(nodes.len() - start);
+        let receivers: Vec<VehicleIdx> = self
+            .vehicles_iter(self.vehicle_type_of(provider).ok()?)
+            .filter(|&vehicle| vehicle != provider)
+            .filter(|&vehicle| {
+                nodes[start..=end]
+                    .iter()
+                    .all(|&node| !self.train_formation_of(node).ids().contains(&vehicle))
+            })
+            .collect();
+        if receivers.is_empty() {
+            return None;
+        }
+        let receiver = receivers[random.below(receivers.len())];
+
+        let (schedule, new_dummy) = self
+            .override_reassign(Segment::new(nodes[start], nodes[end]), provider, receiver)
+            .ok()?;
+        match new_dummy {
+            None => Some(schedule),
+            Some(dummy) if swap && schedule.is_vehicle(provider) => {
+                let dummy_tour = schedule.tour_of(dummy).ok()?;
+                let segment = Segment::new(dummy_tour.first_node(), dummy_tour.last_node());
+                match schedule.override_reassign(segment, dummy, provider).ok()? {
+                    (swapped_schedule, None) => Some(swapped_schedule),
+                    _ => None,
+                }
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+/// Small seeded pseudo-random number generator (SplitMix64), such that perturbations are
+/// reproducible on all platforms.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// uniform random number in 0..bound (bound must be positive)
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
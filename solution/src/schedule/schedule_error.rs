@@ -0,0 +1,71 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+/// Error returned by the modification methods of a schedule. Each variant carries the
+/// human-readable message, which is also used by Display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// The vehicle does not exist or is not a real vehicle.
+    NotAVehicle(String),
+    /// The vehicle is not a dummy vehicle.
+    NotADummy(String),
+    /// Some node is not compatible with the vehicle type.
+    IncompatibleVehicleType(String),
+    /// No depot is available to spawn or de-spawn the vehicle (e.g., the fleet is fixed).
+    DepotUnavailable(String),
+    /// The maintenance slot has no free track left.
+    CapacityExceeded(String),
+    /// The train formation already has the maximal number of vehicles.
+    FormationTooLong(String),
+    /// The vehicle is not part of the train formation of a node.
+    NotInFormation(String),
+    /// The resulting tour would be invalid (e.g., a node cannot reach its successor) or the
+    /// segment is not part of the tour.
+    InvalidTour(String),
+    /// The maximal number of dummy tours (see config) would be exceeded, i.e., the instance is
+    /// most likely infeasible.
+    Infeasible(String),
+}
+
+impl ScheduleError {
+    pub fn message(&self) -> &str {
+        match self {
+            ScheduleError::NotAVehicle(message)
+            | ScheduleError::NotADummy(message)
+            | ScheduleError::IncompatibleVehicleType(message)
+            | ScheduleError::DepotUnavailable(message)
+            | ScheduleError::CapacityExceeded(message)
+            | ScheduleError::FormationTooLong(message)
+            | ScheduleError::NotInFormation(message)
+            | ScheduleError::InvalidTour(message)
+            | ScheduleError::Infeasible(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Allows callers that still work with String errors to use `?`.
+impl From<ScheduleError> for String {
+    fn from(error: ScheduleError) -> String {
+        error.to_string()
+    }
+}
@@ -0,0 +1,189 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use itertools::Itertools;
+use model::base_types::{Location, NodeIdx, VehicleIdx};
+use rapid_time::DateTime;
+
+use super::Schedule;
+
+/// Kind of a [`SimulationEvent`]. Events at the same time are ordered as listed here, i.e., a
+/// vehicle first arrives and decouples before it couples and departs again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SimulationEventKind {
+    Arrive,
+    Decouple,
+    EndMaintenance,
+    StartMaintenance,
+    Couple,
+    Depart,
+}
+
+/// An event of a vehicle, e.g., for feeding a discrete-event simulator.
+/// Couple and decouple events list the other vehicles of the formation in the label.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationEvent {
+    pub time: DateTime,
+    pub vehicle: VehicleIdx,
+    pub kind: SimulationEventKind,
+    pub location: Location,
+    pub label: String,
+}
+
+impl Schedule {
+    /// Lists the events of all real vehicles sorted by time (then by kind and vehicle):
+    /// * depart and arrive for every service trip and dead head trip (including the repositioning
+    ///   leg). As in export_gantt_data, dead head trips depart as soon as the previous activity
+    ///   ends,
+    /// * couple at the departure of a service trip if the vehicle joins a formation of several
+    ///   vehicles, decouple at the arrival if the formation of its next activity differs,
+    /// * start and end of maintenance.
+    pub fn export_for_simulation(&self) -> Vec<SimulationEvent> {
+        let mut events = Vec::new();
+        for vehicle in self.vehicles_iter_all() {
+            let tour = self.tour_of(vehicle).unwrap();
+            let mut event = |time, kind, location, label: String| {
+                events.push(SimulationEvent {
+                    time,
+                    vehicle,
+                    kind,
+                    location,
+                    label,
+                })
+            };
+            let mut time = tour.start_time();
+            let mut previous_partners: Vec<VehicleIdx> = Vec::new();
+            for (node1_idx, node2_idx) in tour.all_nodes_iter().tuple_windows() {
+                let node1 = self.network.node(node1_idx);
+                let node2 = self.network.node(node2_idx);
+
+                if node1.end_location() != node2.start_location() {
+                    let arrival = time + self.network.dead_head_time_between(node1_idx, node2_idx);
+                    let label = format!(
+                        "dead head trip {}-{}",
+                        self.location_id(node1.end_location()),
+                        self.location_id(node2.start_location())
+                    );
+                    event(
+                        time,
+                        SimulationEventKind::Depart,
+                        node1.end_location(),
+                        label.clone(),
+                    );
+                    event(
+                        arrival,
+                        SimulationEventKind::Arrive,
+                        node2.start_location(),
+                        label,
+                    );
+                    time = arrival;
+                }
+
+                if node2.is_end_depot() {
+                    if let Some(repositioning) = tour.repositioning() {
+                        let destination = self.network.node(repositioning).start_location();
+                        let arrival = time
+                            + self.network.locations().travel_time(
+                                node2.start_location(),
+                                destination,
+                                time,
+                            );
+                        let label = format!(
+                            "dead head trip {}-{}",
+                            self.location_id(node2.start_location()),
+                            self.location_id(destination)
+                        );
+                        event(
+                            time,
+                            SimulationEventKind::Depart,
+                            node2.start_location(),
+                            label.clone(),
+                        );
+                        event(arrival, SimulationEventKind::Arrive, destination, label);
+                    }
+                    break;
+                }
+
+                let label = node2.id().to_string();
+                if node2.is_service() {
+                    let partners = self.partners_of(vehicle, node2_idx);
+                    if !partners.is_empty() && partners != previous_partners {
+                        event(
+                            node2.start_time(),
+                            SimulationEventKind::Couple,
+                            node2.start_location(),
+                            self.formation_label(&label, &partners),
+                        );
+                    }
+                    event(
+                        node2.start_time(),
+                        SimulationEventKind::Depart,
+                        node2.start_location(),
+                        label.clone(),
+                    );
+                    event(
+                        node2.end_time(),
+                        SimulationEventKind::Arrive,
+                        node2.end_location(),
+                        label.clone(),
+                    );
+                    let next_partners = tour
+                        .successor_of(node2_idx)
+                        .filter(|&next| self.network.node(next).is_service())
+                        .map(|next| self.partners_of(vehicle, next))
+                        .unwrap_or_default();
+                    if !partners.is_empty() && partners != next_partners {
+                        event(
+                            node2.end_time(),
+                            SimulationEventKind::Decouple,
+                            node2.end_location(),
+                            self.formation_label(&label, &partners),
+                        );
+                    }
+                    previous_partners = partners;
+                } else {
+                    event(
+                        node2.start_time(),
+                        SimulationEventKind::StartMaintenance,
+                        node2.start_location(),
+                        label.clone(),
+                    );
+                    event(
+                        node2.end_time(),
+                        SimulationEventKind::EndMaintenance,
+                        node2.end_location(),
+                        label,
+                    );
+                    previous_partners = Vec::new();
+                }
+                time = node2.end_time();
+            }
+        }
+        events.sort_by_key(|event| (event.time, event.kind, event.vehicle));
+        events
+    }
+
+    /// The other vehicles of the formation operating the node (sorted).
+    fn partners_of(&self, vehicle: VehicleIdx, node: NodeIdx) -> Vec<VehicleIdx> {
+        self.train_formation_of(node)
+            .ids()
+            .into_iter()
+            .filter(|&other| other != vehicle)
+            .sorted()
+            .collect()
+    }
+
+    fn formation_label(&self, label: &str, partners: &[VehicleIdx]) -> String {
+        format!("{} with {}", label, partners.iter().join(", "))
+    }
+}
@@ -0,0 +1,65 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use model::base_types::{Distance, PassengerCount, VehicleCount};
+use rapid_time::Duration;
+
+use super::Schedule;
+
+/// Aggregated key figures of a schedule, e.g., for reporting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScheduleStatistics {
+    pub number_of_vehicles: usize,
+    pub number_of_dummy_tours: usize,
+    pub total_distance: Distance,
+    pub service_distance: Distance,
+    pub dead_head_distance: Distance,
+    pub total_overhead_duration: Duration,
+    pub unserved_passengers: (PassengerCount, PassengerCount), // (not fitting, not seated)
+    pub depot_balance_violation: VehicleCount,
+}
+
+impl ScheduleStatistics {
+    pub fn print(&self) {
+        println!("Number of vehicles: {}", self.number_of_vehicles);
+        println!("Number of dummy tours: {}", self.number_of_dummy_tours);
+        println!(
+            "Total distance: {} (service: {}, dead head: {})",
+            self.total_distance, self.service_distance, self.dead_head_distance
+        );
+        println!("Total overhead duration: {}", self.total_overhead_duration);
+        println!(
+            "Unserved passengers: {} (not seated: {})",
+            self.unserved_passengers.0, self.unserved_passengers.1
+        );
+        println!(
+            "Total depot balance violations: {}",
+            self.depot_balance_violation
+        );
+    }
+}
+
+impl Schedule {
+    pub fn statistics(&self) -> ScheduleStatistics {
+        ScheduleStatistics {
+            number_of_vehicles: self.number_of_vehicles(),
+            number_of_dummy_tours: self.number_of_dummy_tours(),
+            total_distance: self.total_distance(),
+            service_distance: self.total_service_distance(),
+            dead_head_distance: self.total_dead_head_distance(),
+            total_overhead_duration: self.total_overhead_duration(),
+            unserved_passengers: self.unserved_passengers(),
+            depot_balance_violation: self.total_depot_balance_violation(),
+        }
+    }
+}
@@ -11,14 +11,22 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use itertools::assert_equal;
-use model::base_types::{Distance, VehicleIdx, VehicleTypeIdx};
+use std::collections::HashMap;
+
+use itertools::{assert_equal, Itertools};
+use model::{
+    base_types::{Distance, Idx, NodeIdx, PassengerCount, VehicleIdx, VehicleTypeIdx},
+    json_serialisation::load_rolling_stock_problem_instance_from_json,
+};
+use rapid_time::{DateTime, Duration};
+use serde_json::json;
 
 use crate::{
+    json_serialisation::schedule_to_json,
     path::Path,
     segment::Segment,
-    test_utilities::{default_schedule, init_test_data},
-    Schedule,
+    test_utilities::{default_schedule, init_test_data, load_test_input, TestData},
+    GanttActivity, GanttBar, Schedule, ScheduleError, SimulationEventKind,
 };
 
 #[test]
@@ -1308,3 +1316,1694 @@ fn reassign_end_depots_greedily_test() {
     );
     new_schedule.verify_consistency();
 }
+
+#[test]
+fn vehicles_passing_through_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let veh1 = VehicleIdx::vehicle_from(1);
+    let veh2 = VehicleIdx::vehicle_from(2);
+
+    // ACT
+    let passing_trip45_fast = schedule.vehicles_passing_through(d.trip45_fast);
+    let passing_trip34 = schedule.vehicles_passing_through(d.trip34);
+
+    // ASSERT
+    assert!(!schedule.is_fully_covered(d.trip45_fast));
+    assert_eq!(passing_trip45_fast, vec![veh1, veh2]);
+    assert_eq!(passing_trip34, vec![veh0]);
+}
+
+#[test]
+fn non_home_spawn_penalty_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = Schedule::empty(d.network.clone());
+
+    // ACT
+    let closed_schedule = schedule
+        .spawn_vehicle_for_path(
+            d.vt1,
+            vec![d.start_depot1, d.trip12, d.trip23, d.trip31, d.end_depot1],
+        )
+        .unwrap()
+        .0;
+    let open_schedule = closed_schedule
+        .spawn_vehicle_for_path(d.vt1, vec![d.start_depot2, d.trip34, d.end_depot1])
+        .unwrap()
+        .0;
+
+    // ASSERT
+    assert_eq!(closed_schedule.non_home_spawn_penalty(), 0);
+    assert_eq!(open_schedule.non_home_spawn_penalty(), 1);
+}
+
+#[test]
+fn strictly_closed_rotations_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let mut input_data = load_test_input();
+    input_data["parameters"]["closedRotations"] = json!("strict");
+    let strict_network = load_rolling_stock_problem_instance_from_json(input_data);
+    let open_path = vec![d.start_depot2, d.trip34, d.end_depot1];
+
+    // ACT
+    let open_schedule = Schedule::empty(d.network.clone())
+        .spawn_vehicle_for_path(d.vt1, open_path.clone())
+        .unwrap()
+        .0;
+    let (strict_schedule, vehicle) = Schedule::empty(strict_network)
+        .spawn_vehicle_for_path(d.vt1, open_path)
+        .unwrap();
+    let strict_schedule = strict_schedule
+        .spawn_vehicle_for_path(d.vt1, vec![d.trip12, d.trip23])
+        .unwrap()
+        .0;
+    let improved_schedule = strict_schedule.improve_depots(None);
+    let reassigned_schedule = improved_schedule.reassign_end_depots_greedily().unwrap();
+
+    // ASSERT
+    assert_eq!(open_schedule.closed_rotation_violation(), 1);
+    assert_equal(
+        strict_schedule.tour_of(vehicle).unwrap().all_nodes_iter(),
+        [d.start_depot2, d.trip34, d.end_depot2],
+    );
+    for schedule in [&strict_schedule, &improved_schedule, &reassigned_schedule] {
+        assert_eq!(schedule.closed_rotation_violation(), 0);
+        schedule.verify_consistency();
+    }
+}
+
+#[test]
+fn interday_repositioning_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let (schedule, closed_vehicle) = default_schedule(&d)
+        .spawn_vehicle_for_path(d.vt1, vec![d.start_depot1, d.trip12, d.end_depot1])
+        .unwrap();
+
+    // ACT
+    let repositioning = schedule.interday_repositioning();
+
+    // ASSERT
+    assert_eq!(
+        repositioning,
+        vec![
+            (
+                VehicleIdx::vehicle_from(0),
+                d.depot2,
+                d.depot1,
+                Distance::from_meter(21000) // loc2 -> loc1
+            ),
+            (
+                VehicleIdx::vehicle_from(1),
+                d.depot1,
+                d.depot2,
+                Distance::from_meter(12000) // loc1 -> loc2
+            ),
+            (
+                VehicleIdx::vehicle_from(2),
+                d.depot2,
+                d.depot1,
+                Distance::from_meter(21000) // loc2 -> loc1
+            ),
+        ]
+    );
+    assert!(repositioning
+        .iter()
+        .all(|(vehicle, _, _, _)| *vehicle != closed_vehicle));
+    assert_eq!(schedule.closed_rotation_violation(), 3);
+}
+
+#[test]
+fn merge_compatible_dummies_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let mut schedule = Schedule::empty(d.network.clone());
+    for trip in [d.trip12, d.trip23, d.trip34] {
+        let (new_schedule, vehicle) = schedule.spawn_vehicle_for_path(d.vt1, vec![trip]).unwrap();
+        schedule = new_schedule.replace_vehicle_by_dummy(vehicle).unwrap();
+    }
+    let dummy1 = VehicleIdx::dummy_from(1);
+
+    // ACT
+    let merged_schedule = schedule.merge_compatible_dummies();
+
+    // ASSERT
+    assert_eq!(schedule.number_of_dummy_tours(), 3);
+    assert_eq!(merged_schedule.number_of_dummy_tours(), 1);
+    assert_equal(merged_schedule.dummy_iter(), [dummy1].iter().cloned());
+    assert_equal(
+        merged_schedule.tour_of(dummy1).unwrap().all_nodes_iter(),
+        [d.trip12, d.trip23, d.trip34].iter().cloned(),
+    );
+    assert_eq!(
+        merged_schedule.unserved_passengers(),
+        schedule.unserved_passengers()
+    );
+    merged_schedule.verify_consistency();
+}
+
+#[test]
+fn missing_mandatory_maintenance_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["maintenance"]["mandatoryDailyVisit"] = json!(true);
+    input_data["maintenanceSlots"] = json!([{"id": "maintenance_loc2", "location": "loc2",
+        "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00", "trackCount": 2}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let maintenance = network.maintenance_nodes().next().unwrap();
+    let trip12 = NodeIdx::service_from(12);
+    let (schedule, vehicle) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![trip12])
+        .unwrap();
+
+    // ACT
+    let (schedule_with_maintenance, _) = schedule
+        .add_path_to_vehicle_tour(
+            vehicle,
+            Path::new_from_single_node(maintenance, network.clone()),
+        )
+        .unwrap();
+
+    // ASSERT
+    assert_eq!(schedule.missing_mandatory_maintenance(), 1);
+    assert_eq!(schedule_with_maintenance.missing_mandatory_maintenance(), 0);
+    assert!(schedule_with_maintenance
+        .tour_of(vehicle)
+        .unwrap()
+        .visits_maintenance());
+}
+
+#[test]
+fn statistics_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d)
+        .replace_vehicle_by_dummy(VehicleIdx::vehicle_from(1))
+        .unwrap();
+
+    // ACT
+    let statistics = schedule.statistics();
+
+    // ASSERT
+    assert_eq!(statistics.number_of_vehicles, schedule.number_of_vehicles());
+    assert_eq!(statistics.number_of_vehicles, 2);
+    assert_eq!(
+        statistics.number_of_dummy_tours,
+        schedule.number_of_dummy_tours()
+    );
+    assert_eq!(statistics.number_of_dummy_tours, 1);
+    assert_eq!(statistics.total_distance, schedule.total_distance());
+    assert_eq!(
+        statistics.service_distance,
+        schedule.total_service_distance()
+    );
+    assert_eq!(
+        statistics.dead_head_distance,
+        schedule.total_dead_head_distance()
+    );
+    assert_eq!(
+        statistics.total_distance,
+        statistics.service_distance + statistics.dead_head_distance
+    );
+    assert_eq!(
+        statistics.total_overhead_duration,
+        schedule.total_overhead_duration()
+    );
+    assert_eq!(
+        statistics.unserved_passengers,
+        schedule.unserved_passengers()
+    );
+    assert_eq!(
+        statistics.depot_balance_violation,
+        schedule.total_depot_balance_violation()
+    );
+}
+
+#[test]
+fn unhonored_preferred_successors_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    assert_eq!(input_data["departures"][1]["route"], json!("route_2-3"));
+    input_data["departures"][1]["segments"][0]["preferredSuccessor"] = json!("dep_segment_3-4");
+    input_data["departures"][2]["segments"][0]["preferredSuccessor"] = json!("dep_segment_1-2"); // unreachable
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let vt = VehicleTypeIdx::from(0);
+    let trip23 = NodeIdx::service_from(13);
+    let trip34 = NodeIdx::service_from(14);
+    let trip31 = NodeIdx::service_from(18);
+
+    // ACT
+    let honoring_schedule = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(vt, vec![trip23, trip34])
+        .unwrap()
+        .0
+        .spawn_vehicle_for_path(vt, vec![trip31])
+        .unwrap()
+        .0;
+    let arbitrary_schedule = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(vt, vec![trip23, trip31])
+        .unwrap()
+        .0
+        .spawn_vehicle_for_path(vt, vec![trip34])
+        .unwrap()
+        .0;
+
+    // ASSERT
+    assert_eq!(network.preferred_successor_of(trip23), Some(trip34));
+    assert_eq!(network.preferred_successors_iter().count(), 1);
+    assert_eq!(honoring_schedule.costs(), arbitrary_schedule.costs());
+    assert_eq!(honoring_schedule.unhonored_preferred_successors(), 0);
+    assert_eq!(arbitrary_schedule.unhonored_preferred_successors(), 1);
+}
+
+#[test]
+fn maintenance_options_for_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["maintenanceSlots"] = json!([
+        {"id": "morning_loc3", "location": "loc3",
+            "start": "2020-01-01T07:00:00", "end": "2020-01-01T07:30:00", "trackCount": 1},
+        {"id": "gap_loc1", "location": "loc1",
+            "start": "2020-01-01T08:35:00", "end": "2020-01-01T08:50:00", "trackCount": 2},
+        {"id": "too_long_loc1", "location": "loc1",
+            "start": "2020-01-01T08:35:00", "end": "2020-01-01T08:59:00", "trackCount": 2},
+        {"id": "too_far_loc2", "location": "loc2",
+            "start": "2020-01-01T08:35:00", "end": "2020-01-01T08:50:00", "trackCount": 2},
+        {"id": "occupied_loc1", "location": "loc1",
+            "start": "2020-01-01T08:36:00", "end": "2020-01-01T08:48:00", "trackCount": 1},
+        {"id": "evening_loc4", "location": "loc4",
+            "start": "2020-01-01T10:00:00", "end": "2020-01-01T11:00:00", "trackCount": 1}
+    ]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let slot = |id: &str| {
+        network
+            .maintenance_nodes()
+            .find(|&m| network.node(m).id() == id)
+            .unwrap()
+    };
+    let trip31 = NodeIdx::service_from(18);
+    let trip14 = NodeIdx::service_from(19);
+    let (schedule, vehicle) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![trip31, trip14])
+        .unwrap();
+    let (schedule, _) = schedule
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![slot("occupied_loc1")])
+        .unwrap();
+
+    // ACT
+    let options = schedule.maintenance_options_for(vehicle);
+
+    // ASSERT
+    assert_eq!(
+        options,
+        vec![slot("morning_loc3"), slot("gap_loc1"), slot("evening_loc4")]
+    );
+    assert!(schedule
+        .maintenance_options_for(VehicleIdx::vehicle_from(42))
+        .is_empty());
+}
+
+#[test]
+fn format_tours_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d)
+        .replace_vehicle_by_dummy(VehicleIdx::vehicle_from(1))
+        .unwrap();
+
+    // ACT
+    let output = schedule.format_tours();
+
+    // ASSERT
+    assert_eq!(
+        output,
+        "veh_0(vt1): s_depot1 - dep_segment_1-2 - dep_segment_2-3 - dep_segment_3-4 - \
+         dep_segment_4-5 - dep_segment_5-1 - e_depot2\n\
+         veh_2(vt1): s_depot1 - dep_segment_1-2 - dep_segment_2-3 - dep_segment_3-1 - e_depot2\n\
+         dummy_3: dep_segment_3-1 - dep_segment_1-4\n"
+    );
+}
+
+#[test]
+fn depot_usage_output_is_deterministic_test() {
+    // ARRANGE
+    let schedules: Vec<Schedule> = (0..2)
+        .map(|_| default_schedule(&init_test_data()))
+        .collect();
+
+    // ACT
+    let balances: Vec<String> = schedules
+        .iter()
+        .map(|schedule| schedule.format_depot_balances())
+        .collect();
+    let depot_loads: Vec<serde_json::Value> = schedules
+        .iter()
+        .map(|schedule| schedule_to_json(schedule)["depotLoads"].clone())
+        .collect();
+
+    // ASSERT
+    assert_eq!(balances[0], balances[1]);
+    assert_eq!(depot_loads[0], depot_loads[1]);
+    let depot_ids: Vec<&str> = depot_loads[0]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|load| load["depot"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        depot_ids,
+        vec![
+            "OVERFLOW_DEPOT",
+            "depot1",
+            "depot2",
+            "depot3",
+            "depot4",
+            "depot5"
+        ]
+    );
+}
+
+#[test]
+fn can_reach_with_formation_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["routes"][7]["segments"][0]["maximalFormationCount"] = json!(1);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let vt1 = VehicleTypeIdx::from(0);
+    let vt2 = VehicleTypeIdx::from(1);
+    let trip31 = NodeIdx::service_from(18);
+    let trip14 = NodeIdx::service_from(19);
+    let (schedule, vehicle) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(vt1, vec![trip31])
+        .unwrap();
+    let (schedule, _) = schedule.spawn_vehicle_for_path(vt1, vec![trip31]).unwrap();
+    let long_formation = schedule.train_formation_of(trip31).vehicle_types();
+    let single_unit = vec![schedule.vehicle_type_of(vehicle).unwrap()];
+
+    // ACT
+    let long_formation_reaches = network.can_reach_with_formation(trip31, trip14, &long_formation);
+    let single_unit_reaches = network.can_reach_with_formation(trip31, trip14, &single_unit);
+
+    // ASSERT
+    assert_eq!(long_formation, vec![vt1, vt1]);
+    assert!(network.can_reach(trip31, trip14));
+    assert!(!long_formation_reaches);
+    assert!(single_unit_reaches);
+    assert!(!network.can_reach_with_formation(trip31, trip14, &[vt2]));
+    assert!(!network.can_reach_with_formation(trip14, trip31, &single_unit));
+}
+
+#[test]
+fn zero_shunting_same_location_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    // trip12 arrives at loc2 at 06:30, trip23 departs from loc2 at 07:00
+    input_data["parameters"]["shunting"]["minimalDuration"] = json!(3600);
+    let network = load_rolling_stock_problem_instance_from_json(input_data.clone());
+    input_data["parameters"]["shunting"]["zeroSameLocation"] = json!(true);
+    let through_running_network = load_rolling_stock_problem_instance_from_json(input_data);
+    let trip12 = NodeIdx::service_from(12);
+    let trip23 = NodeIdx::service_from(13);
+    let trip14 = NodeIdx::service_from(19);
+
+    // ACT
+    let reachable = network.can_reach(trip12, trip23);
+    let reachable_through_running = through_running_network.can_reach(trip12, trip23);
+
+    // ASSERT
+    assert!(!reachable);
+    assert!(reachable_through_running);
+    assert_eq!(
+        through_running_network.minimal_duration_between_nodes(trip12, trip23),
+        Duration::ZERO
+    );
+    // connections with dead head trip (loc2 to loc1) are not affected
+    assert_eq!(
+        through_running_network.minimal_duration_between_nodes(trip12, trip14),
+        network.minimal_duration_between_nodes(trip12, trip14)
+    );
+    assert!(Schedule::empty(through_running_network.clone())
+        .spawn_vehicle_for_path(
+            through_running_network.vehicle_type_for(trip12),
+            vec![trip12, trip23]
+        )
+        .is_ok());
+}
+
+#[test]
+fn loop_service_trip_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    // circular line starting and ending at loc1, arriving before trip12 departs from loc1
+    input_data["routes"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"id": "route_1-1",
+        "vehicleType": "vt1", "segments": [{"id": "segment_1-1", "order": 0, "origin": "loc1",
+        "destination": "loc1", "distance": 5000, "duration": 1800}]}));
+    input_data["departures"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"id": "trip_1-1",
+        "route": "route_1-1", "segments": [{"id": "dep_segment_1-1", "routeSegment": "segment_1-1",
+        "departure": "2020-01-01T04:00:00", "passengers": 50, "seated": 0}]}));
+    // a non-zero diagonal must not turn staying at loc1 into a dead head trip
+    input_data["deadHeadTrips"]["distances"][0][0] = json!(9999);
+    input_data["deadHeadTrips"]["durations"][0][0] = json!(600);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let loop_trip = network
+        .all_service_nodes()
+        .find(|&node| network.original_id_of(node) == "dep_segment_1-1")
+        .unwrap();
+    let trip12 = NodeIdx::service_from(12);
+
+    // ACT
+    let (schedule, vehicle) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![loop_trip, trip12])
+        .unwrap();
+    let tour = schedule.tour_of(vehicle).unwrap();
+
+    // ASSERT
+    assert!(network.node(loop_trip).is_loop());
+    assert!(!network.node(trip12).is_loop());
+    assert_eq!(
+        network.node(loop_trip).travel_distance(),
+        Distance::from_meter(5000)
+    );
+    assert_eq!(
+        network.node(loop_trip).duration(),
+        Duration::from_seconds(1800)
+    );
+    assert_eq!(
+        network.dead_head_distance_between(loop_trip, trip12),
+        Distance::ZERO
+    );
+    assert_eq!(
+        network.dead_head_time_between(loop_trip, trip12),
+        Duration::ZERO
+    );
+    assert_eq!(tour.service_distance(), Distance::from_meter(6000));
+    assert_eq!(tour.dead_head_distance(), Distance::ZERO);
+    schedule.verify_consistency();
+}
+
+#[test]
+fn split_tour_into_single_node_tours_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh1 = VehicleIdx::vehicle_from(1);
+
+    // ACT
+    let split_schedule = schedule.split_tour_into_single_node_tours(veh1).unwrap();
+
+    // ASSERT
+    assert!(!split_schedule.is_vehicle(veh1));
+    assert_eq!(split_schedule.number_of_vehicles(), 4);
+    assert_eq!(split_schedule.number_of_dummy_tours(), 0);
+    let new_vehicles: Vec<VehicleIdx> = split_schedule
+        .vehicles_iter_all()
+        .filter(|&v| !schedule.is_vehicle(v))
+        .collect();
+    assert_eq!(new_vehicles.len(), 2);
+    for (vehicle, trip) in new_vehicles.into_iter().zip([d.trip31, d.trip14]) {
+        let tour = split_schedule.tour_of(vehicle).unwrap();
+        assert_equal(tour.all_non_depot_nodes_iter(), [trip]);
+        assert_eq!(split_schedule.vehicle_type_of(vehicle).unwrap(), d.vt1);
+        assert_eq!(
+            split_schedule.train_formation_of(trip).vehicle_count(),
+            schedule.train_formation_of(trip).vehicle_count()
+        );
+    }
+    assert!(split_schedule
+        .split_tour_into_single_node_tours(VehicleIdx::dummy_from(0))
+        .is_err());
+    split_schedule.verify_consistency();
+}
+
+#[test]
+fn assignment_distance_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let veh2 = VehicleIdx::vehicle_from(2);
+
+    // ACT
+    let neighbor = schedule
+        .fit_reassign(Segment::new(d.trip51, d.trip51), veh0, veh2)
+        .unwrap();
+
+    // ASSERT
+    assert_eq!(schedule.assignment_distance(&schedule), 0);
+    assert_equal(neighbor.train_formation_of(d.trip51).ids(), [veh2]);
+    assert_eq!(neighbor.assignment_distance(&schedule), 1);
+    assert_eq!(schedule.assignment_distance(&neighbor), 1);
+    let reference = schedule.reference_assignment();
+    assert_eq!(reference["dep_segment_5-1"], vec![veh0.to_string()]);
+    assert_eq!(schedule.assignment_distance_to(&reference), 0);
+    assert_eq!(neighbor.assignment_distance_to(&reference), 1);
+}
+
+#[test]
+fn export_gantt_data_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let tour = schedule.tour_of(veh0).unwrap();
+
+    // ACT
+    let bars: Vec<GanttBar> = schedule
+        .export_gantt_data()
+        .into_iter()
+        .filter(|bar| bar.vehicle == veh0)
+        .collect();
+
+    // ASSERT
+    assert_eq!(bars.first().unwrap().start, tour.start_time());
+    assert_eq!(bars.last().unwrap().end, tour.end_time());
+    for (bar1, bar2) in bars.iter().tuple_windows() {
+        assert_eq!(bar1.end, bar2.start);
+        assert_eq!(bar1.destination, bar2.origin);
+    }
+    assert!(bars.iter().all(|bar| bar.start < bar.end));
+    let service_labels: Vec<&str> = bars
+        .iter()
+        .filter(|bar| bar.activity == GanttActivity::Service)
+        .map(|bar| bar.label.as_str())
+        .collect();
+    assert_eq!(
+        service_labels,
+        vec![
+            "dep_segment_1-2",
+            "dep_segment_2-3",
+            "dep_segment_3-4",
+            "dep_segment_4-5",
+            "dep_segment_5-1"
+        ]
+    );
+    assert_eq!(bars.first().unwrap().activity, GanttActivity::Service); // depot1 is at loc1
+    assert_eq!(bars.last().unwrap().activity, GanttActivity::DeadHead); // to depot2
+    assert!(bars.iter().any(|bar| bar.activity == GanttActivity::Idle));
+}
+
+#[test]
+fn schedule_error_variants_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["vehicleTypes"][0]["maximalFormationCount"] = json!(1);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let vt1 = VehicleTypeIdx::from(0);
+    let vt2 = VehicleTypeIdx::from(1);
+    let trip31 = NodeIdx::service_from(18);
+    let trip14 = NodeIdx::service_from(19);
+    let (schedule, vehicle) = Schedule::empty(network)
+        .spawn_vehicle_for_path(vt1, vec![trip14])
+        .unwrap();
+    let not_a_vehicle = VehicleIdx::vehicle_from(42);
+
+    // ACT
+    let not_a_vehicle_error = schedule.replace_vehicle_by_dummy(not_a_vehicle);
+    let not_a_dummy_error = schedule.spawn_vehicle_to_replace_dummy_tour(vehicle, vt1);
+    let incompatible_error = schedule.spawn_vehicle_for_path(vt2, vec![trip31]);
+    let formation_too_long_error = schedule.spawn_vehicle_for_path(vt1, vec![trip14]);
+
+    // ASSERT
+    assert_eq!(
+        not_a_vehicle_error.err().unwrap(),
+        ScheduleError::NotAVehicle(format!(
+            "Cannot delete vehicle {} from schedule.",
+            not_a_vehicle
+        ))
+    );
+    assert!(matches!(
+        not_a_dummy_error.err().unwrap(),
+        ScheduleError::NotADummy(_)
+    ));
+    assert!(matches!(
+        incompatible_error.err().unwrap(),
+        ScheduleError::IncompatibleVehicleType(_)
+    ));
+    let formation_too_long_error = formation_too_long_error.err().unwrap();
+    assert!(matches!(
+        formation_too_long_error,
+        ScheduleError::FormationTooLong(_)
+    ));
+    // Display and the conversion to String keep the message
+    assert_eq!(
+        formation_too_long_error.to_string(),
+        String::from(formation_too_long_error.clone())
+    );
+    assert!(formation_too_long_error
+        .to_string()
+        .ends_with("Formation is full."));
+}
+
+#[test]
+fn total_idle_time_at_depots_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = Schedule::empty(d.network.clone());
+    let (horizon_start, horizon_end) = d.network.time_horizon();
+
+    // ACT
+    let (schedule_with_vehicle, vehicle) = schedule
+        .spawn_vehicle_for_path(d.vt1, vec![d.start_depot1, d.trip12, d.end_depot1])
+        .unwrap();
+
+    // ASSERT
+    assert_eq!(schedule.total_idle_time_at_depots(), Duration::ZERO);
+    let tour = schedule_with_vehicle.tour_of(vehicle).unwrap();
+    let despawn = DateTime::new("2020-01-01T06:30:00")
+        + d.network.dead_head_time_between(d.trip12, d.end_depot1);
+    assert_eq!(tour.end_time(), despawn);
+    assert_eq!(
+        schedule_with_vehicle.total_idle_time_at_depots(),
+        (tour.start_time() - horizon_start) + (horizon_end - despawn)
+    );
+    assert_eq!(
+        schedule_with_vehicle.total_idle_time_at_depots() + (despawn - tour.start_time()),
+        horizon_end - horizon_start
+    );
+}
+
+#[test]
+fn same_real_assignment_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let with_dummy_for = |trip: NodeIdx| {
+        let (schedule_with_vehicle, vehicle) =
+            schedule.spawn_vehicle_for_path(d.vt1, vec![trip]).unwrap();
+        (
+            schedule_with_vehicle
+                .replace_vehicle_by_dummy(vehicle)
+                .unwrap(),
+            schedule_with_vehicle,
+        )
+    };
+    let (schedule_with_dummy1, schedule_with_vehicle) = with_dummy_for(d.trip45_fast);
+    let (schedule_with_dummy2, _) = with_dummy_for(d.trip45);
+
+    // ACT
+    let same_real_assignment = schedule_with_dummy1.same_real_assignment(&schedule_with_dummy2);
+    let same_with_vehicle = schedule.same_real_assignment(&schedule_with_vehicle);
+
+    // ASSERT
+    assert_eq!(schedule_with_dummy1.number_of_dummy_tours(), 1);
+    assert_eq!(schedule_with_dummy2.number_of_dummy_tours(), 1);
+    assert!(same_real_assignment);
+    assert!(schedule_with_dummy1 != schedule_with_dummy2);
+    assert!(schedule.same_real_assignment(&schedule_with_dummy1));
+    assert!(!same_with_vehicle);
+}
+
+#[test]
+fn infinite_depot_capacity_never_blocks_spawning_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    // depot1 has a capacity of 2 (vt1: 2) in the test instance
+    input_data["depots"][0]["capacity"] = json!("Infinity");
+    input_data["depots"][0]["allowedTypes"][0]["capacity"] = json!("Infinity");
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let d = TestData {
+        network,
+        ..init_test_data()
+    };
+    let mut schedule = Schedule::empty(d.network.clone());
+
+    // ACT
+    let mut can_spawn = Vec::new();
+    let mut start_depots = Vec::new();
+    for _ in 0..6 {
+        can_spawn.push(schedule.can_depot_spawn_vehicle(d.start_depot1, d.vt1));
+        let (new_schedule, vehicle) = schedule
+            .spawn_vehicle_for_path(d.vt1, vec![d.start_depot1, d.trip12, d.end_depot1])
+            .unwrap();
+        start_depots.push(
+            new_schedule
+                .tour_of(vehicle)
+                .unwrap()
+                .start_depot()
+                .unwrap(),
+        );
+        schedule = new_schedule;
+    }
+
+    // ASSERT
+    assert!(can_spawn.iter().all(|&can_spawn| can_spawn));
+    assert!(start_depots.iter().all(|&depot| depot == d.start_depot1)); // no overflow depot
+    assert!(schedule.can_depot_spawn_vehicle(d.start_depot1, d.vt1));
+    assert_eq!(
+        schedule.number_of_vehicles_of_same_type_spawned_at(d.depot1, d.vt1),
+        6
+    );
+    assert!(schedule.validity_report().is_feasible());
+}
+
+#[test]
+fn dominated_end_depot_is_consolidated_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    // depot6 is at the same location as depot1 but has less capacity for all vehicle types
+    input_data["depots"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"id": "depot6",
+        "location": "loc1", "capacity": 1, "allowedTypes": [{"vehicleType": "vt1",
+        "capacity": 1}]}));
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let depot = |id: &str| {
+        network
+            .depots_iter()
+            .find(|&depot_idx| network.get_depot(depot_idx).id() == id)
+            .unwrap()
+    };
+    let (depot1, depot6) = (depot("depot1"), depot("depot6"));
+    let trip12 = network
+        .all_service_nodes()
+        .find(|&node| network.node(node).id() == "dep_segment_1-2")
+        .unwrap();
+    let vt = VehicleTypeIdx::from(0);
+    let mut schedule = Schedule::empty(network.clone());
+    for _ in 0..3 {
+        schedule = schedule.spawn_vehicle_for_path(vt, vec![trip12]).unwrap().0;
+    }
+
+    // ACT
+    let schedule = schedule.reassign_end_depots_consistent_with_transitions();
+
+    // ASSERT
+    schedule.verify_consistency();
+    assert_equal(network.dominated_end_depots(), [(depot6, depot1)]);
+    assert_eq!(network.end_depot_for(depot6), depot1);
+    assert_eq!(
+        network.get_end_depot_node(depot6),
+        network.get_end_depot_node(depot1)
+    );
+    assert_eq!(network.end_depot_nodes().count(), 6); // 5 depots and the overflow depot
+    assert_eq!(schedule.number_of_vehicles_spawned_at(depot1), 2);
+    assert_eq!(schedule.number_of_vehicles_spawned_at(depot6), 1);
+    for vehicle in schedule.vehicles_iter_all() {
+        let end_depot = schedule.tour_of(vehicle).unwrap().end_depot().unwrap();
+        assert_eq!(network.get_depot_idx(end_depot), depot1);
+    }
+    assert_eq!(schedule.depot_balance(depot1, vt), 0);
+    assert_eq!(schedule.total_depot_balance_violation(), 0);
+    assert_eq!(schedule.non_home_spawn_penalty(), 0);
+    assert_eq!(
+        schedule_to_json(&schedule)["consolidatedEndDepots"],
+        json!([{"depot": "depot6", "replacedBy": "depot1"}])
+    );
+}
+
+#[test]
+fn compact_ids_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let paths = [
+        vec![
+            d.start_depot1,
+            d.trip12,
+            d.trip23,
+            d.trip34,
+            d.trip45,
+            d.trip51,
+            d.end_depot2,
+        ],
+        vec![d.start_depot2, d.trip31, d.trip14, d.end_depot1],
+        vec![d.start_depot1, d.trip12, d.trip23, d.trip31, d.end_depot2],
+    ];
+    let schedule_spawned_in = |order: [usize; 3], dummy_first: bool| {
+        let mut schedule = Schedule::empty(d.network.clone());
+        if dummy_first {
+            schedule = schedule.add_dummy_for_path(vec![d.trip45_fast]).unwrap();
+        }
+        for idx in order {
+            schedule = schedule
+                .spawn_vehicle_for_path(d.vt1, paths[idx].clone())
+                .unwrap()
+                .0;
+        }
+        if !dummy_first {
+            schedule = schedule.add_dummy_for_path(vec![d.trip45_fast]).unwrap();
+        }
+        schedule
+    };
+    let schedule1 = schedule_spawned_in([0, 1, 2], false);
+    let schedule2 = schedule_spawned_in([2, 1, 0], true);
+
+    // ACT
+    let compacted1 = schedule1.compact_ids();
+    let compacted2 = schedule2.compact_ids();
+
+    // ASSERT
+    compacted1.verify_consistency();
+    compacted2.verify_consistency();
+    assert_ne!(schedule_to_json(&schedule1), schedule_to_json(&schedule2));
+    assert_eq!(schedule_to_json(&compacted1), schedule_to_json(&compacted2));
+    assert_equal(
+        compacted1.vehicles_iter_all(),
+        (0..3).map(VehicleIdx::vehicle_from),
+    );
+    assert_equal(compacted1.dummy_iter(), [VehicleIdx::dummy_from(3)]);
+    assert_equal(
+        compacted1.train_formation_of(d.trip12).ids(),
+        [VehicleIdx::vehicle_from(0), VehicleIdx::vehicle_from(1)],
+    );
+    assert_eq!(
+        compacted1.number_of_vehicles(),
+        schedule1.number_of_vehicles()
+    );
+    assert_eq!(
+        compacted1.maintenance_violation(),
+        schedule1.maintenance_violation()
+    );
+    assert_eq!(compacted1.costs(), schedule1.costs());
+}
+
+#[test]
+fn compact_ids_keeps_roster_units_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let mut input_data = load_test_input();
+    input_data["vehicleRoster"] = json!([{"id": "bike_unit", "vehicleType": "vt1", "seats": 24}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let paths = [
+        vec![d.start_depot1, d.trip12, d.trip23, d.trip31, d.end_depot2],
+        vec![
+            d.start_depot1,
+            d.trip12,
+            d.trip23,
+            d.trip34,
+            d.trip45,
+            d.trip51,
+            d.end_depot2,
+        ],
+        vec![d.start_depot2, d.trip31, d.trip14, d.end_depot1],
+    ];
+    let mut schedule = Schedule::empty(network.clone());
+    for path in paths.iter() {
+        schedule = schedule
+            .spawn_vehicle_for_path(d.vt1, path.clone())
+            .unwrap()
+            .0;
+    }
+    let unit_vehicle = |schedule: &Schedule| {
+        schedule
+            .vehicles_iter_all()
+            .find(|&vehicle| schedule.get_vehicle(vehicle).unwrap().roster_unit() == Some(0))
+            .unwrap()
+    };
+
+    // ACT
+    let compacted = schedule.compact_ids();
+
+    // ASSERT
+    compacted.verify_consistency();
+    // the first spawned vehicle took over the unit and keeps it (and its seats) under its new id
+    assert_equal(
+        compacted
+            .tour_of(unit_vehicle(&compacted))
+            .unwrap()
+            .all_nodes_iter(),
+        paths[0].iter().copied(),
+    );
+    assert_equal(
+        schedule
+            .tour_of(unit_vehicle(&schedule))
+            .unwrap()
+            .all_nodes_iter(),
+        paths[0].iter().copied(),
+    );
+    assert_eq!(
+        compacted
+            .get_vehicle(unit_vehicle(&compacted))
+            .unwrap()
+            .seats(),
+        24
+    );
+    for service_trip in network.all_service_nodes() {
+        assert_eq!(
+            compacted.train_formation_of(service_trip).seats(),
+            schedule.train_formation_of(service_trip).seats()
+        );
+    }
+    let recomputed = network
+        .all_service_nodes()
+        .map(|service_trip| compacted.unserved_passengers_at(service_trip))
+        .fold((0, 0), |acc, (unfit, unseated)| {
+            (acc.0 + unfit, acc.1 + unseated)
+        });
+    assert_eq!(compacted.unserved_passengers(), recomputed);
+    assert_eq!(
+        compacted.unserved_passengers(),
+        schedule.unserved_passengers()
+    );
+    assert_eq!(
+        schedule_to_json(&compacted)["fleet"]
+            .to_string()
+            .matches("bike_unit")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn swap_vehicle_ids_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let veh1 = VehicleIdx::vehicle_from(1);
+    let veh2 = VehicleIdx::vehicle_from(2);
+
+    // ACT
+    let swapped = schedule.swap_vehicle_ids(veh0, veh2).unwrap();
+    let swapped_back = swapped.swap_vehicle_ids(veh2, veh0).unwrap();
+
+    // ASSERT
+    swapped.verify_consistency();
+    for (old_id, new_id) in [(veh0, veh2), (veh1, veh1), (veh2, veh0)] {
+        assert_equal(
+            swapped.tour_of(new_id).unwrap().all_nodes_iter(),
+            schedule.tour_of(old_id).unwrap().all_nodes_iter(),
+        );
+    }
+    assert_equal(swapped.train_formation_of(d.trip12).ids(), [veh2, veh0]);
+    assert_equal(swapped.train_formation_of(d.trip34).ids(), [veh2]);
+    assert_equal(swapped.vehicles_iter_all(), [veh0, veh1, veh2]);
+    assert_eq!(swapped.costs(), schedule.costs());
+    assert_eq!(schedule_to_json(&swapped_back), schedule_to_json(&schedule));
+    assert!(schedule
+        .swap_vehicle_ids(veh0, VehicleIdx::dummy_from(3))
+        .is_err());
+}
+
+#[test]
+fn assignments_keyed_by_original_trip_id_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let assigned = |vehicle: Idx| json!({"vehicle": VehicleIdx::vehicle_from(vehicle).to_string(), "vehicleType": "vt1"});
+
+    // ACT
+    let assignments = schedule_to_json(&schedule)["assignments"].clone();
+
+    // ASSERT
+    assert_eq!(d.network.original_id_of(d.trip34), "dep_segment_3-4");
+    assert_eq!(assignments["dep_segment_3-4"], json!([assigned(0)]));
+    assert_eq!(
+        assignments["dep_segment_1-2"],
+        json!([assigned(0), assigned(2)])
+    );
+    assert_eq!(assignments["dep_segment_4-5fast"], json!([]));
+    assert_eq!(assignments.as_object().unwrap().len(), 8);
+}
+
+#[test]
+fn unserved_demand_grouped_by_location_and_time_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let empty_schedule = Schedule::empty(schedule.get_network());
+
+    for schedule in [schedule, empty_schedule] {
+        // ACT
+        let unserved_demand = schedule_to_json(&schedule)["unservedDemand"].clone();
+
+        // ASSERT
+        let groups = unserved_demand.as_array().unwrap();
+        assert!(!groups.is_empty());
+        let total = groups
+            .iter()
+            .map(|group| group["unservedPassengers"].as_u64().unwrap() as u32)
+            .sum::<u32>();
+        let total_seated = groups
+            .iter()
+            .map(|group| group["unservedSeatedPassengers"].as_u64().unwrap() as u32)
+            .sum::<u32>();
+        assert_eq!((total, total_seated), schedule.unserved_passengers());
+        for group in groups {
+            let bucket_start = DateTime::new(group["bucketStart"].as_str().unwrap());
+            let bucket_end = DateTime::new(group["bucketEnd"].as_str().unwrap());
+            assert_eq!(bucket_end - bucket_start, Duration::from_seconds(3600));
+        }
+        assert!(groups
+            .iter()
+            .map(|group| (group["location"].clone(), group["bucketStart"].clone()))
+            .all_unique());
+    }
+}
+
+#[test]
+fn add_repositioning_leg_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let tour = schedule.tour_of(veh0).unwrap();
+
+    // ACT
+    let repositioned_schedule = schedule.add_repositioning_leg(veh0, d.depot3).unwrap();
+
+    // ASSERT
+    repositioned_schedule.verify_consistency();
+    let repositioned_tour = repositioned_schedule.tour_of(veh0).unwrap();
+    assert_eq!(repositioned_tour.repositioning(), Some(d.end_depot3));
+    assert_eq!(repositioned_tour.end_depot().unwrap(), d.end_depot2);
+    assert_eq!(repositioned_tour.final_depot().unwrap(), d.end_depot3);
+    assert_eq!(
+        repositioned_tour.dead_head_distance(),
+        tour.dead_head_distance() + Distance::from_meter(23000) // loc2 -> loc3
+    );
+    assert!(repositioned_tour.costs() > tour.costs());
+    assert_eq!(
+        repositioned_schedule.costs(),
+        schedule.costs() + repositioned_tour.costs() - tour.costs()
+    );
+
+    let json = schedule_to_json(&repositioned_schedule);
+    let vehicle = json["fleet"][0]["vehicles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|vehicle| vehicle["id"] == veh0.to_string())
+        .unwrap();
+    assert_eq!(vehicle["endDepot"], "depot2");
+    assert_eq!(vehicle["repositioningDepot"], "depot3");
+    let repositioning_leg = vehicle["deadHeadTrips"].as_array().unwrap().last().unwrap();
+    assert_eq!(repositioning_leg["origin"], "loc2");
+    assert_eq!(repositioning_leg["destination"], "loc3");
+    assert_eq!(
+        DateTime::new(repositioning_leg["arrival"].as_str().unwrap())
+            - DateTime::new(repositioning_leg["departure"].as_str().unwrap()),
+        Duration::from_seconds(2700)
+    );
+    assert!(json["deadHeadTrips"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(
+            |dead_head_trip| dead_head_trip["id"] == repositioning_leg["id"]
+                && dead_head_trip["destination"] == "loc3"
+        ));
+
+    assert!(tour.add_repositioning(d.end_depot2).is_err()); // same location
+    assert!(tour.add_repositioning(d.start_depot3).is_err()); // not an end depot
+}
+
+#[test]
+fn covered_and_uncovered_service_trips_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+
+    // ACT
+    let covered: Vec<NodeIdx> = schedule.covered_service_trips_iter().collect();
+    let uncovered: Vec<NodeIdx> = schedule.unserved_service_trips().collect();
+
+    // ASSERT
+    assert!(covered.contains(&d.trip12));
+    assert!(covered.contains(&d.trip31));
+    assert!(uncovered.contains(&d.trip34));
+    assert!(uncovered.contains(&d.trip14));
+    assert!(covered.iter().all(|&trip| schedule.is_fully_covered(trip)));
+    assert!(uncovered
+        .iter()
+        .all(|&trip| !schedule.is_fully_covered(trip)));
+    assert_equal(
+        covered.iter().chain(uncovered.iter()).copied().sorted(),
+        d.network.all_service_nodes().sorted(),
+    );
+}
+
+#[test]
+fn unserved_passengers_iter_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+
+    // ACT
+    let unserved: Vec<(NodeIdx, (PassengerCount, PassengerCount))> =
+        schedule.unserved_passengers_iter().collect();
+
+    // ASSERT
+    assert_equal(
+        unserved.iter().map(|&(trip, _)| trip),
+        schedule.unserved_service_trips(),
+    );
+    assert!(unserved.iter().all(|&(trip, _)| trip != d.trip12)); // fully covered
+    let (_, deficit) = unserved
+        .iter()
+        .find(|&&(trip, _)| trip == d.trip34)
+        .unwrap();
+    assert_eq!(*deficit, schedule.unserved_passengers_at(d.trip34));
+    assert_ne!(*deficit, (0, 0));
+    assert_eq!(
+        unserved
+            .iter()
+            .fold((0, 0), |acc, &(_, (unfit, unseated))| (
+                acc.0 + unfit,
+                acc.1 + unseated
+            )),
+        schedule.unserved_passengers()
+    );
+}
+
+#[test]
+fn unserved_service_trips_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+
+    // ACT
+    let unserved: Vec<NodeIdx> = schedule.unserved_service_trips().collect();
+    let partially_served: Vec<(NodeIdx, PassengerCount)> =
+        schedule.partially_served_service_trips().collect();
+
+    // ASSERT
+    assert!(unserved
+        .iter()
+        .all(|&trip| !schedule.is_fully_covered(trip)));
+    assert!(!unserved.contains(&d.trip12)); // fully covered
+    assert!(unserved.contains(&d.trip34));
+    let (unfit, unseated) = schedule.unserved_passengers_at(d.trip34);
+    assert!(partially_served.contains(&(d.trip34, unfit.max(unseated))));
+    assert!(partially_served.iter().all(|&(trip, deficit)| {
+        unserved.contains(&trip)
+            && deficit > 0
+            && schedule.train_formation_of(trip).vehicle_count() > 0
+    }));
+}
+
+#[test]
+fn home_vehicle_at_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh1 = VehicleIdx::vehicle_from(1);
+    let veh2 = VehicleIdx::vehicle_from(2);
+
+    // ACT
+    let homed_schedule = schedule.home_vehicle_at(veh2, d.depot1).unwrap();
+
+    // ASSERT
+    homed_schedule.verify_consistency();
+    let homed_tour = homed_schedule.tour_of(veh2).unwrap();
+    assert_eq!(homed_tour.start_depot().unwrap(), d.start_depot1);
+    assert_eq!(homed_tour.end_depot().unwrap(), d.end_depot1);
+    assert_eq!(schedule.total_depot_balance_violation(), 2);
+    assert_eq!(homed_schedule.total_depot_balance_violation(), 0);
+    assert_eq!(homed_schedule.depot_balance(d.depot1, d.vt1), 0);
+    assert_eq!(homed_schedule.depot_balance(d.depot2, d.vt1), 0);
+    assert_eq!(
+        homed_schedule.costs(),
+        schedule.costs() + homed_tour.costs() - schedule.tour_of(veh2).unwrap().costs()
+    );
+
+    // depot1 can only spawn two vehicles of vt1
+    assert!(matches!(
+        schedule.home_vehicle_at(veh1, d.depot1),
+        Err(ScheduleError::DepotUnavailable(_))
+    ));
+}
+
+#[test]
+fn export_for_simulation_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+    let veh1 = VehicleIdx::vehicle_from(1);
+    let veh2 = VehicleIdx::vehicle_from(2);
+
+    // ACT
+    let events = schedule.export_for_simulation();
+
+    // ASSERT
+    for (event1, event2) in events.iter().tuple_windows() {
+        assert!((event1.time, event1.kind) <= (event2.time, event2.kind));
+    }
+    for vehicle in schedule.vehicles_iter_all() {
+        for node in schedule
+            .tour_of(vehicle)
+            .unwrap()
+            .all_non_depot_nodes_iter()
+        {
+            let trip = d.network.node(node);
+            let count = |kind: SimulationEventKind, time: DateTime| {
+                events
+                    .iter()
+                    .filter(|event| {
+                        event.vehicle == vehicle
+                            && event.kind == kind
+                            && event.time == time
+                            && event.label == trip.id()
+                    })
+                    .count()
+            };
+            assert_eq!(count(SimulationEventKind::Depart, trip.start_time()), 1);
+            assert_eq!(count(SimulationEventKind::Arrive, trip.end_time()), 1);
+        }
+    }
+    let departures = events
+        .iter()
+        .filter(|event| event.kind == SimulationEventKind::Depart)
+        .count();
+    let arrivals = events
+        .iter()
+        .filter(|event| event.kind == SimulationEventKind::Arrive)
+        .count();
+    assert_eq!(departures, arrivals);
+
+    // veh0 and veh2 operate trip12 and trip23 together, veh1 and veh2 operate trip31 together
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let label = |trip: NodeIdx, partner: VehicleIdx| {
+        format!("{} with {}", d.network.node(trip).id(), partner)
+    };
+    let couplings: Vec<(SimulationEventKind, VehicleIdx, String)> = events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event.kind,
+                SimulationEventKind::Couple | SimulationEventKind::Decouple
+            )
+        })
+        .map(|event| (event.kind, event.vehicle, event.label.clone()))
+        .collect();
+    assert_eq!(
+        couplings,
+        vec![
+            (SimulationEventKind::Couple, veh0, label(d.trip12, veh2)),
+            (SimulationEventKind::Couple, veh2, label(d.trip12, veh0)),
+            (SimulationEventKind::Decouple, veh0, label(d.trip23, veh2)),
+            (SimulationEventKind::Decouple, veh2, label(d.trip23, veh0)),
+            (SimulationEventKind::Couple, veh1, label(d.trip31, veh2)),
+            (SimulationEventKind::Couple, veh2, label(d.trip31, veh1)),
+            (SimulationEventKind::Decouple, veh1, label(d.trip31, veh2)),
+            (SimulationEventKind::Decouple, veh2, label(d.trip31, veh1)),
+        ]
+    );
+}
+
+#[test]
+fn unserved_passengers_running_total_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let veh1 = VehicleIdx::vehicle_from(1);
+    let veh2 = VehicleIdx::vehicle_from(2);
+    let fresh_computation = |schedule: &Schedule| {
+        Schedule::compute_unserved_passengers(&schedule.network, &schedule.train_formations)
+    };
+
+    // ACT
+    let mut schedules = vec![default_schedule(&d)];
+    schedules.push(schedules[0].replace_vehicle_by_dummy(veh2).unwrap());
+    let dummy = schedules[1].dummy_iter().next().unwrap();
+    schedules.push(
+        schedules[1]
+            .spawn_vehicle_to_replace_dummy_tour(dummy, d.vt1)
+            .unwrap()
+            .0,
+    );
+    schedules.push(
+        schedules[2]
+            .remove_segment(Segment::new(d.trip31, d.trip31), veh1)
+            .unwrap(),
+    );
+    schedules.push(
+        schedules[3]
+            .split_tour_into_single_node_tours(veh0)
+            .unwrap(),
+    );
+    schedules.push(schedules[4].merge_compatible_dummies());
+
+    // ASSERT
+    for schedule in schedules.iter() {
+        assert_eq!(schedule.unserved_passengers(), fresh_computation(schedule));
+    }
+    // the modifications change the unserved passengers
+    assert!(!schedules
+        .iter()
+        .map(|schedule| schedule.unserved_passengers())
+        .all_equal());
+}
+
+#[test]
+fn min_vehicles_violation_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    let route = input_data["routes"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|route| route["id"] == "route_1-4")
+        .unwrap();
+    route["minVehicles"] = json!(2);
+    let d = TestData {
+        network: load_rolling_stock_problem_instance_from_json(input_data),
+        ..init_test_data()
+    };
+    let schedule = default_schedule(&d); // trip14 is only operated by veh1
+    let veh2 = VehicleIdx::vehicle_from(2);
+
+    // ACT
+    let (schedule_with_second_vehicle, _) = schedule
+        .add_path_to_vehicle_tour(
+            veh2,
+            Path::new_from_single_node(d.trip14, d.network.clone()),
+        )
+        .unwrap();
+
+    // ASSERT
+    assert_eq!(
+        d.network.node(d.trip14).as_service_trip().min_vehicles(),
+        Some(2)
+    );
+    assert_eq!(schedule.train_formation_of(d.trip14).vehicle_count(), 1);
+    assert_eq!(schedule.min_vehicles_violation(), 1);
+    assert_eq!(
+        schedule_with_second_vehicle
+            .train_formation_of(d.trip14)
+            .vehicle_count(),
+        2
+    );
+    assert_eq!(schedule_with_second_vehicle.min_vehicles_violation(), 0);
+    assert_eq!(
+        default_schedule(&init_test_data()).min_vehicles_violation(),
+        0
+    );
+}
+
+#[test]
+fn from_tours_with_exhausted_fixed_fleet_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["fixedFleet"] = json!([{"depot": "depot1", "vehicleType": "vt1", "count": 1}]);
+    let d = TestData {
+        network: load_rolling_stock_problem_instance_from_json(input_data),
+        ..init_test_data()
+    };
+    let tours = HashMap::from([(
+        d.vt1,
+        vec![
+            vec![d.start_depot1, d.trip12, d.end_depot1],
+            vec![d.start_depot1, d.trip34, d.end_depot1],
+        ],
+    )]);
+
+    // ACT
+    let result = Schedule::from_tours(tours, d.network.clone());
+
+    // ASSERT
+    assert!(result.err().unwrap().contains("Fleet is fixed"));
+}
+
+#[test]
+fn max_dummy_tours_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["maxDummyTours"] = json!(1);
+    let d = TestData {
+        network: load_rolling_stock_problem_instance_from_json(input_data),
+        ..init_test_data()
+    };
+    let schedule = default_schedule(&d);
+    let veh0 = VehicleIdx::vehicle_from(0);
+    let veh1 = VehicleIdx::vehicle_from(1);
+
+    // ACT
+    let schedule_with_one_dummy = schedule.replace_vehicle_by_dummy(veh0).unwrap();
+    let result = schedule_with_one_dummy.replace_vehicle_by_dummy(veh1);
+
+    // ASSERT
+    assert_eq!(schedule_with_one_dummy.number_of_dummy_tours(), 1);
+    assert_eq!(
+        result.err().unwrap(),
+        ScheduleError::Infeasible(String::from(
+            "Instance seems to be infeasible: more than 1 dummy tours needed, 7 service trips remain uncovered."
+        ))
+    );
+}
+
+#[test]
+fn remove_idle_vehicles_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["maintenanceSlots"] = json!([{"id": "maintenance_loc2", "location": "loc2",
+        "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00", "trackCount": 2}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let maintenance = network.maintenance_nodes().next().unwrap();
+    let trip12 = NodeIdx::service_from(12);
+    let (schedule, busy_vehicle) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![trip12])
+        .unwrap();
+    let (schedule, idle_vehicle) = schedule
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![maintenance])
+        .unwrap();
+
+    // ACT
+    let pruned_schedule = schedule.remove_idle_vehicles();
+
+    // ASSERT
+    assert_eq!(schedule.idle_vehicles(), vec![idle_vehicle]);
+    assert!(pruned_schedule.idle_vehicles().is_empty());
+    assert_equal(pruned_schedule.vehicles_iter_all(), vec![busy_vehicle]);
+    assert_eq!(pruned_schedule.number_of_dummy_tours(), 0);
+    assert_eq!(
+        pruned_schedule.costs(),
+        schedule.costs() - schedule.tour_of(idle_vehicle).unwrap().costs()
+    );
+    assert_eq!(
+        pruned_schedule
+            .train_formation_of(maintenance)
+            .vehicle_count(),
+        0
+    );
+    pruned_schedule.verify_consistency();
+}
+
+#[test]
+fn vehicles_at_depot_over_time_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d);
+
+    // ACT
+    let timeline_depot1 = schedule.vehicles_at_depot_over_time(d.depot1);
+    let timeline_depot2 = schedule.vehicles_at_depot_over_time(d.depot2);
+
+    // ASSERT
+    // veh0 and veh2 leave depot1 at the same time, veh1 arrives later
+    assert_eq!(
+        timeline_depot1,
+        vec![
+            (DateTime::Earliest, 2),
+            (DateTime::new("2020-01-01T06:00:00"), 0),
+            (DateTime::new("2020-01-01T10:15:00"), 1),
+        ]
+    );
+    // veh1 leaves depot2, veh0 and veh2 arrive
+    assert_eq!(timeline_depot2.first(), Some(&(DateTime::Earliest, 1)));
+    assert_eq!(timeline_depot2.last().unwrap().1, 2);
+    assert_eq!(
+        timeline_depot2.iter().map(|(_, count)| *count).max(),
+        Some(2)
+    );
+    assert!(timeline_depot2
+        .iter()
+        .tuple_windows()
+        .all(|((t1, c1), (t2, c2))| t1 < t2 && c1 != c2));
+}
+
+#[test]
+fn total_maintenance_distance_violation_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["maintenance"]["maximalDistance"] = json!(1000);
+    let d = TestData {
+        network: load_rolling_stock_problem_instance_from_json(input_data),
+        ..init_test_data()
+    };
+    let schedule = default_schedule(&d);
+
+    // ACT
+    let violation = schedule.total_maintenance_distance_violation();
+
+    // ASSERT
+    let maximal_distance = d.network.maximal_distance_between_maintenance(d.vt1);
+    let expected: Distance = schedule
+        .get_tours()
+        .values()
+        .map(|tour| tour.total_distance().sub_max_zero(maximal_distance))
+        .sum();
+    assert!(violation > Distance::ZERO);
+    assert_eq!(violation, expected);
+    assert_eq!(
+        default_schedule(&init_test_data()).total_maintenance_distance_violation(),
+        Distance::ZERO
+    );
+}
+
+#[test]
+fn shortfall_timeline_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let schedule = default_schedule(&d); // trip34, trip45, trip51 and trip14 lack one vehicle
+    let time = |time: &str| DateTime::new(&format!("2020-01-01T{}", time));
+
+    // ACT
+    let timeline = schedule.shortfall_timeline();
+    let empty_timeline = Schedule::empty(d.network.clone()).shortfall_timeline();
+
+    // ASSERT
+    assert_eq!(
+        timeline,
+        vec![
+            (DateTime::Earliest, 0),
+            (time("08:00:00"), 1),
+            (time("08:30:00"), 0),
+            (time("09:00:00"), 2), // trip45 and trip14 overlap
+            (time("09:30:00"), 0),
+            (time("10:00:00"), 1),
+            (time("10:30:00"), 0),
+        ]
+    );
+    // each trip needs two vehicles for its 80 passengers, trip34 and trip31 overlap
+    assert_eq!(
+        empty_timeline.iter().map(|(_, count)| *count).max(),
+        Some(4)
+    );
+    assert_eq!(empty_timeline.last().unwrap().1, 0);
+}
+
+#[test]
+fn apply_transition_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["maintenanceSlots"] = json!([{"id": "maintenance_loc2", "location": "loc2",
+        "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00", "trackCount": 1}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let maintenance = network.maintenance_nodes().next().unwrap();
+    let vt1 = VehicleTypeIdx::from(0);
+    let (schedule, vehicle12) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(vt1, vec![NodeIdx::service_from(12)])
+        .unwrap();
+    let schedule_without_other_vehicle = schedule.clone();
+    let (schedule, vehicle34) = schedule
+        .spawn_vehicle_for_path(vt1, vec![NodeIdx::service_from(14)])
+        .unwrap();
+    let transition = schedule.next_day_transition_of(vt1).clone();
+    // a vehicle of the other type occupies the only track of the maintenance slot
+    let (schedule_with_occupied_slot, _) = schedule
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(1), vec![maintenance])
+        .unwrap();
+
+    // ACT
+    let applied_schedule = schedule.apply_transition(&transition).unwrap();
+
+    // ASSERT
+    applied_schedule.verify_consistency();
+    assert!(schedule.maintenance_violation() > 0);
+    assert_eq!(applied_schedule.maintenance_violation(), 0);
+    assert_eq!(schedule.train_formation_of(maintenance).vehicle_count(), 0);
+    let maintained_vehicles = applied_schedule.train_formation_of(maintenance).ids();
+    assert_eq!(maintained_vehicles.len(), 1);
+    let maintained_vehicle = maintained_vehicles[0];
+    assert!([vehicle12, vehicle34].contains(&maintained_vehicle));
+    assert!(!schedule
+        .tour_of(maintained_vehicle)
+        .unwrap()
+        .visits_maintenance());
+    assert!(applied_schedule
+        .tour_of(maintained_vehicle)
+        .unwrap()
+        .visits_maintenance());
+    assert!(schedule_with_occupied_slot
+        .apply_transition(&transition)
+        .is_err());
+    assert!(schedule
+        .apply_transition(schedule_without_other_vehicle.next_day_transition_of(vt1))
+        .is_err());
+}
+
+#[test]
+fn validity_report_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let feasible_schedule = default_schedule(&d); // unbalanced depot1 and depot2
+    let (schedule, overflow_vehicle) = feasible_schedule
+        .spawn_vehicle_for_path(d.vt1, vec![d.start_depot1, d.trip45, d.end_depot1])
+        .unwrap(); // depot1 is full, so the vehicle is spawned at the overflow depot
+
+    // ACT
+    let feasible_report = feasible_schedule.validity_report();
+    let report = schedule.validity_report();
+    let validity_json = &schedule_to_json(&schedule)["validity"];
+
+    // ASSERT
+    assert!(feasible_report.is_feasible());
+    assert_eq!(feasible_report.unbalanced_depots, vec![d.depot1, d.depot2]);
+
+    assert_eq!(
+        schedule.tour_of(overflow_vehicle).unwrap().start_depot(),
+        Ok(d.start_overflow_depot)
+    );
+    assert!(!report.is_feasible());
+    assert_eq!(report.hard_violation_count(), 1);
+    assert!(report.unreachable_tours.is_empty());
+    assert_eq!(
+        report.over_capacity_depots,
+        vec![d.network.overflow_depot_idxs().0]
+    );
+    assert!(report.over_length_formations.is_empty());
+    assert_eq!(report.soft_violation_count(), 2);
+    assert_eq!(report.unbalanced_depots, vec![d.depot1, d.depot2]);
+    assert!(report.maintenance_violations.is_empty());
+    assert!(report.idle_vehicles.is_empty());
+
+    assert_eq!(validity_json["feasible"], json!(false));
+    assert_eq!(
+        validity_json["hardViolations"][1]["kind"],
+        "overCapacityDepot"
+    );
+    assert_eq!(validity_json["hardViolations"][1]["count"], 1);
+    assert_eq!(validity_json["softViolations"][0]["kind"], "depotBalance");
+    assert_eq!(validity_json["softViolations"][0]["count"], 2);
+}
+
+#[test]
+fn perturb_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let d = TestData {
+        network: d.network.with_scaled_demand(0.5), // one vehicle suffices for each trip
+        ..d
+    };
+    let schedule = default_schedule(&d);
+
+    // ACT
+    let perturbed = schedule.perturb(5, 42);
+    let perturbed_again = schedule.perturb(5, 42);
+    let unperturbed = schedule.perturb(0, 42);
+
+    // ASSERT
+    assert_eq!(schedule.unserved_passengers(), (0, 0));
+    perturbed.verify_consistency();
+    assert_eq!(perturbed.unserved_passengers(), (0, 0));
+    assert_eq!(perturbed.number_of_dummy_tours(), 0);
+    assert!(perturbed.validity_report().is_feasible());
+    assert!(perturbed.is_identical_to(&perturbed_again));
+    assert!(unperturbed.is_identical_to(&schedule));
+    for seed in 0..20 {
+        let perturbed = schedule.perturb(3, seed);
+        assert_eq!(perturbed.unserved_passengers(), (0, 0));
+        assert!(perturbed.unserved_service_trips().next().is_none());
+    }
+}
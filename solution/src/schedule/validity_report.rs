@@ -0,0 +1,154 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use itertools::Itertools;
+use model::base_types::{DepotIdx, Distance, NodeIdx, VehicleIdx};
+use model::network::nodes::Node;
+
+use super::Schedule;
+
+/// Violations of a schedule grouped by severity. Hard violations make the schedule operationally
+/// unusable as is, soft violations are only penalized by the objective. All lists are sorted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidityReport {
+    // hard violations
+    pub unreachable_tours: Vec<VehicleIdx>, // some node of the tour cannot reach its successor
+    pub over_capacity_depots: Vec<DepotIdx>, // more vehicles spawned than the depot can hold
+    pub over_length_formations: Vec<NodeIdx>, // more vehicles than the formation limit or tracks
+
+    // soft violations
+    pub unbalanced_depots: Vec<DepotIdx>, // (end) depots with a non-zero depot balance
+    pub maintenance_violations: Vec<VehicleIdx>, // exceeding the range or missing a mandatory visit
+    pub idle_vehicles: Vec<VehicleIdx>,   // vehicles without any service trip (no workload)
+}
+
+impl ValidityReport {
+    /// True iff there are no hard violations.
+    pub fn is_feasible(&self) -> bool {
+        self.hard_violation_count() == 0
+    }
+
+    pub fn hard_violation_count(&self) -> usize {
+        self.unreachable_tours.len()
+            + self.over_capacity_depots.len()
+            + self.over_length_formations.len()
+    }
+
+    pub fn soft_violation_count(&self) -> usize {
+        self.unbalanced_depots.len() + self.maintenance_violations.len() + self.idle_vehicles.len()
+    }
+
+    pub fn print(&self) {
+        println!(
+            "Hard violations: {} (unreachable tours: {}, over-capacity depots: {}, over-length formations: {})",
+            self.hard_violation_count(),
+            self.unreachable_tours.len(),
+            self.over_capacity_depots.len(),
+            self.over_length_formations.len()
+        );
+        println!(
+            "Soft violations: {} (unbalanced depots: {}, maintenance: {}, idle vehicles: {})",
+            self.soft_violation_count(),
+            self.unbalanced_depots.len(),
+            self.maintenance_violations.len(),
+            self.idle_vehicles.len()
+        );
+    }
+}
+
+impl Schedule {
+    /// Aggregates the existing checks into hard and soft violations. Vehicles spawned at the
+    /// overflow depot count as over-capacity, as no real depot can provide them.
+    pub fn validity_report(&self) -> ValidityReport {
+        ValidityReport {
+            unreachable_tours: self.unreachable_tours(),
+            over_capacity_depots: self.over_capacity_depots(),
+            over_length_formations: self.over_length_formations(),
+            unbalanced_depots: self.unbalanced_depots(),
+            maintenance_violations: self.maintenance_violating_vehicles(),
+            idle_vehicles: self.idle_vehicles(),
+        }
+    }
+
+    fn unreachable_tours(&self) -> Vec<VehicleIdx> {
+        self.vehicles_iter_all()
+            .filter(|vehicle| {
+                self.tours[vehicle]
+                    .all_nodes_iter()
+                    .tuple_windows()
+                    .any(|(node, successor)| !self.network.can_reach(node, successor))
+            })
+            .sorted()
+            .collect()
+    }
+
+    fn over_capacity_depots(&self) -> Vec<DepotIdx> {
+        let overflow_depot = self.network.overflow_depot_idxs().0;
+        self.network
+            .depots_iter()
+            .filter(|&depot| {
+                if depot == overflow_depot {
+                    return self.number_of_vehicles_spawned_at(depot) > 0;
+                }
+                self.number_of_vehicles_spawned_at(depot) > self.network.total_capacity_of(depot)
+                    || self.network.vehicle_types().iter().any(|vehicle_type| {
+                        self.number_of_vehicles_of_same_type_spawned_at(depot, vehicle_type)
+                            > self.network.capacity_of(depot, vehicle_type)
+                    })
+            })
+            .sorted()
+            .collect()
+    }
+
+    fn over_length_formations(&self) -> Vec<NodeIdx> {
+        self.train_formations
+            .iter()
+            .filter(|(&node, formation)| {
+                let limit = match self.network.node(node) {
+                    Node::Service(_) => self.network.maximal_formation_count_for(node),
+                    Node::Maintenance((_, maintenance_slot)) => {
+                        Some(maintenance_slot.track_count())
+                    }
+                    _ => None,
+                };
+                limit.is_some_and(|limit| formation.vehicle_count() > limit)
+            })
+            .map(|(&node, _)| node)
+            .sorted()
+            .collect()
+    }
+
+    fn unbalanced_depots(&self) -> Vec<DepotIdx> {
+        self.depot_usage
+            .keys()
+            .map(|(depot, vehicle_type)| (self.network.end_depot_for(*depot), *vehicle_type))
+            .filter(|&(depot, vehicle_type)| self.depot_balance(depot, vehicle_type) != 0)
+            .map(|(depot, _)| depot)
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    fn maintenance_violating_vehicles(&self) -> Vec<VehicleIdx> {
+        let mandatory_visit = self.network.config().maintenance.mandatory_daily_visit;
+        self.tours
+            .iter()
+            .filter(|(&vehicle, tour)| {
+                self.maintenance_distance_violation_of(vehicle, tour) > Distance::ZERO
+                    || (mandatory_visit && !tour.visits_maintenance())
+            })
+            .map(|(&vehicle, _)| vehicle)
+            .sorted()
+            .collect()
+    }
+}
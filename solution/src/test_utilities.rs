@@ -50,14 +50,17 @@ pub struct TestData {
     pub end_overflow_depot: NodeIdx,
 }
 
-pub fn init_test_data() -> TestData {
-    // load file from json
-    let path = "resources/test_instance.json";
-
-    let mut file = File::open(path).unwrap();
+/// Reads resources/test_instance.json (relative to the solution crate), e.g., to modify the input
+/// before loading the network.
+pub fn load_test_input() -> serde_json::Value {
+    let mut file = File::open("resources/test_instance.json").unwrap();
     let mut input_data = String::new();
     file.read_to_string(&mut input_data).unwrap();
-    let input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    serde_json::from_str(&input_data).unwrap()
+}
+
+pub fn init_test_data() -> TestData {
+    let input_data = load_test_input();
     let network = load_rolling_stock_problem_instance_from_json(input_data);
     TestData {
         network,
@@ -14,14 +14,18 @@
 mod modifications;
 #[cfg(test)]
 mod tests;
+mod tour_error;
 use crate::path::Path;
 use crate::segment::Segment;
 use model::base_types::{Cost, Distance, MaintenanceCounter, NodeIdx, INF_DISTANCE};
 use model::network::nodes::Node;
 use model::network::Network;
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use rapid_time::{DateTime, Duration};
+pub use tour_error::TourError;
 
 use itertools::Itertools;
 
@@ -29,6 +33,19 @@ use std::sync::Arc;
 
 type Position = usize; // the position within the tour from 0 to nodes.len()-1
 
+/// Timing of a node within a tour (see Tour::timeline). The time between the departure at this
+/// node and the arrival at the next node is split into dead head time, shunting time (including
+/// turnarounds) and idle time. All three are zero for the last node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NodeTiming {
+    pub node: NodeIdx,
+    pub arrival: DateTime,
+    pub departure: DateTime,
+    pub dead_head_after: Duration,
+    pub shunting_after: Duration,
+    pub idle_after: Duration,
+}
+
 /// This represents a tour of a single vehicle (or a dummy tour). The following holds at all times:
 ///
 /// The tour is a path in the network (implying that there are no intermediate depots).
@@ -56,6 +73,11 @@ pub struct Tour {
     // + dead_head_trip_duration * costs.dead_head_trip
     // + idle_time * costs.idle
     costs: Cost,
+    maintenance_counter: MaintenanceCounter, // derived from the distances, see maintenance_counter()
+    // end depot node to which the vehicle is repositioned (empty) after reaching the end depot of
+    // the tour; dead_head_distance and costs above do not include this leg
+    repositioning: Option<NodeIdx>,
+    nodes_hash: u64, // hash of the nodes, computed once as the tour is immutable
     network: Arc<Network>,
 }
 
@@ -73,6 +95,12 @@ impl Tour {
         self.nodes.len()
     }
 
+    /// Hash of the nodes of the tour (see Schedule::fingerprint). It is computed when the tour is
+    /// created, so unchanged tours are never hashed again.
+    pub fn nodes_hash(&self) -> u64 {
+        self.nodes_hash
+    }
+
     pub fn all_nodes_iter(&self) -> impl Iterator<Item = NodeIdx> + '_ {
         self.nodes.iter().copied()
     }
@@ -86,9 +114,9 @@ impl Tour {
         }
     }
 
-    /// total dead-head distance traveled by the tour
+    /// total dead-head distance traveled by the tour (including the repositioning leg)
     pub fn dead_head_distance(&self) -> Distance {
-        self.dead_head_distance
+        self.dead_head_distance + self.repositioning_distance()
     }
 
     /// total useful duration of the tour (service time + maintenance time)
@@ -96,6 +124,16 @@ impl Tour {
         self.useful_duration
     }
 
+    /// total duration of the service trips of the tour (excluding maintenance, dead head and idle
+    /// time)
+    pub fn service_duration(&self) -> Duration {
+        self.nodes
+            .iter()
+            .filter(|&&node| self.network.node(node).is_service())
+            .map(|&node| self.network.node(node).duration())
+            .sum()
+    }
+
     /// return the service distance (distance of service trips) of the tour
     pub fn service_distance(&self) -> Distance {
         self.service_distance
@@ -103,28 +141,25 @@ impl Tour {
 
     /// return the total distance (service distance + dead head distance) of the tour
     pub fn total_distance(&self) -> Distance {
-        self.service_distance + self.dead_head_distance
+        self.service_distance + self.dead_head_distance()
     }
 
     /// return the maintenance counter of the tour which is the total distance traveled minus the
     /// maximal distance allowed if the tour visits a maintenance node.
+    /// The maximal distance is taken from the vehicle type of the service trips (if the tour
+    /// contains any), otherwise from the config.
     pub fn maintenance_counter(&self) -> MaintenanceCounter {
-        if self.visits_maintenance {
-            self.total_distance().in_meter().unwrap_or(INF_DISTANCE) as MaintenanceCounter
-                - self
-                    .network
-                    .config()
-                    .maintenance
-                    .maximal_distance
-                    .in_meter()
-                    .unwrap_or(INF_DISTANCE) as MaintenanceCounter
-        } else {
-            self.total_distance().in_meter().unwrap_or(INF_DISTANCE) as MaintenanceCounter
-        }
+        self.maintenance_counter
     }
 
     pub fn costs(&self) -> Cost {
-        self.costs
+        self.costs + self.repositioning_costs()
+    }
+
+    /// the end depot node to which the vehicle is repositioned after the end depot of the tour, if
+    /// an explicit repositioning leg was added.
+    pub fn repositioning(&self) -> Option<NodeIdx> {
+        self.repositioning
     }
 
     /// the overhead time (dead_head + idle) between the predecessor and the node itself
@@ -157,6 +192,56 @@ impl Tour {
         self.end_time() - self.start_time() - self.useful_duration()
     }
 
+    /// Iterates over all nodes of the tour with their arrival and departure times and the
+    /// breakdown of the time until the next node. Depots are passed through without any waiting,
+    /// i.e., the vehicle leaves the start depot just in time (see start_time) and only the dead
+    /// head trip is accounted between a depot and its neighbor.
+    pub fn timeline(&self) -> impl Iterator<Item = NodeTiming> + '_ {
+        let times_of = |node: NodeIdx| {
+            let n = self.network.node(node);
+            if n.is_start_depot() {
+                (self.start_time(), self.start_time())
+            } else if n.is_end_depot() {
+                (self.end_time(), self.end_time())
+            } else {
+                (n.start_time(), n.end_time())
+            }
+        };
+        self.nodes.iter().enumerate().map(move |(pos, &node)| {
+            let (arrival, departure) = times_of(node);
+            let (dead_head_after, shunting_after, idle_after) = match self.nth_node(pos + 1) {
+                None => (Duration::ZERO, Duration::ZERO, Duration::ZERO),
+                Some(successor)
+                    if self.network.node(node).is_depot()
+                        || self.network.node(successor).is_depot() =>
+                {
+                    (
+                        self.network.dead_head_time_between(node, successor),
+                        Duration::ZERO,
+                        Duration::ZERO,
+                    )
+                }
+                Some(successor) => {
+                    let dead_head = self.network.dead_head_time_between(node, successor);
+                    let transition = self.network.minimal_duration_between_nodes(node, successor);
+                    (
+                        dead_head,
+                        transition - dead_head,
+                        times_of(successor).0 - departure - transition,
+                    )
+                }
+            };
+            NodeTiming {
+                node,
+                arrival,
+                departure,
+                dead_head_after,
+                shunting_after,
+                idle_after,
+            }
+        })
+    }
+
     pub fn first_node(&self) -> NodeIdx {
         *self.nodes.first().unwrap()
     }
@@ -169,6 +254,16 @@ impl Tour {
         self.nodes.get(pos).copied()
     }
 
+    /// returns the node that directly follows the given node in the tour. None if the node is
+    /// not part of the tour or is the last node.
+    pub fn successor_of(&self, node: NodeIdx) -> Option<NodeIdx> {
+        let pos = self.position_of(node).ok()?;
+        if self.nodes[pos] != node {
+            return None;
+        }
+        self.nth_node(pos + 1)
+    }
+
     pub fn first_non_depot(&self) -> Option<NodeIdx> {
         self.all_non_depot_nodes_iter().next()
     }
@@ -199,6 +294,15 @@ impl Tour {
         }
     }
 
+    /// the depot where the vehicle is parked at the end of the period, i.e., the target of the
+    /// repositioning leg if there is one, otherwise the end depot.
+    pub fn final_depot(&self) -> Result<NodeIdx, String> {
+        match self.repositioning {
+            Some(depot_node) => Ok(depot_node),
+            None => self.end_depot(),
+        }
+    }
+
     pub fn start_time(&self) -> DateTime {
         if self.is_dummy {
             self.network.node(self.first_node()).start_time()
@@ -483,6 +587,28 @@ impl Tour {
     fn compute_costs(&self) -> Cost {
         Self::compute_costs_of_nodes(&self.nodes, &self.network)
     }
+
+    fn repositioning_distance(&self) -> Distance {
+        match self.repositioning {
+            Some(depot_node) => self
+                .network
+                .dead_head_distance_between(self.last_node(), depot_node),
+            None => Distance::ZERO,
+        }
+    }
+
+    fn repositioning_costs(&self) -> Cost {
+        match self.repositioning {
+            Some(depot_node) => {
+                self.network
+                    .dead_head_time_between(self.last_node(), depot_node)
+                    .in_sec()
+                    .unwrap_or(self.network.planning_days().in_sec().unwrap())
+                    * self.network.config().costs.dead_head_trip
+            }
+            None => 0,
+        }
+    }
 }
 
 // one tour is bigger than the other if the number of nodes are bigger.
@@ -539,26 +665,56 @@ impl fmt::Display for Tour {
 // constructors
 impl Tour {
     /// Creates a new tour from a vector of NodeIds. Checks that the tour is valid:
+    /// * start times are strictly increasing (the first out-of-order pair is reported)
     /// * starts with a StartNode
     /// * end with an EndNode
     /// * only Service or MaintenanceNodes in the middle
-    /// * each node can reach its successor
-    /// If one of the checks fails an error message is returned.
-    pub(super) fn new(nodes: Vec<NodeIdx>, network: Arc<Network>) -> Result<Tour, String> {
-        Tour::new_allow_invalid(nodes, network).map_err(|(_, error_msg)| error_msg)
+    /// * each node can reach its successor (the first unreachable pair is reported)
+    ///
+    /// If one of the checks fails a TourError is returned.
+    pub fn new(nodes: Vec<NodeIdx>, network: Arc<Network>) -> Result<Tour, TourError> {
+        Tour::new_allow_invalid(nodes, network).map_err(|(_, error)| error)
     }
 
-    /// Creates a new tour from a vector of NodeIds. Checks that the tour is valid:
-    /// * starts with a StartNode
-    /// * end with an EndNode
-    /// * only Service or MaintenanceNodes in the middle
-    /// * each node can reach is successor
-    /// If one of the checks fails an error is returned containing the error message but also the
-    /// invalid tour.
+    /// Sorts the nodes by start time (start depot first, end depot last) and creates a new tour
+    /// with the same checks as `Tour::new`.
+    pub fn new_sorted(mut nodes: Vec<NodeIdx>, network: Arc<Network>) -> Result<Tour, TourError> {
+        nodes.sort_by_key(|&node| (network.node(node).start_time(), node));
+        Tour::new(nodes, network)
+    }
+
+    /// Creates a new tour from a vector of NodeIds with the same checks as `Tour::new`.
+    ///
+    /// If one of the checks fails an error is returned containing the TourError but also the
+    /// (boxed) invalid tour.
     pub(super) fn new_allow_invalid(
         nodes: Vec<NodeIdx>,
         network: Arc<Network>,
-    ) -> Result<Tour, (Tour, String)> {
+    ) -> Result<Tour, (Box<Tour>, TourError)> {
+        match Tour::check_nodes(&nodes, &network) {
+            Ok(()) => Ok(Tour::new_computing(nodes, false, network)),
+            Err(error) => Err((Box::new(Tour::new_computing(nodes, false, network)), error)),
+        }
+    }
+
+    fn check_nodes(nodes: &[NodeIdx], network: &Network) -> Result<(), TourError> {
+        if let Some((position, (&a, &b))) = nodes
+            .iter()
+            .tuple_windows()
+            .find_position(|(&a, &b)| network.node(a).start_time() >= network.node(b).start_time())
+        {
+            return Err(TourError::NotSorted(
+                (a, b),
+                format!(
+                    "Tour nodes need to be sorted by start time, but {} (position {}) does not start before {} (position {}).",
+                    network.node(a),
+                    position,
+                    network.node(b),
+                    position + 1
+                ),
+            ));
+        }
+
         let mut error_msg = String::new();
         if !network.node(nodes[0]).is_start_depot() {
             error_msg.push_str(&format!(
@@ -584,20 +740,25 @@ impl Tour {
                 ));
             }
         }
-        for (&a, &b) in nodes.iter().tuple_windows() {
-            if !network.can_reach(a, b) {
-                error_msg.push_str(&format!(
-                    "Not a valid Tour: {} cannot reach {}.\n",
+        if !error_msg.is_empty() {
+            return Err(TourError::InvalidDepots(error_msg));
+        }
+
+        if let Some((&a, &b)) = nodes
+            .iter()
+            .tuple_windows()
+            .find(|(&a, &b)| !network.can_reach(a, b))
+        {
+            return Err(TourError::Unreachable(
+                (a, b),
+                format!(
+                    "Not a valid Tour: {} cannot reach {}.",
                     network.node(a),
                     network.node(b)
-                ));
-            }
-        }
-        if !error_msg.is_empty() {
-            Err((Tour::new_computing(nodes, false, network), error_msg))
-        } else {
-            Ok(Tour::new_computing(nodes, false, network))
+                ),
+            ));
         }
+        Ok(())
     }
 
     pub(super) fn new_dummy(path: Path, network: Arc<Network>) -> Result<Tour, String> {
@@ -690,6 +851,44 @@ impl Tour {
         nodes.iter().any(|&n| network.node(n).is_maintenance())
     }
 
+    /// computes the maintenance counter from the (possibly incrementally updated) total distance.
+    /// Only the maximal distance is looked up in the nodes, which usually stops at the first
+    /// non-depot node.
+    fn compute_maintenance_counter(
+        nodes: &[NodeIdx],
+        visits_maintenance: bool,
+        total_distance: Distance,
+        network: &Network,
+    ) -> MaintenanceCounter {
+        let total_distance =
+            total_distance.in_meter().unwrap_or(INF_DISTANCE) as MaintenanceCounter;
+        if visits_maintenance {
+            let maximal_distance = nodes
+                .iter()
+                .find(|&&n| network.node(n).is_service())
+                .map(|&n| network.maximal_distance_between_maintenance(network.vehicle_type_for(n)))
+                .unwrap_or(network.config().maintenance.maximal_distance);
+            total_distance
+                - maximal_distance.in_meter().unwrap_or(INF_DISTANCE) as MaintenanceCounter
+        } else {
+            total_distance
+        }
+    }
+
+    /// computes the maintenance counter of the nodes from scratch (no precomputed values are used).
+    fn compute_maintenance_counter_of_nodes(
+        nodes: &[NodeIdx],
+        network: &Network,
+    ) -> MaintenanceCounter {
+        Tour::compute_maintenance_counter(
+            nodes,
+            Tour::compute_visits_maintenance(nodes, network),
+            Tour::compute_service_distance_of_nodes(nodes, network)
+                + Tour::compute_dead_head_distance_of_nodes(nodes, network),
+            network,
+        )
+    }
+
     /// Creates a new tour from a vector of NodeIds. Trusts that the vector leads to a valid Tour.
     #[allow(clippy::too_many_arguments)]
     fn new_precomputed(
@@ -702,6 +901,20 @@ impl Tour {
         costs: Cost,
         network: Arc<Network>,
     ) -> Tour {
+        let maintenance_counter = Tour::compute_maintenance_counter(
+            &nodes,
+            visits_maintenance,
+            service_distance + dead_head_distance,
+            &network,
+        );
+        debug_assert_eq!(
+            maintenance_counter,
+            Tour::compute_maintenance_counter_of_nodes(&nodes, &network),
+            "cached maintenance counter differs from recomputed one"
+        );
+        let mut hasher = DefaultHasher::new();
+        nodes.hash(&mut hasher);
+        let nodes_hash = hasher.finish();
         Tour {
             nodes,
             is_dummy,
@@ -710,6 +923,9 @@ impl Tour {
             service_distance,
             dead_head_distance,
             costs,
+            maintenance_counter,
+            repositioning: None,
+            nodes_hash,
             network,
         }
     }
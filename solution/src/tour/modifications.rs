@@ -72,6 +72,39 @@ impl Tour {
         ))
     }
 
+    /// Return the tour with an explicit (empty) repositioning leg from the end depot to the given
+    /// end depot node. The leg counts as dead-head distance and costs. Any further modification of
+    /// the tour drops the repositioning leg.
+    pub fn add_repositioning(&self, depot_node: NodeIdx) -> Result<Tour, String> {
+        if self.is_dummy {
+            return Err("cannot add repositioning to dummy tour".to_string());
+        }
+        if !self.network.node(depot_node).is_end_depot() {
+            return Err("node has to be end depot".to_string());
+        }
+        if self.network.node(depot_node).start_location()
+            == self.network.node(self.last_node()).end_location()
+        {
+            return Err("repositioning depot has to be at another location".to_string());
+        }
+        if self
+            .network
+            .dead_head_distance_between(self.last_node(), depot_node)
+            == Distance::Infinity
+        {
+            return Err("repositioning depot cannot be reached from the end depot".to_string());
+        }
+        let mut tour = self.clone();
+        tour.repositioning = Some(depot_node);
+        tour.maintenance_counter = Tour::compute_maintenance_counter(
+            &tour.nodes,
+            tour.visits_maintenance,
+            tour.total_distance(),
+            &tour.network,
+        );
+        Ok(tour)
+    }
+
     /// Return the tour where the end depot is replaced by the new_end_depot.
     pub fn replace_end_depot(&self, new_end_depot: NodeIdx) -> Result<Tour, String> {
         if self.is_dummy {
@@ -208,7 +241,7 @@ impl Tour {
     /// # Properties:
     /// - Assumes that provided node sequence is feasible.
     /// - Dummy: If path contains depots (at the start or end), the depots are
-    /// removed at the beginning.
+    ///   removed at the beginning.
     /// - Non-dummy: If the provided sequence contains a start depot it will be inserted as a prefix.
     /// - Non-dummy: If the provided path contains an end depot it will be inserted as a suffix.
     /// - Note that depot can never clash. So their is no failure possible.
@@ -293,6 +326,71 @@ impl Tour {
             Path::new_trusted(removed_nodes, self.network.clone()),
         )
     }
+
+    /// Replaces the segment of the tour by the replacement path. The subpath between
+    /// segment.start() and segment.end() (inclusive) is removed and the replacement is inserted
+    /// in its place. Returns the new tour and the evicted nodes (as Path).
+    /// Fails if segment.start() or segment.end() is not part of the tour or is a depot.
+    /// Fails if the replacement contains depots.
+    /// Fails if the node before the segment cannot reach the first node of the replacement or the
+    /// last node of the replacement cannot reach the node after the segment.
+    pub fn replace_segment(
+        &self,
+        segment: Segment,
+        replacement: Path,
+    ) -> Result<(Tour, Path), String> {
+        let pos_seg_start = self.position_of(segment.start())?;
+        let pos_seg_end = self.position_of(segment.end())?;
+        if self.nodes[pos_seg_start] != segment.start() || self.nodes[pos_seg_end] != segment.end()
+        {
+            return Err(String::from("Segment is not part of the tour."));
+        }
+        if pos_seg_start > pos_seg_end {
+            return Err(String::from("segment.start() comes after segment.end()."));
+        }
+        if self.network.node(segment.start()).is_depot()
+            || self.network.node(segment.end()).is_depot()
+        {
+            return Err(String::from("Depots cannot be replaced."));
+        }
+        if replacement.iter().any(|n| self.network.node(n).is_depot()) {
+            return Err(String::from("Replacement path cannot contain depots."));
+        }
+
+        if pos_seg_start > 0
+            && !self
+                .network
+                .can_reach(self.nodes[pos_seg_start - 1], replacement.first())
+        {
+            return Err(format!(
+                "Replacement is invalid: {} cannot reach {}.",
+                self.nodes[pos_seg_start - 1],
+                replacement.first()
+            ));
+        }
+        if pos_seg_end < self.nodes.len() - 1
+            && !self
+                .network
+                .can_reach(replacement.last(), self.nodes[pos_seg_end + 1])
+        {
+            return Err(format!(
+                "Replacement is invalid: {} cannot reach {}.",
+                replacement.last(),
+                self.nodes[pos_seg_end + 1]
+            ));
+        }
+
+        let mut new_tour_nodes = self.nodes.clone();
+        let evicted_nodes: Vec<NodeIdx> = new_tour_nodes
+            .splice(pos_seg_start..pos_seg_end + 1, replacement.consume())
+            .collect();
+
+        Ok((
+            Tour::new_computing(new_tour_nodes, self.is_dummy, self.network.clone()),
+            Path::new_trusted(evicted_nodes, self.network.clone())
+                .expect("empty path should be impossible."),
+        ))
+    }
 }
 
 // private methods
@@ -12,16 +12,19 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use itertools::assert_equal;
-use model::base_types::Distance;
+use model::{
+    base_types::Distance, json_serialisation::load_rolling_stock_problem_instance_from_json,
+};
 use rapid_time::{DateTime, Duration};
+use serde_json::json;
 
 use crate::{
     path::Path,
     segment::Segment,
-    test_utilities::{init_test_data, TestData},
+    test_utilities::{init_test_data, load_test_input, TestData},
 };
 
-use super::Tour;
+use super::{NodeTiming, Tour, TourError};
 
 fn default_tour(d: &TestData) -> Tour {
     Tour::new(
@@ -844,3 +847,300 @@ fn remove_invalid_segment() {
     assert!(start_depot_result.is_err());
     assert!(end_depot_result.is_err());
 }
+
+#[test]
+fn replace_segment_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let tour = Tour::new(
+        vec![d.start_depot1, d.trip12, d.trip23, d.trip31, d.end_depot2],
+        d.network.clone(),
+    )
+    .unwrap();
+    let replacement = Path::new(vec![d.trip34, d.trip45_fast], d.network.clone())
+        .unwrap()
+        .unwrap();
+
+    // ACT
+    let (new_tour, evicted_path) = tour
+        .replace_segment(Segment::new(d.trip31, d.trip31), replacement)
+        .unwrap();
+
+    // ASSERT
+    assert_equal(
+        new_tour.all_nodes_iter(),
+        [
+            d.start_depot1,
+            d.trip12,
+            d.trip23,
+            d.trip34,
+            d.trip45_fast,
+            d.end_depot2,
+        ]
+        .iter()
+        .cloned(),
+    );
+    assert_equal(evicted_path.iter(), [d.trip31].iter().cloned());
+    new_tour.verify_consistency();
+}
+
+#[test]
+fn replace_segment_invalid_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let tour = Tour::new(
+        vec![d.start_depot1, d.trip12, d.trip23, d.trip31, d.end_depot2],
+        d.network.clone(),
+    )
+    .unwrap();
+
+    // ACT
+    let unreachable_successor = tour.replace_segment(
+        Segment::new(d.trip23, d.trip23),
+        Path::new_from_single_node(d.trip14, d.network.clone()),
+    );
+    let depot_segment = tour.replace_segment(
+        Segment::new(d.start_depot1, d.trip12),
+        Path::new_from_single_node(d.trip14, d.network.clone()),
+    );
+
+    // ASSERT
+    assert!(unreachable_successor.is_err());
+    assert!(depot_segment.is_err());
+}
+
+#[test]
+fn maintenance_counter_with_type_specific_range_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["vehicleTypes"][0]["maintenanceRangeKm"] = json!(10);
+    input_data["vehicleTypes"][1]["maintenanceRangeKm"] = json!(20);
+    input_data["routes"].as_array_mut().unwrap().push(
+        json!({"id": "route_1-2_vt2", "vehicleType": "vt2", "segments": [
+            {"id": "segment_1-2_vt2", "order": 0, "origin": "loc1", "destination": "loc2",
+             "distance": 1000, "duration": 1800}]}),
+    );
+    input_data["departures"].as_array_mut().unwrap().push(
+        json!({"id": "trip_1-2_vt2", "route": "route_1-2_vt2", "segments": [
+            {"id": "dep_segment_1-2_vt2", "routeSegment": "segment_1-2_vt2",
+             "departure": "2020-01-01T06:00:00", "passengers": 10, "seated": 0}]}),
+    );
+    input_data["maintenanceSlots"] = json!([{"id": "maintenance_loc2", "location": "loc2",
+        "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00", "trackCount": 2}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let node_by_id = |id: &str| {
+        network
+            .all_nodes()
+            .find(|&n| network.node(n).id() == id)
+            .unwrap()
+    };
+    let start_depot = node_by_id("s_depot1");
+    let end_depot = node_by_id("e_depot2");
+    let maintenance = node_by_id("maintenance_loc2");
+
+    // ACT
+    let tour_vt1 = Tour::new(
+        vec![
+            start_depot,
+            node_by_id("dep_segment_1-2"),
+            maintenance,
+            end_depot,
+        ],
+        network.clone(),
+    )
+    .unwrap();
+    let tour_vt2 = Tour::new(
+        vec![
+            start_depot,
+            node_by_id("dep_segment_1-2_vt2"),
+            maintenance,
+            end_depot,
+        ],
+        network.clone(),
+    )
+    .unwrap();
+
+    // ASSERT
+    assert_eq!(tour_vt1.total_distance(), Distance::from_meter(1000));
+    assert_eq!(tour_vt2.total_distance(), Distance::from_meter(1000));
+    assert_eq!(tour_vt1.maintenance_counter(), 1000 - 10000);
+    assert_eq!(tour_vt2.maintenance_counter(), 1000 - 20000);
+}
+
+#[test]
+fn maintenance_counter_is_updated_incrementally_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["maintenanceSlots"] = json!([{"id": "maintenance_loc2", "location": "loc2",
+        "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00", "trackCount": 2}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let node_by_id = |id: &str| {
+        network
+            .all_nodes()
+            .find(|&n| network.node(n).id() == id)
+            .unwrap()
+    };
+    let maintenance = node_by_id("maintenance_loc2");
+    let trip12 = node_by_id("dep_segment_1-2");
+    let tour = Tour::new(
+        vec![
+            node_by_id("s_depot1"),
+            trip12,
+            node_by_id("dep_segment_2-3"),
+            node_by_id("dep_segment_3-4"),
+            node_by_id("dep_segment_4-5"),
+            node_by_id("dep_segment_5-1"),
+            node_by_id("e_depot2"),
+        ],
+        network.clone(),
+    )
+    .unwrap();
+    let recomputed =
+        |tour: &Tour| Tour::compute_maintenance_counter_of_nodes(&tour.nodes, &network);
+
+    // ACT
+    let (with_maintenance, _) =
+        tour.insert_path(Path::new_from_single_node(maintenance, network.clone()));
+    let (with_trip31, _) = with_maintenance.insert_path(Path::new_from_single_node(
+        node_by_id("dep_segment_3-1"),
+        network.clone(),
+    ));
+    let (without_maintenance, _) = with_trip31
+        .remove(Segment::new(maintenance, maintenance))
+        .unwrap();
+    let without_maintenance = without_maintenance.unwrap();
+    let (without_trip12, _) = without_maintenance
+        .remove(Segment::new(trip12, trip12))
+        .unwrap();
+    let without_trip12 = without_trip12.unwrap();
+
+    // ASSERT
+    for tour in [
+        &tour,
+        &with_maintenance,
+        &with_trip31,
+        &without_maintenance,
+        &without_trip12,
+    ] {
+        assert_eq!(tour.maintenance_counter(), recomputed(tour));
+    }
+    assert!(!tour.visits_maintenance());
+    assert!(with_maintenance.visits_maintenance());
+    assert!(with_trip31.visits_maintenance());
+    assert!(!without_maintenance.visits_maintenance());
+    assert!(with_maintenance.maintenance_counter() < 0);
+    assert!(without_trip12.maintenance_counter() > 0);
+}
+
+#[test]
+fn unsorted_nodes_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let unsorted_nodes = vec![
+        d.trip23,
+        d.end_depot2,
+        d.trip12,
+        d.start_depot1,
+        d.trip45,
+        d.trip34,
+        d.trip51,
+    ];
+
+    // ACT
+    let unsorted_tour = Tour::new(unsorted_nodes.clone(), d.network.clone());
+    let sorted_tour = Tour::new_sorted(unsorted_nodes, d.network.clone());
+
+    // ASSERT
+    let error = unsorted_tour.err().unwrap();
+    assert!(matches!(error, TourError::NotSorted(_, _)));
+    assert_eq!(error.nodes(), Some((d.end_depot2, d.trip12)));
+    assert!(error.to_string().contains(&format!(
+        "{} (position 1) does not start before {} (position 2)",
+        d.network.node(d.end_depot2),
+        d.network.node(d.trip12)
+    )));
+    let sorted_tour = sorted_tour.unwrap();
+    assert_equal(
+        sorted_tour.all_nodes_iter(),
+        default_tour(&d).all_nodes_iter(),
+    );
+    assert!(Tour::new_sorted(
+        vec![d.start_depot1, d.trip23, d.end_depot2],
+        d.network.clone()
+    )
+    .is_ok());
+}
+
+#[test]
+fn timeline_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let time = |time: &str| DateTime::new(&format!("2020-01-01T{}", time));
+    let shunting = d.network.config().shunting.minimal; // 2 minutes
+    let tour = Tour::new(
+        vec![d.start_depot1, d.trip12, d.trip23, d.end_depot1],
+        d.network.clone(),
+    )
+    .unwrap();
+    let tour_with_dead_head = Tour::new(
+        vec![d.start_depot1, d.trip12, d.trip34, d.end_depot4],
+        d.network.clone(),
+    )
+    .unwrap();
+
+    // ACT
+    let timeline: Vec<NodeTiming> = tour.timeline().collect();
+    let timeline_with_dead_head: Vec<NodeTiming> = tour_with_dead_head.timeline().collect();
+
+    // ASSERT
+    assert_eq!(
+        timeline,
+        vec![
+            NodeTiming {
+                node: d.start_depot1,
+                arrival: time("06:00:00"),
+                departure: time("06:00:00"),
+                dead_head_after: Duration::ZERO,
+                shunting_after: Duration::ZERO,
+                idle_after: Duration::ZERO,
+            },
+            NodeTiming {
+                node: d.trip12,
+                arrival: time("06:00:00"),
+                departure: time("06:30:00"),
+                dead_head_after: Duration::ZERO,
+                shunting_after: shunting,
+                idle_after: Duration::new("0:30") - shunting, // gap minus shunting
+            },
+            NodeTiming {
+                node: d.trip23,
+                arrival: time("07:00:00"),
+                departure: time("07:30:00"),
+                dead_head_after: Duration::new("0:45"),
+                shunting_after: Duration::ZERO,
+                idle_after: Duration::ZERO,
+            },
+            NodeTiming {
+                node: d.end_depot1,
+                arrival: time("08:15:00"),
+                departure: time("08:15:00"),
+                dead_head_after: Duration::ZERO,
+                shunting_after: Duration::ZERO,
+                idle_after: Duration::ZERO,
+            },
+        ]
+    );
+
+    // trip12 ends at loc2, trip34 starts at loc3: 45 minutes dead head trip and 5 minutes of
+    // shunting before and after it
+    let after_trip12 = timeline_with_dead_head[1];
+    assert_eq!(after_trip12.dead_head_after, Duration::new("0:45"));
+    assert_eq!(after_trip12.shunting_after, Duration::new("0:10"));
+    assert_eq!(after_trip12.idle_after, Duration::new("0:35"));
+    assert_eq!(
+        after_trip12.idle_after,
+        timeline_with_dead_head[2].arrival
+            - after_trip12.departure
+            - d.network.minimal_duration_between_nodes(d.trip12, d.trip34)
+    );
+}
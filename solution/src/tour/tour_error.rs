@@ -0,0 +1,63 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+use model::base_types::NodeIdx;
+
+/// Error returned by the constructors of a tour. Each variant carries the human-readable message,
+/// which is also used by Display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TourError {
+    /// The start times of the nodes are not strictly increasing. Carries the first out-of-order
+    /// pair of consecutive nodes.
+    NotSorted((NodeIdx, NodeIdx), String),
+    /// The tour does not start with a start depot, does not end with an end depot, has no
+    /// non-depot node or has a depot in the middle.
+    InvalidDepots(String),
+    /// A node cannot reach its successor. Carries the first such pair of consecutive nodes.
+    Unreachable((NodeIdx, NodeIdx), String),
+}
+
+impl TourError {
+    pub fn message(&self) -> &str {
+        match self {
+            TourError::NotSorted(_, message)
+            | TourError::InvalidDepots(message)
+            | TourError::Unreachable(_, message) => message,
+        }
+    }
+
+    /// The offending pair of consecutive nodes (None for invalid depots).
+    pub fn nodes(&self) -> Option<(NodeIdx, NodeIdx)> {
+        match self {
+            TourError::NotSorted(nodes, _) | TourError::Unreachable(nodes, _) => Some(*nodes),
+            TourError::InvalidDepots(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for TourError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for TourError {}
+
+/// Allows callers that still work with String errors to use `?`.
+impl From<TourError> for String {
+    fn from(error: TourError) -> String {
+        error.to_string()
+    }
+}
@@ -11,9 +11,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+#[cfg(test)]
+mod tests;
+
 use std::fmt;
 
-use model::base_types::{PassengerCount, VehicleCount, VehicleIdx};
+use model::base_types::{PassengerCount, TrainLength, VehicleCount, VehicleIdx, VehicleTypeIdx};
 
 use crate::vehicle::Vehicle;
 use std::iter::Iterator;
@@ -21,13 +24,25 @@ use std::iter::Iterator;
 #[derive(Clone)]
 pub struct TrainFormation {
     formation: Vec<Vehicle>, // index 0 is at front, index len()-1 is tail
+
+    // cached sums over the vehicles, as they are needed for every coverage check
+    seats: PassengerCount,
+    capacity: PassengerCount,
 }
 
 // static functions
 impl TrainFormation {
     pub(crate) fn empty() -> TrainFormation {
+        TrainFormation::new(Vec::new())
+    }
+
+    pub(crate) fn new(formation: Vec<Vehicle>) -> TrainFormation {
+        let seats = formation.iter().map(|v| v.seats()).sum();
+        let capacity = formation.iter().map(|v| v.capacity()).sum();
         TrainFormation {
-            formation: Vec::new(),
+            formation,
+            seats,
+            capacity,
         }
     }
 }
@@ -50,9 +65,7 @@ impl TrainFormation {
         new_formation.push(new);
         new_formation.swap_remove(pos);
 
-        Ok(TrainFormation {
-            formation: new_formation,
-        })
+        Ok(TrainFormation::new(new_formation))
     }
 
     pub(crate) fn remove(&self, vehicle: VehicleIdx) -> Result<TrainFormation, String> {
@@ -70,18 +83,14 @@ impl TrainFormation {
         // remove vehicle:
         new_formation.remove(pos);
 
-        Ok(TrainFormation {
-            formation: new_formation,
-        })
+        Ok(TrainFormation::new(new_formation))
     }
 
     pub(crate) fn add_at_tail(&self, vehicle: Vehicle) -> TrainFormation {
         let mut new_formation = self.formation.clone();
         new_formation.push(vehicle);
 
-        TrainFormation {
-            formation: new_formation,
-        }
+        TrainFormation::new(new_formation)
     }
 
     pub fn ids(&self) -> Vec<VehicleIdx> {
@@ -92,12 +101,33 @@ impl TrainFormation {
         self.formation.iter()
     }
 
+    /// The vehicle types of the formation (from front to tail).
+    pub fn vehicle_types(&self) -> Vec<VehicleTypeIdx> {
+        self.formation.iter().map(|v| v.type_idx()).collect()
+    }
+
     pub fn capacity(&self) -> PassengerCount {
-        self.formation.iter().map(|v| v.capacity()).sum()
+        self.capacity
     }
 
     pub fn seats(&self) -> PassengerCount {
-        self.formation.iter().map(|v| v.seats()).sum()
+        self.seats
+    }
+
+    /// Sum of the lengths (in meter) of all vehicles in the formation.
+    pub fn total_length(&self) -> TrainLength {
+        self.formation.iter().map(|v| v.length()).sum()
+    }
+
+    /// Number of seats per meter of the formation. None if the formation has no length (e.g., it
+    /// is empty or the lengths of the vehicle types are unknown).
+    pub fn seat_density(&self) -> Option<f64> {
+        let total_length = self.total_length();
+        if total_length == 0 {
+            None
+        } else {
+            Some(self.seats() as f64 / total_length as f64)
+        }
     }
 
     pub fn vehicle_count(&self) -> VehicleCount {
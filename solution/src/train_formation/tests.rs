@@ -0,0 +1,133 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use model::{
+    base_types::{PassengerCount, VehicleIdx},
+    json_serialisation::load_rolling_stock_problem_instance_from_json,
+};
+use serde_json::json;
+
+use crate::{
+    test_utilities::{init_test_data, load_test_input},
+    vehicle::Vehicle,
+};
+
+use super::TrainFormation;
+
+#[test]
+fn total_length_and_seat_density_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let vehicle_types = d.network.vehicle_types();
+    let veh0 = Vehicle::new(VehicleIdx::vehicle_from(0), d.vt1, vehicle_types.clone());
+    let veh1 = Vehicle::new(VehicleIdx::vehicle_from(1), d.vt2, vehicle_types.clone());
+
+    // ACT
+    let formation = TrainFormation::empty().add_at_tail(veh0).add_at_tail(veh1);
+
+    // ASSERT
+    assert_eq!(formation.total_length(), 160);
+    assert_eq!(formation.seats(), 50);
+    assert_eq!(formation.seat_density(), Some(50.0 / 160.0));
+    assert_eq!(TrainFormation::empty().total_length(), 0);
+    assert_eq!(TrainFormation::empty().seat_density(), None);
+}
+
+#[test]
+fn cached_seats_and_capacity_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let vehicle_types = d.network.vehicle_types();
+    let vehicle = |idx, vehicle_type| {
+        Vehicle::new(
+            VehicleIdx::vehicle_from(idx),
+            vehicle_type,
+            vehicle_types.clone(),
+        )
+    };
+    let assert_cache_is_fresh = |formation: &TrainFormation| {
+        assert_eq!(
+            formation.seats(),
+            formation.iter().map(|v| v.seats()).sum::<PassengerCount>()
+        );
+        assert_eq!(
+            formation.capacity(),
+            formation
+                .iter()
+                .map(|v| v.capacity())
+                .sum::<PassengerCount>()
+        );
+    };
+
+    // ACT & ASSERT
+    let formation = TrainFormation::empty();
+    assert_cache_is_fresh(&formation);
+    assert_eq!(formation.seats(), 0);
+
+    let formation = formation.add_at_tail(vehicle(0, d.vt1));
+    assert_cache_is_fresh(&formation);
+
+    let formation = formation.add_at_tail(vehicle(1, d.vt2));
+    assert_cache_is_fresh(&formation);
+    assert_eq!(formation.seats(), 50);
+
+    let formation = formation
+        .replace(VehicleIdx::vehicle_from(0), vehicle(2, d.vt2))
+        .unwrap();
+    assert_cache_is_fresh(&formation);
+
+    let formation = formation.remove(VehicleIdx::vehicle_from(1)).unwrap();
+    assert_cache_is_fresh(&formation);
+    assert_eq!(formation.vehicle_count(), 1);
+
+    let formation = formation.remove(VehicleIdx::vehicle_from(2)).unwrap();
+    assert_cache_is_fresh(&formation);
+    assert_eq!(formation.capacity(), 0);
+}
+
+#[test]
+fn vehicle_roster_overrides_seats_test() {
+    // ARRANGE
+    let d = init_test_data();
+    let mut input_data = load_test_input();
+    input_data["vehicleRoster"] = json!([{"id": "bike_unit", "vehicleType": "vt1", "seats": 24}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let vehicle_types = network.vehicle_types();
+    let vehicle = |idx, vehicle_type| {
+        Vehicle::new(
+            VehicleIdx::vehicle_from(idx),
+            vehicle_type,
+            vehicle_types.clone(),
+        )
+    };
+    let bike_unit = vehicle_types.roster_units_of(d.vt1).next().unwrap();
+
+    // ACT
+    let unit_vehicle = vehicle(0, d.vt1).with_roster_unit(bike_unit, &vehicle_types);
+    let formation = TrainFormation::empty()
+        .add_at_tail(unit_vehicle.clone())
+        .add_at_tail(vehicle(1, d.vt1));
+
+    // ASSERT
+    assert_eq!(vehicle_types.roster_units_of(d.vt2).count(), 0);
+    assert_eq!(unit_vehicle.seats(), 24);
+    assert_eq!(unit_vehicle.capacity(), 50); // not overridden
+    assert_eq!(
+        unit_vehicle.renamed(VehicleIdx::vehicle_from(7)).seats(),
+        24
+    );
+    assert_eq!(vehicle(0, d.vt1).seats(), 30); // the id alone does not make the unit
+    assert_eq!(vehicle(1, d.vt1).seats(), 30);
+    assert_eq!(formation.seats(), 24 + 30);
+    assert_eq!(formation.capacity(), 50 + 50);
+}
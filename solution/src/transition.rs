@@ -135,7 +135,7 @@ impl Transition {
                         tours
                             .get(vehicles.last().unwrap())
                             .unwrap()
-                            .end_depot()
+                            .final_depot()
                             .unwrap(),
                         tours
                             .get(vehicles.first().unwrap())
@@ -169,6 +169,38 @@ impl Transition {
         }
     }
 
+    /// Returns the same transition with each vehicle replaced by renaming(vehicle). The
+    /// representation is canonical: empty cycles are dropped, each cycle starts with its smallest
+    /// vehicle and the cycles are sorted by their first vehicle.
+    pub fn renamed(&self, renaming: impl Fn(VehicleIdx) -> VehicleIdx) -> Transition {
+        let mut cycles: Vec<TransitionCycle> = self
+            .cycles
+            .iter()
+            .filter(|cycle| !cycle.is_empty())
+            .map(|cycle| {
+                let mut vehicles: Vec<VehicleIdx> = cycle.iter().map(&renaming).collect();
+                let first_position = vehicles.iter().position_min().unwrap();
+                vehicles.rotate_left(first_position);
+                TransitionCycle::new(vehicles, cycle.maintenance_counter())
+            })
+            .collect();
+        cycles.sort_by_key(|cycle| cycle.first());
+
+        let cycle_lookup = cycles
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, cycle)| cycle.iter().map(move |vehicle| (vehicle, idx)))
+            .collect();
+
+        Transition {
+            cycles,
+            total_maintenance_violation: self.total_maintenance_violation,
+            total_maintenance_counter: self.total_maintenance_counter,
+            cycle_lookup,
+            empty_cycles: Vec::new(),
+        }
+    }
+
     pub fn get_successor_of(&self, vehicle: VehicleIdx) -> VehicleIdx {
         let cycle_idx = self.cycle_lookup.get(&vehicle).unwrap();
         let cycle = self.cycles.get(*cycle_idx).unwrap();
@@ -243,7 +275,7 @@ impl Transition {
                     let tour = tours.get(&vehicle).unwrap();
                     network
                         .dead_head_distance_between(
-                            tour.end_depot().unwrap(),
+                            tour.final_depot().unwrap(),
                             tour.start_depot().unwrap(),
                         )
                         .in_meter()
@@ -255,7 +287,7 @@ impl Transition {
                     .circular_tuple_windows()
                     .map(|(vehicle_1, vehicle_2)| {
                         let end_depot_of_vehicle_1 =
-                            tours.get(vehicle_1).unwrap().end_depot().unwrap();
+                            tours.get(vehicle_1).unwrap().final_depot().unwrap();
                         let start_depot_of_vehicle_2 =
                             tours.get(vehicle_2).unwrap().start_depot().unwrap();
                         network
@@ -310,7 +342,7 @@ impl Transition {
                 tours
                     .get(cluster.last().unwrap())
                     .unwrap()
-                    .end_depot()
+                    .final_depot()
                     .unwrap(),
                 tour.start_depot().unwrap(),
             )
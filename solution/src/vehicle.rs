@@ -14,7 +14,7 @@
 use std::{fmt, sync::Arc};
 
 use model::{
-    base_types::{PassengerCount, VehicleCount, VehicleIdx, VehicleTypeIdx},
+    base_types::{PassengerCount, TrainLength, VehicleCount, VehicleIdx, VehicleTypeIdx},
     vehicle_types::{VehicleType, VehicleTypes},
 };
 
@@ -22,6 +22,9 @@ use model::{
 pub struct Vehicle {
     idx: VehicleIdx,
     vehicle_type: Arc<VehicleType>,
+    roster_unit: Option<usize>, // unit of the vehicle roster (see RosterUnit) this vehicle is
+    seats: PassengerCount,      // seats of the roster unit, otherwise of the type
+    capacity: PassengerCount,   // capacity of the roster unit, otherwise of the type
 }
 
 impl Vehicle {
@@ -30,9 +33,38 @@ impl Vehicle {
         type_idx: VehicleTypeIdx,
         vehicle_types: Arc<VehicleTypes>,
     ) -> Vehicle {
+        let vehicle_type = vehicle_types.get(type_idx).unwrap().clone();
         Vehicle {
             idx,
-            vehicle_type: vehicle_types.get(type_idx).unwrap().clone(),
+            seats: vehicle_type.seats(),
+            capacity: vehicle_type.capacity(),
+            vehicle_type,
+            roster_unit: None,
+        }
+    }
+
+    /// Makes this vehicle the given unit of the vehicle roster, which must be of the same type.
+    pub(super) fn with_roster_unit(self, unit: usize, vehicle_types: &VehicleTypes) -> Vehicle {
+        assert_eq!(
+            vehicle_types.roster_unit(unit).vehicle_type(),
+            self.type_idx(),
+            "Roster unit {} is not of vehicle type {}.",
+            vehicle_types.roster_unit(unit).id(),
+            self.vehicle_type.id()
+        );
+        Vehicle {
+            roster_unit: Some(unit),
+            seats: vehicle_types.seats_of(unit),
+            capacity: vehicle_types.capacity_of(unit),
+            ..self
+        }
+    }
+
+    /// The same vehicle (type, roster unit, seats and capacity) under another id.
+    pub(super) fn renamed(&self, idx: VehicleIdx) -> Vehicle {
+        Vehicle {
+            idx,
+            ..self.clone()
         }
     }
 
@@ -44,12 +76,20 @@ impl Vehicle {
         self.vehicle_type.idx()
     }
 
+    pub fn roster_unit(&self) -> Option<usize> {
+        self.roster_unit
+    }
+
     pub fn seats(&self) -> PassengerCount {
-        self.vehicle_type.seats()
+        self.seats
     }
 
     pub fn capacity(&self) -> PassengerCount {
-        self.vehicle_type.capacity()
+        self.capacity
+    }
+
+    pub fn length(&self) -> TrainLength {
+        self.vehicle_type.length()
     }
 
     pub fn maximal_formation_count(&self) -> Option<VehicleCount> {
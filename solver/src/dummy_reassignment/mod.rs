@@ -0,0 +1,70 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use rapid_solve::objective::{EvaluatedSolution, Objective};
+use solution::Schedule;
+
+use crate::local_search::{neighborhood::swaps::SwapInfo, ScheduleWithInfo};
+
+/// For each remaining dummy tour (i.e., uncovered demand) it is evaluated whether spawning a new
+/// vehicle to cover it yields a better objective value than leaving it uncovered. The better
+/// option is kept.
+pub struct DummyReassignmentSolver {
+    objective: Arc<Objective<ScheduleWithInfo>>,
+}
+
+impl DummyReassignmentSolver {
+    pub fn initialize(objective: Arc<Objective<ScheduleWithInfo>>) -> Self {
+        Self { objective }
+    }
+
+    pub fn solve(
+        &self,
+        solution: EvaluatedSolution<ScheduleWithInfo>,
+    ) -> EvaluatedSolution<ScheduleWithInfo> {
+        let dummies: Vec<_> = solution.solution().get_schedule().dummy_iter().collect();
+        let mut current_solution = solution;
+
+        for dummy in dummies {
+            let schedule: &Schedule = current_solution.solution().get_schedule();
+            let best_candidate = schedule
+                .get_vehicle_types()
+                .iter()
+                .filter_map(|vehicle_type| {
+                    schedule
+                        .spawn_vehicle_to_replace_dummy_tour(dummy, vehicle_type)
+                        .ok()
+                        .map(|(new_schedule, vehicle)| {
+                            self.objective.evaluate(ScheduleWithInfo::new(
+                                new_schedule,
+                                SwapInfo::NoSwap,
+                                format!("Spawned {} to cover dummy {}", vehicle, dummy),
+                            ))
+                        })
+                })
+                .min_by(|a, b| a.objective_value().cmp(b.objective_value()));
+
+            if let Some(candidate) = best_candidate {
+                if candidate.objective_value() < current_solution.objective_value() {
+                    current_solution = candidate;
+                }
+            }
+        }
+        current_solution
+    }
+}
@@ -0,0 +1,64 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fs::File, io::Read, sync::Arc};
+
+use model::{
+    base_types::VehicleIdx, json_serialisation::load_rolling_stock_problem_instance_from_json,
+};
+
+use crate::{
+    local_search::{neighborhood::swaps::SwapInfo, ScheduleWithInfo},
+    min_cost_flow_solver::MinCostFlowSolver,
+    objective,
+};
+
+use super::DummyReassignmentSolver;
+
+#[test]
+fn spawning_for_dummy_improves_objective_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let network =
+        load_rolling_stock_problem_instance_from_json(serde_json::from_str(&input_data).unwrap());
+    let objective = Arc::new(objective::build());
+    let full_schedule = MinCostFlowSolver::initialize(network.clone()).solve();
+    let vehicle: VehicleIdx = full_schedule.vehicles_iter_all().next().unwrap();
+    let schedule_with_dummy = full_schedule.replace_vehicle_by_dummy(vehicle).unwrap();
+    let solution = objective.evaluate(ScheduleWithInfo::new(
+        schedule_with_dummy,
+        SwapInfo::NoSwap,
+        String::from("Schedule with dummy"),
+    ));
+    let solver = DummyReassignmentSolver::initialize(objective.clone());
+
+    // ACT
+    let result = solver.solve(solution.clone());
+
+    // ASSERT
+    assert_eq!(
+        solution.solution().get_schedule().number_of_dummy_tours(),
+        1
+    );
+    assert!(solution.solution().get_schedule().unserved_passengers().0 > 0);
+    assert!(result.objective_value() < solution.objective_value());
+    let result_schedule = result.solution().get_schedule();
+    assert_eq!(result_schedule.number_of_dummy_tours(), 0);
+    assert_eq!(result_schedule.unserved_passengers(), (0, 0));
+    assert_eq!(
+        result_schedule.number_of_vehicles(),
+        solution.solution().get_schedule().number_of_vehicles() + 1
+    );
+}
@@ -11,9 +11,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod dummy_reassignment;
 pub mod local_search;
+pub mod marginal_cost;
 pub mod min_cost_flow_solver;
 pub mod objective;
 pub mod one_node_per_tour;
+pub mod start_solver;
 pub mod transition_cycle_tsp;
 pub mod transition_local_search;
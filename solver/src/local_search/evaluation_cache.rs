@@ -0,0 +1,200 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rapid_solve::objective::{EvaluatedSolution, Objective, ObjectiveValue};
+use solution::Schedule;
+
+use super::ScheduleWithInfo;
+
+// maximal number of independently locked parts of the cache
+const SHARDS: usize = 16;
+
+/// Keeps the objective values of the most recently evaluated schedules (keyed by their
+/// fingerprint), such that revisited schedules are not evaluated again. As fingerprints might
+/// collide, a cached value is only used if the cached schedule is identical to the evaluated one.
+/// The entries are distributed by fingerprint over up to SHARDS shards, each behind its own lock
+/// and with its share of the capacity. If a shard is full, its least recently used entry is
+/// dropped.
+/// With capacity 0, every schedule is evaluated by the objective.
+pub struct EvaluationCache {
+    objective: Arc<Objective<ScheduleWithInfo>>,
+    shards: Vec<Mutex<Lru<(Schedule, ObjectiveValue)>>>,
+    hits: AtomicUsize,
+}
+
+impl EvaluationCache {
+    pub fn new(objective: Arc<Objective<ScheduleWithInfo>>, capacity: usize) -> EvaluationCache {
+        let shard_count = capacity.min(SHARDS);
+        let shards = (0..shard_count)
+            .map(|shard| {
+                let remainder = usize::from(shard < capacity % shard_count);
+                Mutex::new(Lru::new(capacity / shard_count + remainder))
+            })
+            .collect();
+        EvaluationCache {
+            objective,
+            shards,
+            hits: AtomicUsize::new(0),
+        }
+    }
+
+    /// Evaluates the schedule by the objective unless an identical schedule is cached.
+    pub fn evaluate(&self, solution: ScheduleWithInfo) -> EvaluatedSolution<ScheduleWithInfo> {
+        if self.shards.is_empty() {
+            return self.objective.evaluate(solution);
+        }
+        let schedule = solution.get_schedule();
+        let fingerprint = schedule.fingerprint();
+        let shard = &self.shards[(fingerprint % self.shards.len() as u64) as usize];
+        let cached_value = shard
+            .lock()
+            .unwrap()
+            .get(fingerprint)
+            .filter(|(cached_schedule, _)| cached_schedule.is_identical_to(schedule))
+            .map(|(_, objective_value)| objective_value.clone());
+        if let Some(objective_value) = cached_value {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return EvaluatedSolution::new(solution, objective_value);
+        }
+
+        let evaluated = self.objective.evaluate(solution);
+        shard.lock().unwrap().insert(
+            fingerprint,
+            (
+                evaluated.solution().get_schedule().clone(),
+                evaluated.objective_value().clone(),
+            ),
+        );
+        evaluated
+    }
+
+    /// Number of evaluations answered by the cache so far.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of cached evaluations.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Least recently used cache with a fixed positive capacity. The entries are kept in a doubly
+/// linked list (most recently used first) over a vector of slots, such that lookups, insertions
+/// and evictions take constant time.
+struct Lru<V> {
+    capacity: usize,
+    slot_of: HashMap<u64, usize>,
+    slots: Vec<LruSlot<V>>,
+    most_recent: Option<usize>,
+    least_recent: Option<usize>,
+}
+
+struct LruSlot<V> {
+    key: u64,
+    value: V,
+    more_recent: Option<usize>,
+    less_recent: Option<usize>,
+}
+
+impl<V> Lru<V> {
+    fn new(capacity: usize) -> Lru<V> {
+        assert!(capacity > 0, "capacity of the LRU cache must be positive");
+        Lru {
+            capacity,
+            slot_of: HashMap::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            most_recent: None,
+            least_recent: None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// The value of the key (which becomes the most recently used one).
+    fn get(&mut self, key: u64) -> Option<&V> {
+        let slot = *self.slot_of.get(&key)?;
+        self.unlink(slot);
+        self.push_most_recent(slot);
+        Some(&self.slots[slot].value)
+    }
+
+    /// Inserts (or replaces) the value of the key. If the cache is full, the least recently used
+    /// entry is dropped.
+    fn insert(&mut self, key: u64, value: V) {
+        if let Some(&slot) = self.slot_of.get(&key) {
+            self.slots[slot].value = value;
+            self.unlink(slot);
+            self.push_most_recent(slot);
+            return;
+        }
+        let new_slot = LruSlot {
+            key,
+            value,
+            more_recent: None,
+            less_recent: None,
+        };
+        let slot = if self.slots.len() < self.capacity {
+            self.slots.push(new_slot);
+            self.slots.len() - 1
+        } else {
+            let slot = self.least_recent.unwrap();
+            self.unlink(slot);
+            self.slot_of.remove(&self.slots[slot].key);
+            self.slots[slot] = new_slot;
+            slot
+        };
+        self.slot_of.insert(key, slot);
+        self.push_most_recent(slot);
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let more_recent = self.slots[slot].more_recent.take();
+        let less_recent = self.slots[slot].less_recent.take();
+        match more_recent {
+            Some(more_recent) => self.slots[more_recent].less_recent = less_recent,
+            None => self.most_recent = less_recent,
+        }
+        match less_recent {
+            Some(less_recent) => self.slots[less_recent].more_recent = more_recent,
+            None => self.least_recent = more_recent,
+        }
+    }
+
+    fn push_most_recent(&mut self, slot: usize) {
+        self.slots[slot].less_recent = self.most_recent;
+        if let Some(most_recent) = self.most_recent {
+            self.slots[most_recent].more_recent = Some(slot);
+        }
+        self.most_recent = Some(slot);
+        if self.least_recent.is_none() {
+            self.least_recent = Some(slot);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::Lru;
+
+#[test]
+fn lru_evicts_least_recently_used_test() {
+    // ARRANGE
+    let mut lru = Lru::new(2);
+    lru.insert(1, "a");
+    lru.insert(2, "b");
+
+    // ACT
+    let first = lru.get(1).copied(); // 2 is now the least recently used
+    lru.insert(3, "c"); // evicts 2
+    lru.insert(1, "d"); // replaces the value, nothing is evicted
+    lru.insert(4, "e"); // evicts 3
+
+    // ASSERT
+    assert_eq!(first, Some("a"));
+    assert_eq!(lru.len(), 2);
+    assert_eq!(lru.get(2), None);
+    assert_eq!(lru.get(3), None);
+    assert_eq!(lru.get(1), Some(&"d"));
+    assert_eq!(lru.get(4), Some(&"e"));
+}
+
+#[test]
+fn lru_with_capacity_one_test() {
+    // ARRANGE
+    let mut lru = Lru::new(1);
+
+    // ACT
+    lru.insert(1, 10);
+    lru.insert(2, 20);
+    lru.insert(2, 21);
+
+    // ASSERT
+    assert_eq!(lru.len(), 1);
+    assert_eq!(lru.get(1), None);
+    assert_eq!(lru.get(2), Some(&21));
+}
@@ -11,18 +11,35 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod evaluation_cache;
 pub mod neighborhood;
-use std::sync::Arc;
+#[cfg(test)]
+mod tests;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::iter;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
 use std::time::{self as stdtime, Instant};
 
 use crate::objective;
+use model::base_types::RoundingPolicy;
 use model::network::Network;
+use rapid_solve::heuristics::common::ParallelNeighborhood;
+use rapid_solve::heuristics::parallel_local_search::parallel_local_improver::{
+    ParallelLocalImprover, ParallelMinimizer,
+};
 use rapid_solve::heuristics::parallel_local_search::ParallelLocalSearchSolver;
-use rapid_solve::objective::{EvaluatedSolution, Objective};
+use rapid_solve::heuristics::Solver;
+use rapid_solve::objective::{EvaluatedSolution, Objective, ObjectiveValue};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use solution::Schedule;
 
-use rapid_time::Duration;
+use rapid_time::{DateTime, Duration};
 
+use self::evaluation_cache::EvaluationCache;
+use self::neighborhood::operator_selection::OperatorSelection;
 use self::neighborhood::swaps::SwapInfo;
 use self::neighborhood::RSSchedParallelNeighborhood;
 
@@ -46,6 +63,24 @@ impl ScheduleWithInfo {
         }
     }
 
+    /// Description of the move that produced this schedule from the previous schedule, e.g.,
+    /// "PathExchange ... from veh_3 to veh_7, -1 vehicle". Only built on demand (e.g., for the
+    /// accepted neighbor), as the neighborhood produces many candidates.
+    pub fn move_description(&self, previous_schedule: &Schedule) -> String {
+        let vehicle_count_change = self.schedule.number_of_vehicles() as i64
+            - previous_schedule.number_of_vehicles() as i64;
+        format!(
+            "{}, {:+} vehicle{}",
+            self.print_text,
+            vehicle_count_change,
+            if vehicle_count_change.abs() == 1 {
+                ""
+            } else {
+                "s"
+            }
+        )
+    }
+
     pub fn get_schedule(&self) -> &Schedule {
         &self.schedule
     }
@@ -59,10 +94,785 @@ impl ScheduleWithInfo {
     }
 }
 
+/// Like the ParallelMinimizer, evaluates the whole neighborhood in parallel, but selects the best
+/// improving neighbor reproducibly: ties in the objective value are broken by the order of the
+/// schedules. (The reduction order of rayon is unspecified, so the ParallelMinimizer might return
+/// a different neighbor among equally good ones in each run.)
+struct DeterministicParallelMinimizer {
+    neighborhood: Arc<RSSchedParallelNeighborhood>,
+    evaluation_cache: Arc<EvaluationCache>,
+}
+
+impl ParallelLocalImprover<ScheduleWithInfo> for DeterministicParallelMinimizer {
+    fn improve(
+        &self,
+        solution: &EvaluatedSolution<ScheduleWithInfo>,
+    ) -> Option<EvaluatedSolution<ScheduleWithInfo>> {
+        self.neighborhood
+            .neighbors_of(solution.solution())
+            .map(|neighbor| self.evaluation_cache.evaluate(neighbor))
+            .filter(|neighbor| neighbor.objective_value() < solution.objective_value())
+            .min_by(compare_deterministically)
+    }
+}
+
+/// Like the ParallelMinimizer, but the neighbors are evaluated via the evaluation cache.
+struct CachedParallelMinimizer {
+    neighborhood: Arc<RSSchedParallelNeighborhood>,
+    evaluation_cache: Arc<EvaluationCache>,
+}
+
+impl ParallelLocalImprover<ScheduleWithInfo> for CachedParallelMinimizer {
+    fn improve(
+        &self,
+        solution: &EvaluatedSolution<ScheduleWithInfo>,
+    ) -> Option<EvaluatedSolution<ScheduleWithInfo>> {
+        self.neighborhood
+            .neighbors_of(solution.solution())
+            .map(|neighbor| self.evaluation_cache.evaluate(neighbor))
+            .filter(|neighbor| neighbor.objective_value() < solution.objective_value())
+            .min_by(|a, b| a.objective_value().cmp(b.objective_value()))
+    }
+}
+
+/// Total order on evaluated solutions: first by objective value, then by the solution itself.
+fn compare_deterministically<S: Ord>(
+    a: &EvaluatedSolution<S>,
+    b: &EvaluatedSolution<S>,
+) -> Ordering {
+    a.objective_value()
+        .cmp(b.objective_value())
+        .then_with(|| a.solution().cmp(b.solution()))
+}
+
+/// Evaluates only the neighbors touching nodes within a time window and returns the best improving
+/// one. The planning horizon is divided into consecutive windows of the configured size. After
+/// each improvement the search continues in the next window, such that the window slides across
+/// the whole horizon. If a window contains no improving neighbor, the following windows are tried.
+/// Only if no window yields an improvement, None is returned.
+struct WindowedMinimizer {
+    neighborhood: Arc<RSSchedParallelNeighborhood>,
+    evaluation_cache: Arc<EvaluationCache>,
+    time_windows: Vec<(DateTime, DateTime)>,
+    next_window: Mutex<usize>,
+    deterministic: bool,
+}
+
+impl WindowedMinimizer {
+    fn new(
+        neighborhood: Arc<RSSchedParallelNeighborhood>,
+        evaluation_cache: Arc<EvaluationCache>,
+        time_window: Duration,
+        time_horizon: (DateTime, DateTime),
+        deterministic: bool,
+    ) -> WindowedMinimizer {
+        WindowedMinimizer {
+            neighborhood,
+            evaluation_cache,
+            time_windows: time_windows(time_horizon, time_window),
+            next_window: Mutex::new(0),
+            deterministic,
+        }
+    }
+}
+
+impl ParallelLocalImprover<ScheduleWithInfo> for WindowedMinimizer {
+    fn improve(
+        &self,
+        solution: &EvaluatedSolution<ScheduleWithInfo>,
+    ) -> Option<EvaluatedSolution<ScheduleWithInfo>> {
+        let mut next_window = self.next_window.lock().unwrap();
+        for offset in 0..self.time_windows.len() {
+            let window = (*next_window + offset) % self.time_windows.len();
+            let neighborhood = self
+                .neighborhood
+                .restricted_to_time_window(self.time_windows[window]);
+            let improving_neighbors = neighborhood
+                .neighbors_of(solution.solution())
+                .map(|neighbor| self.evaluation_cache.evaluate(neighbor))
+                .filter(|neighbor| neighbor.objective_value() < solution.objective_value());
+            let best_neighbor = if self.deterministic {
+                improving_neighbors.min_by(compare_deterministically)
+            } else {
+                improving_neighbors.min_by(|a, b| a.objective_value().cmp(b.objective_value()))
+            };
+            if best_neighbor.is_some() {
+                *next_window = (window + 1) % self.time_windows.len();
+                return best_neighbor;
+            }
+        }
+        None
+    }
+}
+
+/// Lets the operators generate only their current share of candidates (see OperatorSelection).
+/// If this yields no improving neighbor, all candidates are generated, such that the local
+/// search only stops in a true local optimum. Accepted moves update the operator weights.
+struct SampledMinimizer {
+    sampled: Box<dyn ParallelLocalImprover<ScheduleWithInfo>>,
+    exhaustive: Box<dyn ParallelLocalImprover<ScheduleWithInfo>>,
+    operator_selection: Arc<OperatorSelection>,
+}
+
+impl ParallelLocalImprover<ScheduleWithInfo> for SampledMinimizer {
+    fn improve(
+        &self,
+        solution: &EvaluatedSolution<ScheduleWithInfo>,
+    ) -> Option<EvaluatedSolution<ScheduleWithInfo>> {
+        let improved_solution = self
+            .sampled
+            .improve(solution)
+            .or_else(|| self.exhaustive.improve(solution));
+        if let Some(improved_solution) = improved_solution.as_ref() {
+            self.operator_selection
+                .reward(improved_solution.solution().get_last_swap_info());
+        }
+        improved_solution
+    }
+}
+
+/// Threshold accepting: selects the best neighbor (deterministically) whose objective value is
+/// smaller than the current objective value plus the threshold, i.e., slightly worse neighbors
+/// are accepted as well. The solution the search just came from is not accepted again, such that
+/// two neighbors cannot alternate. After each iteration the threshold is multiplied by decay, so
+/// the search turns into a plain descent. If no neighbor is accepted anymore but a better solution
+/// has been seen before, the search jumps back to it. The decayed threshold is rounded with the
+/// given rounding policy (truncated if rounding would not decrease it).
+struct ThresholdAccepting<S, N> {
+    neighborhood: Arc<N>,
+    objective: Arc<Objective<S>>,
+    decay: f64,
+    rounding: RoundingPolicy,
+    state: Arc<ThresholdState<S>>,
+}
+
+impl<S, N> ThresholdAccepting<S, N> {
+    fn new(
+        neighborhood: Arc<N>,
+        objective: Arc<Objective<S>>,
+        state: Arc<ThresholdState<S>>,
+        decay: f64,
+        rounding: RoundingPolicy,
+    ) -> ThresholdAccepting<S, N> {
+        ThresholdAccepting {
+            neighborhood,
+            objective,
+            decay,
+            rounding,
+            state,
+        }
+    }
+}
+
+impl<S, N> ParallelLocalImprover<S> for ThresholdAccepting<S, N>
+where
+    S: Clone + Ord + Send + Sync,
+    N: ParallelNeighborhood<S>,
+{
+    fn improve(&self, solution: &EvaluatedSolution<S>) -> Option<EvaluatedSolution<S>> {
+        let mut run = self.state.run.lock().unwrap();
+        if run
+            .best_seen
+            .as_ref()
+            .is_none_or(|best| solution.objective_value() < best.objective_value())
+        {
+            run.best_seen = Some(solution.clone());
+        }
+
+        let acceptance_limit = solution.objective_value().clone() + run.threshold.clone();
+        let previous = run.previous.take();
+        let accepted_neighbor = self
+            .neighborhood
+            .neighbors_of(solution.solution())
+            .filter(|neighbor| previous.as_ref() != Some(neighbor))
+            .map(|neighbor| self.objective.evaluate(neighbor))
+            .filter(|neighbor| *neighbor.objective_value() < acceptance_limit)
+            .min_by(compare_deterministically);
+        let decayed_threshold = objective::scale(&run.threshold, self.decay, self.rounding);
+        // rounding up could keep a small threshold constant, so the search would never end
+        run.threshold = if decayed_threshold < run.threshold {
+            decayed_threshold
+        } else {
+            objective::scale(&run.threshold, self.decay, RoundingPolicy::Truncate)
+        };
+
+        let next_solution = accepted_neighbor.or_else(|| {
+            run.best_seen
+                .take()
+                .filter(|best| best.objective_value() < solution.objective_value())
+        });
+        if next_solution.is_some() {
+            run.previous = Some(solution.solution().clone());
+        }
+        next_solution
+    }
+}
+
+/// The threshold, the best solution seen and the previous solution of a threshold accepting run.
+/// Shared with the PlateauRestartSolver, which resets it before each run of the local search,
+/// such that every restart starts again with the initial threshold.
+struct ThresholdState<S> {
+    initial_threshold: ObjectiveValue,
+    run: Mutex<ThresholdRun<S>>,
+}
+
+struct ThresholdRun<S> {
+    threshold: ObjectiveValue,
+    best_seen: Option<EvaluatedSolution<S>>,
+    previous: Option<S>,
+}
+
+impl<S> ThresholdState<S> {
+    fn new(initial_threshold: ObjectiveValue) -> ThresholdState<S> {
+        ThresholdState {
+            run: Mutex::new(ThresholdState::initial_run(&initial_threshold)),
+            initial_threshold,
+        }
+    }
+
+    fn reset(&self) {
+        *self.run.lock().unwrap() = ThresholdState::initial_run(&self.initial_threshold);
+    }
+
+    fn initial_run(initial_threshold: &ObjectiveValue) -> ThresholdRun<S> {
+        ThresholdRun {
+            threshold: initial_threshold.clone(),
+            best_seen: None,
+            previous: None,
+        }
+    }
+}
+
+/// Searches in parallel for an improving neighbor and takes any one that is found (like the
+/// TakeAnyRecursion of rapid_solve). If no improving neighbor is found, the best recursion_width
+/// neighbors (with distinct objective values) of each solution are taken to recursion.
+/// Instead of collecting the candidates in a vector behind a shared mutex, each thread folds its
+/// candidates into a local collection and the collections are merged when the threads join. A
+/// found improvement is announced by an atomic flag, so the search never waits for a lock.
+/// With a candidate budget, no collection retains more candidates than the budget: the worst
+/// candidates are dropped as soon as better ones arrive, so at most budget candidates are taken
+/// to the next recursion level.
+pub struct TakeAnyParallelRecursion<S, N> {
+    recursion_depth: u8,
+    recursion_width: u8,
+    candidate_budget: Option<usize>,
+    neighborhood: Arc<N>,
+    objective: Arc<Objective<S>>,
+}
+
+impl<S, N> TakeAnyParallelRecursion<S, N> {
+    pub fn new(
+        recursion_depth: u8,
+        recursion_width: u8,
+        neighborhood: Arc<N>,
+        objective: Arc<Objective<S>>,
+    ) -> TakeAnyParallelRecursion<S, N> {
+        TakeAnyParallelRecursion {
+            recursion_depth,
+            recursion_width,
+            candidate_budget: None,
+            neighborhood,
+            objective,
+        }
+    }
+
+    pub fn with_candidate_budget(
+        self,
+        candidate_budget: Option<usize>,
+    ) -> TakeAnyParallelRecursion<S, N> {
+        TakeAnyParallelRecursion {
+            candidate_budget,
+            ..self
+        }
+    }
+}
+
+impl<S, N> ParallelLocalImprover<S> for TakeAnyParallelRecursion<S, N>
+where
+    S: Clone + Send + Sync,
+    N: ParallelNeighborhood<S>,
+{
+    fn improve(&self, solution: &EvaluatedSolution<S>) -> Option<EvaluatedSolution<S>> {
+        self.improve_recursion(
+            vec![solution.clone()],
+            solution.objective_value(),
+            self.recursion_depth,
+        )
+    }
+}
+
+impl<S, N> TakeAnyParallelRecursion<S, N>
+where
+    S: Clone + Send + Sync,
+    N: ParallelNeighborhood<S>,
+{
+    fn improve_recursion(
+        &self,
+        solutions: Vec<EvaluatedSolution<S>>,
+        objective_to_beat: &ObjectiveValue,
+        remaining_recursion: u8,
+    ) -> Option<EvaluatedSolution<S>> {
+        let budget = self.candidate_budget.unwrap_or(usize::MAX);
+        let width = if remaining_recursion > 0 {
+            (self.recursion_width as usize).min(budget)
+        } else {
+            0
+        };
+        let found = AtomicBool::new(false);
+        let candidates = solutions
+            .par_iter()
+            .map(|solution| {
+                self.neighborhood
+                    .neighbors_of(solution.solution())
+                    .take_any_while(|_| !found.load(AtomicOrdering::Relaxed))
+                    .map(|neighbor| self.objective.evaluate(neighbor))
+                    .fold(
+                        || RecursionCandidates::new(width),
+                        |candidates, neighbor| {
+                            if neighbor.objective_value() < objective_to_beat {
+                                found.store(true, AtomicOrdering::Relaxed);
+                            }
+                            candidates.insert(neighbor, objective_to_beat)
+                        },
+                    )
+                    .reduce(
+                        || RecursionCandidates::new(width),
+                        RecursionCandidates::merge,
+                    )
+                    .with_limit(budget)
+            })
+            .reduce(
+                || RecursionCandidates::new(budget),
+                RecursionCandidates::merge,
+            );
+        // the solutions of this level are not needed during the recursion
+        drop(solutions);
+
+        if candidates.improving.is_some() {
+            return candidates.improving;
+        }
+        if remaining_recursion == 0 {
+            return None;
+        }
+        self.improve_recursion(candidates.best, objective_to_beat, remaining_recursion - 1)
+    }
+}
+
+/// Candidates of the TakeAnyParallelRecursion: the best improving neighbor found and the best
+/// non-improving neighbors with distinct objective values (sorted), at most limit many.
+struct RecursionCandidates<S> {
+    improving: Option<EvaluatedSolution<S>>,
+    best: Vec<EvaluatedSolution<S>>,
+    limit: usize,
+}
+
+impl<S> RecursionCandidates<S> {
+    fn new(limit: usize) -> RecursionCandidates<S> {
+        RecursionCandidates {
+            improving: None,
+            best: Vec::new(),
+            limit,
+        }
+    }
+
+    fn insert(
+        mut self,
+        neighbor: EvaluatedSolution<S>,
+        objective_to_beat: &ObjectiveValue,
+    ) -> RecursionCandidates<S> {
+        if neighbor.objective_value() < objective_to_beat {
+            self.improving = RecursionCandidates::better(self.improving, Some(neighbor));
+        } else {
+            self.keep_if_among_best(neighbor);
+        }
+        self
+    }
+
+    fn merge(mut self, other: RecursionCandidates<S>) -> RecursionCandidates<S> {
+        self.improving = RecursionCandidates::better(self.improving, other.improving);
+        for candidate in other.best {
+            self.keep_if_among_best(candidate);
+        }
+        self
+    }
+
+    /// Drops the worst candidates beyond the new limit.
+    fn with_limit(mut self, limit: usize) -> RecursionCandidates<S> {
+        self.best.truncate(limit);
+        self.limit = limit;
+        self
+    }
+
+    /// Inserts the candidate at its position, unless a candidate with the same objective value is
+    /// kept already or it is worse than limit many candidates. The worst candidate is dropped
+    /// before the insertion if the limit is reached, so there are never more than limit.
+    fn keep_if_among_best(&mut self, candidate: EvaluatedSolution<S>) {
+        if let Err(position) = self
+            .best
+            .binary_search_by(|kept| kept.objective_value().cmp(candidate.objective_value()))
+        {
+            if position < self.limit {
+                if self.best.len() == self.limit {
+                    self.best.pop();
+                }
+                self.best.insert(position, candidate);
+            }
+        }
+    }
+
+    fn better(
+        a: Option<EvaluatedSolution<S>>,
+        b: Option<EvaluatedSolution<S>>,
+    ) -> Option<EvaluatedSolution<S>> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(if b.objective_value() < a.objective_value() {
+                b
+            } else {
+                a
+            }),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Divides the time horizon into consecutive windows of the given size. The first and the last
+/// window are extended to the beginning and the end of time, such that every node is covered.
+fn time_windows(
+    (horizon_start, horizon_end): (DateTime, DateTime),
+    window_size: Duration,
+) -> Vec<(DateTime, DateTime)> {
+    let mut window_ends = Vec::new();
+    let mut end = horizon_start + window_size;
+    while end < horizon_end {
+        window_ends.push(end);
+        end = end + window_size;
+    }
+    window_ends.push(DateTime::Latest);
+    iter::once(DateTime::Earliest)
+        .chain(window_ends.iter().copied())
+        .zip(window_ends.iter().copied())
+        .collect()
+}
+
+/// Counts the improving moves accepted by the local search per kind of move (see SwapInfo::kind),
+/// e.g., to see which neighborhood operators are productive.
+#[derive(Default)]
+pub struct MoveTally {
+    counts: Mutex<BTreeMap<&'static str, u32>>,
+}
+
+impl MoveTally {
+    fn record(&self, swap_info: SwapInfo) {
+        *self
+            .counts
+            .lock()
+            .unwrap()
+            .entry(swap_info.kind())
+            .or_insert(0) += 1;
+    }
+
+    /// Number of accepted improving moves per kind of move.
+    pub fn counts(&self) -> BTreeMap<&'static str, u32> {
+        self.counts.lock().unwrap().clone()
+    }
+
+    /// Total number of accepted improving moves.
+    pub fn total(&self) -> u32 {
+        self.counts.lock().unwrap().values().sum()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "improvingMoves": self.counts() })
+    }
+}
+
+/// Wraps the ParallelMinimizer (or the DeterministicParallelMinimizer, WindowedMinimizer or
+/// SampledMinimizer) but stops the local search as soon as the current solution reaches
+/// the target objective value (e.g., a lower bound), as no further improvement is possible, or
+/// as soon as the stop flag is set (e.g., on interrupt by the user). In both cases the current
+/// solution, which is the best found so far, is returned by the local search.
+/// Each accepted improvement is recorded in the move tally.
+/// The search also stops if the iteration cap is reached (the iterations are shared by all
+/// restarts).
+struct StoppableImprover {
+    minimizer: Box<dyn ParallelLocalImprover<ScheduleWithInfo>>,
+    target_objective: Option<ObjectiveValue>,
+    stop_flag: Option<Arc<AtomicBool>>,
+    move_tally: Arc<MoveTally>,
+    iterations: Arc<IterationCounter>,
+}
+
+impl ParallelLocalImprover<ScheduleWithInfo> for StoppableImprover {
+    fn improve(
+        &self,
+        solution: &EvaluatedSolution<ScheduleWithInfo>,
+    ) -> Option<EvaluatedSolution<ScheduleWithInfo>> {
+        if is_stopped(&self.stop_flag) {
+            println!("Local search interrupted, returning best solution so far.");
+            return None;
+        }
+        if let Some(target_objective) = self.target_objective.as_ref() {
+            if solution.objective_value() <= target_objective {
+                println!("Target objective value reached.");
+                return None;
+            }
+        }
+        if !self.iterations.try_start_iteration() {
+            println!("Maximal number of iterations reached.");
+            return None;
+        }
+        let improved_solution = self.minimizer.improve(solution);
+        if let Some(improved_solution) = improved_solution.as_ref() {
+            self.move_tally
+                .record(improved_solution.solution().get_last_swap_info());
+        }
+        improved_solution
+    }
+}
+
+/// Counts the neighborhood evaluations of the local search (over all restarts) and enforces the
+/// optional cap.
+pub struct IterationCounter {
+    max_iterations: Option<usize>,
+    iterations: AtomicUsize,
+}
+
+impl IterationCounter {
+    pub fn new(max_iterations: Option<usize>) -> IterationCounter {
+        IterationCounter {
+            max_iterations,
+            iterations: AtomicUsize::new(0),
+        }
+    }
+
+    /// Counts a new iteration. Returns false (without counting) if the cap is reached.
+    fn try_start_iteration(&self) -> bool {
+        self.iterations
+            .fetch_update(
+                AtomicOrdering::Relaxed,
+                AtomicOrdering::Relaxed,
+                |iterations| match self.max_iterations {
+                    Some(max_iterations) if iterations >= max_iterations => None,
+                    _ => Some(iterations + 1),
+                },
+            )
+            .is_ok()
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.max_iterations
+            .is_some_and(|max_iterations| self.iterations() >= max_iterations)
+    }
+
+    /// Number of neighborhood evaluations so far.
+    pub fn iterations(&self) -> usize {
+        self.iterations.load(AtomicOrdering::Relaxed)
+    }
+}
+
+fn is_stopped(stop_flag: &Option<Arc<AtomicBool>>) -> bool {
+    stop_flag
+        .as_ref()
+        .is_some_and(|stop_flag| stop_flag.load(AtomicOrdering::Relaxed))
+}
+
+/// Runs the local search and, whenever it gets stuck in a local optimum, restarts it from a
+/// perturbation of the best schedule found so far (a few random feasibility-preserving moves, see
+/// Schedule::perturb).
+/// Stops as soon as plateau_length restarts in a row did not improve the best schedule, or if
+/// the target objective value is reached, or if the stop flag is set.
+/// With threshold accepting, the threshold is reset before each run of the local search.
+pub struct PlateauRestartSolver {
+    local_search_solver: ParallelLocalSearchSolver<ScheduleWithInfo>,
+    objective: Arc<Objective<ScheduleWithInfo>>,
+    target_objective: Option<ObjectiveValue>,
+    stop_flag: Option<Arc<AtomicBool>>,
+    plateau_length: u32,
+    perturbation_strength: u32,
+    move_tally: Arc<MoveTally>,
+    iterations: Arc<IterationCounter>,
+    threshold_state: Option<Arc<ThresholdState<ScheduleWithInfo>>>,
+}
+
+impl PlateauRestartSolver {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        local_search_solver: ParallelLocalSearchSolver<ScheduleWithInfo>,
+        objective: Arc<Objective<ScheduleWithInfo>>,
+        target_objective: Option<ObjectiveValue>,
+        stop_flag: Option<Arc<AtomicBool>>,
+        plateau_length: u32,
+        perturbation_strength: u32,
+        move_tally: Arc<MoveTally>,
+        iterations: Arc<IterationCounter>,
+    ) -> PlateauRestartSolver {
+        PlateauRestartSolver {
+            local_search_solver,
+            objective,
+            target_objective,
+            stop_flag,
+            plateau_length,
+            perturbation_strength,
+            move_tally,
+            iterations,
+            threshold_state: None,
+        }
+    }
+
+    fn with_threshold_state(
+        self,
+        threshold_state: Option<Arc<ThresholdState<ScheduleWithInfo>>>,
+    ) -> PlateauRestartSolver {
+        PlateauRestartSolver {
+            threshold_state,
+            ..self
+        }
+    }
+
+    /// Runs the local search from the given schedule with a fresh threshold (if any).
+    fn run_local_search(
+        &self,
+        initial_solution: ScheduleWithInfo,
+    ) -> EvaluatedSolution<ScheduleWithInfo> {
+        if let Some(threshold_state) = self.threshold_state.as_ref() {
+            threshold_state.reset();
+        }
+        self.local_search_solver.solve(initial_solution)
+    }
+
+    /// The improving moves accepted by the local search so far (over all restarts).
+    pub fn move_tally(&self) -> &MoveTally {
+        &self.move_tally
+    }
+
+    /// Number of neighborhood evaluations so far (over all restarts).
+    pub fn iterations(&self) -> usize {
+        self.iterations.iterations()
+    }
+
+    /// Applies perturbation_strength random moves to the schedule (see Schedule::perturb). The
+    /// restart number is the seed, such that each restart starts from a different but
+    /// reproducible schedule.
+    fn perturb(&self, schedule: &Schedule, restart: u32) -> ScheduleWithInfo {
+        ScheduleWithInfo::new(
+            schedule.perturb(self.perturbation_strength as usize, restart as u64),
+            SwapInfo::NoSwap,
+            format!(
+                "Restart {}: {} random move(s) applied",
+                restart, self.perturbation_strength
+            ),
+        )
+    }
+}
+
+impl Solver<ScheduleWithInfo> for PlateauRestartSolver {
+    fn solve(&self, initial_solution: ScheduleWithInfo) -> EvaluatedSolution<ScheduleWithInfo> {
+        let mut best_solution = self.run_local_search(initial_solution);
+        let mut restart = 0;
+        let mut restarts_without_improvement = 0;
+        while restarts_without_improvement < self.plateau_length
+            && !is_stopped(&self.stop_flag)
+            && !self.iterations.is_exhausted()
+            && self
+                .target_objective
+                .as_ref()
+                .is_none_or(|target| best_solution.objective_value() > target)
+        {
+            restart += 1;
+            println!(
+                "\nPlateau reached, restarting local search (restart {}):",
+                restart
+            );
+            let perturbed = self.perturb(best_solution.solution().get_schedule(), restart);
+            let solution = self.run_local_search(perturbed);
+            if solution.objective_value() < best_solution.objective_value() {
+                println!("Restart {} improved the best schedule:", restart);
+                self.objective.print_objective_value_with_comparison(
+                    solution.objective_value(),
+                    best_solution.objective_value(),
+                );
+                best_solution = solution;
+                restarts_without_improvement = 0;
+            } else {
+                restarts_without_improvement += 1;
+            }
+        }
+        best_solution
+    }
+}
+
+/// Builds the local search solver. If a target objective value is provided, the search stops
+/// as soon as a solution reaches it. If a stop flag is provided, the search stops as soon as it
+/// is set (e.g., by a signal handler) and returns the best solution found so far.
+/// Plateau restarts, the deterministic selection of the best neighbor, the sliding time window
+/// of the neighborhood, the operator weights, the iteration cap, threshold accepting, the
+/// recursion and the evaluation cache are configured by the local search parameters of the
+/// network's config.
 pub fn build_local_search_solver(
     network: Arc<Network>,
+    target_objective: Option<ObjectiveValue>,
+    stop_flag: Option<Arc<AtomicBool>>,
+) -> PlateauRestartSolver {
+    let config = network.config();
+    let move_tally = Arc::new(MoveTally::default());
+    let iterations = Arc::new(IterationCounter::new(config.local_search.max_iterations));
+    let threshold_state = config
+        .local_search
+        .threshold_accepting
+        .map(|threshold_accepting| {
+            Arc::new(ThresholdState::new(objective::costs_threshold(
+                &network,
+                threshold_accepting.initial_threshold,
+            )))
+        });
+    let objective = Arc::new(objective::build_for(&network));
+    let local_search_solver = build_descent_solver(
+        network,
+        target_objective.clone(),
+        stop_flag.clone(),
+        move_tally.clone(),
+        iterations.clone(),
+        threshold_state.clone(),
+    );
+    PlateauRestartSolver::new(
+        local_search_solver,
+        objective,
+        target_objective,
+        stop_flag,
+        config.local_search.plateau_length,
+        config.local_search.perturbation_strength,
+        move_tally,
+        iterations,
+    )
+    .with_threshold_state(threshold_state)
+}
+
+/// Builds the plain local search (descent) that stops in the first local optimum, or the
+/// threshold accepting search if a threshold state is given.
+fn build_descent_solver(
+    network: Arc<Network>,
+    target_objective: Option<ObjectiveValue>,
+    stop_flag: Option<Arc<AtomicBool>>,
+    move_tally: Arc<MoveTally>,
+    iterations: Arc<IterationCounter>,
+    threshold_state: Option<Arc<ThresholdState<ScheduleWithInfo>>>,
 ) -> ParallelLocalSearchSolver<ScheduleWithInfo> {
-    let objective = Arc::new(objective::build());
+    let objective = Arc::new(objective::build_for(&network));
+    let deterministic = network.config().local_search.deterministic;
+    let decay = network
+        .config()
+        .local_search
+        .threshold_accepting
+        .map_or(1.0, |threshold_accepting| threshold_accepting.decay);
+    let recursion = network.config().local_search.recursion;
+    let rounding = network.config().rounding;
+    let time_window = network.config().local_search.time_window;
+    let time_horizon = network.time_horizon();
+    let evaluation_cache_size = network.config().local_search.evaluation_cache_size;
+    let evaluation_cache = Arc::new(EvaluationCache::new(
+        objective.clone(),
+        evaluation_cache_size,
+    ));
 
     let segment_limit = Duration::new("3:00:00");
     let overhead_threshold = Duration::new("0:10:00"); // tours of real-vehicle-providers are not splitted at nodes under these duration
@@ -84,7 +894,12 @@ pub fn build_local_search_solver(
             println!(
                 "Iteration {} - Swap: {}",
                 iteration_counter,
-                current_solution.solution().get_print_text()
+                match previous_solution {
+                    Some(prev_solution) => current_solution
+                        .solution()
+                        .move_description(prev_solution.solution().get_schedule()),
+                    None => current_solution.solution().get_print_text().to_string(),
+                }
             );
             println!("Objective value:");
             match previous_solution {
@@ -110,10 +925,76 @@ pub fn build_local_search_solver(
         },
     );
 
+    let minimizer_for = |neighborhood: Arc<RSSchedParallelNeighborhood>| -> Box<
+        dyn ParallelLocalImprover<ScheduleWithInfo>,
+    > {
+        if let Some(time_window) = time_window {
+            Box::new(WindowedMinimizer::new(
+                neighborhood,
+                evaluation_cache.clone(),
+                time_window,
+                time_horizon,
+                deterministic,
+            ))
+        } else if deterministic {
+            Box::new(DeterministicParallelMinimizer {
+                neighborhood,
+                evaluation_cache: evaluation_cache.clone(),
+            })
+        } else if evaluation_cache_size > 0 {
+            Box::new(CachedParallelMinimizer {
+                neighborhood,
+                evaluation_cache: evaluation_cache.clone(),
+            })
+        } else {
+            Box::new(ParallelMinimizer::new(neighborhood, objective.clone()))
+        }
+    };
+    let operator_selection = neighborhood.operator_selection();
+    let minimizer: Box<dyn ParallelLocalImprover<ScheduleWithInfo>> =
+        if let Some(threshold_state) = threshold_state {
+            // all candidates are generated, as a sampled neighborhood would end the search early
+            // (the config rules out a time window, operator weights and the evaluation cache)
+            Box::new(ThresholdAccepting::new(
+                Arc::new(neighborhood.exhaustive()),
+                objective.clone(),
+                threshold_state,
+                decay,
+                rounding,
+            ))
+        } else if let Some(recursion) = recursion {
+            // as for threshold accepting, all candidates are generated
+            Box::new(
+                TakeAnyParallelRecursion::new(
+                    recursion.depth,
+                    recursion.width,
+                    Arc::new(neighborhood.exhaustive()),
+                    objective.clone(),
+                )
+                .with_candidate_budget(recursion.candidate_budget),
+            )
+        } else if operator_selection.is_exhaustive() {
+            minimizer_for(neighborhood.clone())
+        } else {
+            Box::new(SampledMinimizer {
+                sampled: minimizer_for(neighborhood.clone()),
+                exhaustive: minimizer_for(Arc::new(neighborhood.exhaustive())),
+                operator_selection,
+            })
+        };
+
+    let local_improver = Box::new(StoppableImprover {
+        minimizer,
+        target_objective,
+        stop_flag,
+        move_tally,
+        iterations,
+    });
+
     ParallelLocalSearchSolver::with_options(
         neighborhood,
         objective,
-        None,
+        Some(local_improver),
         Some(function_between_steps),
         None,
         None,
@@ -11,17 +11,19 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod operator_selection;
 pub mod swaps;
 use model::base_types::{NodeIdx, VehicleIdx};
 use model::network::Network;
 use rapid_solve::heuristics::common::ParallelNeighborhood;
-use rapid_time::Duration;
+use rapid_time::{DateTime, Duration};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use solution::{segment::Segment, Schedule};
 use std::sync::Arc;
 
 use std::iter;
 
+use self::operator_selection::{sample, Operator, OperatorSelection};
 use self::swaps::{PathExchange, SpawnVehicleForMaintenance, Swap, SwapInfo};
 
 use super::ScheduleWithInfo;
@@ -30,6 +32,9 @@ use super::ScheduleWithInfo;
 pub struct RSSchedParallelNeighborhood {
     segment_length_limit: Option<Duration>,
     overhead_threshold: Option<Duration>,
+    time_window: Option<(DateTime, DateTime)>,
+    operator_selection: Arc<OperatorSelection>,
+    exhaustive: bool, // ignore the operator shares (disabled operators stay disabled)
     network: Arc<Network>,
 }
 
@@ -42,9 +47,63 @@ impl RSSchedParallelNeighborhood {
         RSSchedParallelNeighborhood {
             segment_length_limit,
             overhead_threshold,
+            time_window: None,
+            operator_selection: Arc::new(OperatorSelection::new(
+                network.config().local_search.operator_weights,
+                network.config().local_search.adaptation_rate,
+            )),
+            exhaustive: false,
             network,
         }
     }
+
+    pub fn operator_selection(&self) -> Arc<OperatorSelection> {
+        self.operator_selection.clone()
+    }
+
+    /// Same neighborhood, but every enabled operator generates all its candidates regardless of
+    /// its current weight.
+    pub fn exhaustive(&self) -> RSSchedParallelNeighborhood {
+        RSSchedParallelNeighborhood {
+            exhaustive: true,
+            ..self.clone()
+        }
+    }
+
+    /// Takes the share of the candidates that the operator currently generates.
+    fn sample<T>(&self, candidates: Vec<T>, operator: Operator) -> Vec<T> {
+        let share = if !self.operator_selection.is_enabled(operator) {
+            0.0
+        } else if self.exhaustive {
+            1.0
+        } else {
+            self.operator_selection.share(operator)
+        };
+        sample(candidates, share, self.operator_selection.round())
+    }
+
+    /// Restricts the neighborhood to moves touching a node that starts within the given time
+    /// window (start inclusive, end exclusive): maintenance slots and hitch-hiking trips to add,
+    /// nodes to remove, and the first node of exchanged segments.
+    pub fn restricted_to_time_window(
+        &self,
+        time_window: (DateTime, DateTime),
+    ) -> RSSchedParallelNeighborhood {
+        RSSchedParallelNeighborhood {
+            time_window: Some(time_window),
+            ..self.clone()
+        }
+    }
+
+    fn is_in_time_window(&self, node: NodeIdx) -> bool {
+        match self.time_window {
+            None => true,
+            Some((start, end)) => {
+                let start_time = self.network.node(node).start_time();
+                start <= start_time && start_time < end
+            }
+        }
+    }
 }
 
 impl ParallelNeighborhood<ScheduleWithInfo> for RSSchedParallelNeighborhood {
@@ -52,14 +111,17 @@ impl ParallelNeighborhood<ScheduleWithInfo> for RSSchedParallelNeighborhood {
         &'a self,
         schedule_with_info: &'a ScheduleWithInfo,
     ) -> impl ParallelIterator<Item = ScheduleWithInfo> + 'a {
+        self.operator_selection.next_round();
         let spawning_iterator = self.spawn_vehicle_for_maintenance_iterator(schedule_with_info);
         let segment_exchange_iterator = self.segment_exchange_iterator(schedule_with_info);
         let hitch_hiking_iterator = self.hitch_hiking_iterator(schedule_with_info);
         let remove_single_node_iterator = self.remove_single_node_iterator(schedule_with_info);
+        let home_vehicle_iterator = self.home_vehicle_iterator(schedule_with_info);
         spawning_iterator
             .chain(segment_exchange_iterator)
             .chain(hitch_hiking_iterator)
             .chain(remove_single_node_iterator)
+            .chain(home_vehicle_iterator)
     }
 }
 
@@ -74,8 +136,9 @@ impl RSSchedParallelNeighborhood {
             .network
             .maintenance_nodes()
             .filter(|&m| {
-                schedule.train_formation_of(m).vehicle_count()
-                    < self.network.track_count_of_maintenance_slot(m)
+                self.is_in_time_window(m)
+                    && schedule.train_formation_of(m).vehicle_count()
+                        < self.network.track_count_of_maintenance_slot(m)
             })
             .collect();
 
@@ -85,10 +148,26 @@ impl RSSchedParallelNeighborhood {
                 / self.network.track_count_of_maintenance_slot(m)
         });
 
+        // while vehicles miss their mandatory daily maintenance visit, each of them is offered
+        // every maintenance slot in every round (unless the operator is disabled)
+        let maintenance_nodes = if schedule.missing_mandatory_maintenance() > 0
+            && self
+                .operator_selection
+                .is_enabled(Operator::SpawnVehicleForMaintenance)
+        {
+            maintenance_nodes
+        } else {
+            self.sample(maintenance_nodes, Operator::SpawnVehicleForMaintenance)
+        };
+
         maintenance_nodes
             .into_par_iter()
             .flat_map(move |maintenance| {
-                let receivers: Vec<_> = schedule.vehicles_iter_all().collect();
+                // vehicles that already visit a maintenance slot are rejected by the swap
+                let receivers: Vec<_> = schedule
+                    .vehicles_iter_all()
+                    .filter(|&vehicle| !schedule.tour_of(vehicle).unwrap().visits_maintenance())
+                    .collect();
                 receivers.into_par_iter().filter_map(move |receiver| {
                     let swap = SpawnVehicleForMaintenance::new(maintenance, receiver);
                     match swap.apply(schedule) {
@@ -133,7 +212,7 @@ impl RSSchedParallelNeighborhood {
         }
 
         // as provider first take dummies then real Vehicles:
-        providers.into_par_iter().flat_map(move |provider|
+        self.sample(providers, Operator::PathExchange).into_par_iter().flat_map(move |provider|
             // create segment of provider's tour
             self.segments(provider, schedule)
             .flat_map(move |seg|
@@ -173,21 +252,27 @@ impl RSSchedParallelNeighborhood {
 
         let vehicles: Vec<_> = schedule.vehicles_iter_all().collect();
 
-        vehicles.into_par_iter().flat_map(move |vehicle| {
-            let vehicle_type = schedule.vehicle_type_of(vehicle).unwrap();
-            let service_nodes: Vec<_> = self.network.service_nodes(vehicle_type).collect();
-            service_nodes.into_par_iter().filter_map(move |node| {
-                let swap = swaps::AddTripForHitchHiking::new(node, vehicle);
-                match swap.apply(schedule) {
-                    Ok(new_schedule) => Some(ScheduleWithInfo::new(
-                        new_schedule,
-                        SwapInfo::AddTripForHitchHiking(vehicle),
-                        format!("{}", swap),
-                    )),
-                    Err(_) => None,
-                }
+        self.sample(vehicles, Operator::AddTripForHitchHiking)
+            .into_par_iter()
+            .flat_map(move |vehicle| {
+                let vehicle_type = schedule.vehicle_type_of(vehicle).unwrap();
+                let service_nodes: Vec<_> = self
+                    .network
+                    .service_nodes(vehicle_type)
+                    .filter(|&node| self.is_in_time_window(node))
+                    .collect();
+                service_nodes.into_par_iter().filter_map(move |node| {
+                    let swap = swaps::AddTripForHitchHiking::new(node, vehicle);
+                    match swap.apply(schedule) {
+                        Ok(new_schedule) => Some(ScheduleWithInfo::new(
+                            new_schedule,
+                            SwapInfo::AddTripForHitchHiking(vehicle),
+                            format!("{}", swap),
+                        )),
+                        Err(_) => None,
+                    }
+                })
             })
-        })
     }
 
     pub fn remove_single_node_iterator<'a>(
@@ -197,21 +282,58 @@ impl RSSchedParallelNeighborhood {
         let schedule = schedule_with_info.get_schedule();
         let vehicles: Vec<_> = schedule.vehicles_iter_all().collect();
 
-        vehicles.into_par_iter().flat_map(move |vehicle| {
-            let tour = schedule.tour_of(vehicle).unwrap();
-            let non_depot_nodes: Vec<_> = tour.all_non_depot_nodes_iter().collect();
-            non_depot_nodes.into_par_iter().filter_map(move |node| {
-                let swap = swaps::RemoveSingleNode::new(node, vehicle);
-                match swap.apply(schedule) {
-                    Ok(new_schedule) => Some(ScheduleWithInfo::new(
-                        new_schedule,
-                        SwapInfo::RemoveSingleNode(vehicle),
-                        format!("{}", swap),
-                    )),
-                    Err(_) => None,
-                }
+        self.sample(vehicles, Operator::RemoveSingleNode)
+            .into_par_iter()
+            .flat_map(move |vehicle| {
+                let tour = schedule.tour_of(vehicle).unwrap();
+                let non_depot_nodes: Vec<_> = tour
+                    .all_non_depot_nodes_iter()
+                    .filter(|&node| self.is_in_time_window(node))
+                    .collect();
+                non_depot_nodes.into_par_iter().filter_map(move |node| {
+                    let swap = swaps::RemoveSingleNode::new(node, vehicle);
+                    match swap.apply(schedule) {
+                        Ok(new_schedule) => Some(ScheduleWithInfo::new(
+                            new_schedule,
+                            SwapInfo::RemoveSingleNode(vehicle),
+                            format!("{}", swap),
+                        )),
+                        Err(_) => None,
+                    }
+                })
+            })
+    }
+
+    /// Creates all HomeVehicles where every vehicle is homed at every depot (except the overflow
+    /// depot).
+    pub fn home_vehicle_iterator<'a>(
+        &'a self,
+        schedule_with_info: &'a ScheduleWithInfo,
+    ) -> impl ParallelIterator<Item = ScheduleWithInfo> + 'a {
+        let schedule = schedule_with_info.get_schedule();
+        let vehicles: Vec<_> = schedule.vehicles_iter_all().collect();
+        let (overflow_depot, _, _) = self.network.overflow_depot_idxs();
+
+        self.sample(vehicles, Operator::HomeVehicle)
+            .into_par_iter()
+            .flat_map(move |vehicle| {
+                let depots: Vec<_> = self
+                    .network
+                    .depots_iter()
+                    .filter(|&depot| depot != overflow_depot)
+                    .collect();
+                depots.into_par_iter().filter_map(move |depot| {
+                    let swap = swaps::HomeVehicle::new(vehicle, depot);
+                    match swap.apply(schedule) {
+                        Ok(new_schedule) => Some(ScheduleWithInfo::new(
+                            new_schedule,
+                            SwapInfo::HomeVehicle(vehicle),
+                            format!("{}", swap),
+                        )),
+                        Err(_) => None,
+                    }
+                })
             })
-        })
     }
 
     fn segments<'a>(
@@ -234,6 +356,8 @@ impl RSSchedParallelNeighborhood {
             .filter(move |(_, n)| {
                 schedule.is_dummy(provider) || tour.preceding_overhead(*n).unwrap() >= threshold
             })
+            // only take nodes within the time window (if any)
+            .filter(move |(_, n)| self.is_in_time_window(*n))
             .flat_map(move |(i, seg_start)| {
                 // all non-depot nodes (after the start) could be the end of the segment
                 let end_nodes: Vec<_> = tour
@@ -0,0 +1,148 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use model::config::OperatorWeights;
+
+use super::swaps::SwapInfo;
+
+/// Weights of an operator that was not successful recently do not drop below this fraction of
+/// its configured weight, such that every enabled operator keeps generating candidates.
+const MINIMAL_RELATIVE_WEIGHT: f64 = 0.1;
+
+/// The operators of the neighborhood (in the order of OperatorWeights::as_array).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operator {
+    SpawnVehicleForMaintenance,
+    PathExchange,
+    AddTripForHitchHiking,
+    RemoveSingleNode,
+    HomeVehicle,
+}
+
+impl Operator {
+    fn index(self) -> usize {
+        match self {
+            Operator::SpawnVehicleForMaintenance => 0,
+            Operator::PathExchange => 1,
+            Operator::AddTripForHitchHiking => 2,
+            Operator::RemoveSingleNode => 3,
+            Operator::HomeVehicle => 4,
+        }
+    }
+
+    /// The operator that produced a move. None for SwapInfo::NoSwap.
+    pub fn of(swap_info: SwapInfo) -> Option<Operator> {
+        match swap_info {
+            SwapInfo::SpawnVehicleForMaintenance(_) => Some(Operator::SpawnVehicleForMaintenance),
+            SwapInfo::PathExchange(_) => Some(Operator::PathExchange),
+            SwapInfo::AddTripForHitchHiking(_) => Some(Operator::AddTripForHitchHiking),
+            SwapInfo::RemoveSingleNode(_) => Some(Operator::RemoveSingleNode),
+            SwapInfo::HomeVehicle(_) => Some(Operator::HomeVehicle),
+            SwapInfo::NoSwap => None,
+        }
+    }
+}
+
+/// Decides which share of its candidates each operator generates. The share of an operator is its
+/// weight relative to the largest weight. With a positive adaptation rate, the weights follow the
+/// recent successes (adaptive large neighborhood search): after each accepted move the weight of
+/// the successful operator moves towards its configured weight and all other weights move
+/// towards MINIMAL_RELATIVE_WEIGHT times their configured weight. Operators with a configured
+/// weight of zero are never applied.
+pub struct OperatorSelection {
+    configured_weights: [f64; 5],
+    weights: Mutex<[f64; 5]>,
+    adaptation_rate: f64,
+    round: AtomicUsize,
+}
+
+impl OperatorSelection {
+    pub fn new(operator_weights: OperatorWeights, adaptation_rate: f64) -> OperatorSelection {
+        OperatorSelection {
+            configured_weights: operator_weights.as_array(),
+            weights: Mutex::new(operator_weights.as_array()),
+            adaptation_rate,
+            round: AtomicUsize::new(0),
+        }
+    }
+
+    /// True if every operator generates all its candidates (or none if disabled), i.e., the
+    /// weights are uniform and do not adapt.
+    pub fn is_exhaustive(&self) -> bool {
+        let enabled: Vec<f64> = self
+            .configured_weights
+            .iter()
+            .copied()
+            .filter(|w| *w > 0.0)
+            .collect();
+        self.adaptation_rate == 0.0 && enabled.iter().all(|w| *w == enabled[0])
+    }
+
+    pub fn is_enabled(&self, operator: Operator) -> bool {
+        self.configured_weights[operator.index()] > 0.0
+    }
+
+    /// Share of its candidates the operator currently generates (between 0 and 1).
+    pub fn share(&self, operator: Operator) -> f64 {
+        let weights = self.weights.lock().unwrap();
+        let maximal_weight = weights.iter().copied().fold(0.0, f64::max);
+        if maximal_weight == 0.0 {
+            0.0
+        } else {
+            weights[operator.index()] / maximal_weight
+        }
+    }
+
+    /// Updates the weights after the move of the given kind was accepted.
+    pub fn reward(&self, swap_info: SwapInfo) {
+        if self.adaptation_rate == 0.0 {
+            return;
+        }
+        let successful = Operator::of(swap_info).map(Operator::index);
+        let mut weights = self.weights.lock().unwrap();
+        for (i, weight) in weights.iter_mut().enumerate() {
+            let target = if Some(i) == successful {
+                self.configured_weights[i]
+            } else {
+                MINIMAL_RELATIVE_WEIGHT * self.configured_weights[i]
+            };
+            *weight = (1.0 - self.adaptation_rate) * *weight + self.adaptation_rate * target;
+        }
+    }
+
+    /// Starts the next round. The sampled candidates are rotated by the round, such that all
+    /// candidates are generated over consecutive rounds.
+    pub fn next_round(&self) {
+        self.round.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn round(&self) -> usize {
+        self.round.load(Ordering::Relaxed)
+    }
+}
+
+/// Takes the given share of the candidates (rounded up), starting at a position that rotates
+/// with the round.
+pub fn sample<T>(mut candidates: Vec<T>, share: f64, round: usize) -> Vec<T> {
+    if share >= 1.0 || candidates.is_empty() {
+        return candidates;
+    }
+    let count = (candidates.len() as f64 * share).ceil() as usize;
+    let offset = round * count % candidates.len();
+    candidates.rotate_left(offset);
+    candidates.truncate(count);
+    candidates
+}
@@ -12,10 +12,12 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 mod add_trip_for_hitch_hiking;
+mod home_vehicle;
 mod path_exchange;
 mod remove_single_node;
 mod spawn_vehicle_for_maintenance;
 pub use add_trip_for_hitch_hiking::AddTripForHitchHiking;
+pub use home_vehicle::HomeVehicle;
 pub use path_exchange::PathExchange;
 pub use remove_single_node::RemoveSingleNode;
 pub use spawn_vehicle_for_maintenance::SpawnVehicleForMaintenance;
@@ -36,9 +38,24 @@ pub enum SwapInfo {
     PathExchange(VehicleIdx),               // last provider
     AddTripForHitchHiking(VehicleIdx),      // last vehicle
     RemoveSingleNode(VehicleIdx),           // last vehicle
+    HomeVehicle(VehicleIdx),                // last vehicle
     NoSwap,
 }
 
+impl SwapInfo {
+    /// Name of the kind of move (e.g., for telemetry).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SwapInfo::SpawnVehicleForMaintenance(_) => "spawnVehicleForMaintenance",
+            SwapInfo::PathExchange(_) => "pathExchange",
+            SwapInfo::AddTripForHitchHiking(_) => "addTripForHitchHiking",
+            SwapInfo::RemoveSingleNode(_) => "removeSingleNode",
+            SwapInfo::HomeVehicle(_) => "homeVehicle",
+            SwapInfo::NoSwap => "noSwap",
+        }
+    }
+}
+
 // assumes that all vehicles are real vehicles in the given schedule
 fn improve_depot_and_recompute_transitions(
     schedule: Schedule,
@@ -0,0 +1,56 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+use model::base_types::{DepotIdx, VehicleIdx};
+use solution::Schedule;
+
+use super::Swap;
+
+/// Moves the start and the end depot of a vehicle to the given depot, such that the vehicle is
+/// homed at this depot.
+pub struct HomeVehicle {
+    vehicle: VehicleIdx,
+    depot: DepotIdx,
+}
+
+impl HomeVehicle {
+    pub(crate) fn new(vehicle: VehicleIdx, depot: DepotIdx) -> HomeVehicle {
+        HomeVehicle { vehicle, depot }
+    }
+}
+
+impl Swap for HomeVehicle {
+    fn apply(&self, schedule: &Schedule) -> Result<Schedule, String> {
+        let network = schedule.get_network();
+        let tour = schedule.tour_of(self.vehicle)?;
+        if tour.start_depot().unwrap() == network.get_start_depot_node(self.depot)
+            && tour.end_depot().unwrap() == network.get_end_depot_node(self.depot)
+        {
+            return Err(format!(
+                "Vehicle {} is already homed at {}",
+                self.vehicle, self.depot
+            ));
+        }
+        schedule
+            .home_vehicle_at(self.vehicle, self.depot)
+            .map_err(String::from)
+    }
+}
+
+impl fmt::Display for HomeVehicle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HomeVehicle {} at {}", self.vehicle, self.depot)
+    }
+}
@@ -31,7 +31,9 @@ impl RemoveSingleNode {
 
 impl Swap for RemoveSingleNode {
     fn apply(&self, schedule: &Schedule) -> Result<Schedule, String> {
-        schedule.remove_segment(Segment::new(self.node, self.node), self.vehicle)
+        schedule
+            .remove_segment(Segment::new(self.node, self.node), self.vehicle)
+            .map_err(String::from)
         /* let first_schedule =
             schedule.remove_segment(Segment::new(self.node, self.node), self.vehicle)?;
         if schedule.get_network().node(self.node).is_maintenance() {
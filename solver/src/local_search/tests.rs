@@ -0,0 +1,1010 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    fs::File,
+    io::Read,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use itertools::Itertools;
+use model::{
+    base_types::{NodeIdx, RoundingPolicy, VehicleIdx, VehicleTypeIdx},
+    config::OperatorWeights,
+    json_serialisation::load_rolling_stock_problem_instance_from_json,
+    network::Network,
+};
+use rapid_solve::{
+    heuristics::{
+        common::ParallelNeighborhood,
+        parallel_local_search::{
+            parallel_local_improver::{ParallelLocalImprover, TakeAnyRecursion},
+            ParallelLocalSearchSolver,
+        },
+        Solver,
+    },
+    objective::{BaseValue, Coefficient, Indicator, LinearCombination, Objective, ObjectiveValue},
+};
+use rapid_time::Duration;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde_json::json;
+use solution::{Schedule, ScheduleError};
+
+use crate::{min_cost_flow_solver::MinCostFlowSolver, objective};
+
+use super::{
+    build_local_search_solver,
+    evaluation_cache::EvaluationCache,
+    neighborhood::{
+        operator_selection::{sample, Operator, OperatorSelection},
+        swaps::SwapInfo,
+        RSSchedParallelNeighborhood,
+    },
+    time_windows, DeterministicParallelMinimizer, IterationCounter, MoveTally, RecursionCandidates,
+    ScheduleWithInfo, StoppableImprover, TakeAnyParallelRecursion, ThresholdAccepting,
+    ThresholdState,
+};
+
+fn load_test_network() -> Arc<Network> {
+    load_rolling_stock_problem_instance_from_json(load_test_input())
+}
+
+/// Three vehicles following the routes. Plain descent gets stuck in a local optimum with
+/// unserved passengers from here.
+fn local_optimum_start_schedule(network: Arc<Network>) -> Schedule {
+    let trip = NodeIdx::service_from;
+    let vehicle_type = network.vehicle_type_for(trip(12));
+    [
+        vec![trip(12), trip(13), trip(14), trip(15), trip(17)],
+        vec![trip(18), trip(19)],
+        vec![trip(16)],
+    ]
+    .into_iter()
+    .fold(Schedule::empty(network), |schedule, path| {
+        schedule
+            .spawn_vehicle_for_path(vehicle_type, path)
+            .unwrap()
+            .0
+    })
+}
+
+fn load_test_input() -> serde_json::Value {
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    serde_json::from_str(&input_data).unwrap()
+}
+
+#[test]
+fn lower_bound_test() {
+    // ARRANGE
+    let network = load_test_network();
+
+    // ACT
+    let lower_bound = objective::lower_bound(&network);
+
+    // ASSERT
+    assert_eq!(
+        lower_bound
+            .iter()
+            .map(|value| value.unwrap_integer())
+            .collect::<Vec<_>>(),
+        vec![0, 0, 0, 0, 4, 1290800, 0]
+    );
+}
+
+#[test]
+fn local_search_stops_at_target_objective_test() {
+    // ARRANGE
+    let network = load_test_network();
+    let objective = objective::build();
+    let start_solution = ScheduleWithInfo::new(
+        MinCostFlowSolver::initialize(network.clone()).solve(),
+        SwapInfo::NoSwap,
+        String::from("Result from min cost flow solver"),
+    );
+    let target_objective = objective
+        .evaluate(start_solution.clone())
+        .objective_value()
+        .clone();
+    let local_search_solver =
+        build_local_search_solver(network, Some(target_objective.clone()), None);
+
+    // ACT
+    let result = local_search_solver.solve(start_solution.clone());
+
+    // ASSERT
+    assert!(*result.objective_value() == target_objective);
+    assert!(*result.solution() == start_solution);
+    assert_eq!(
+        result.solution().get_print_text(),
+        "Result from min cost flow solver"
+    );
+}
+
+#[test]
+fn evaluation_cache_matches_fresh_evaluation_test() {
+    // ARRANGE
+    let network = load_test_network();
+    let objective = Arc::new(objective::build());
+    let evaluation_cache = EvaluationCache::new(objective.clone(), 1);
+    let schedule = |schedule: Schedule| {
+        ScheduleWithInfo::new(schedule, SwapInfo::NoSwap, String::from("test"))
+    };
+    let first_schedule = MinCostFlowSolver::initialize(network.clone()).solve();
+    let second_schedule = local_optimum_start_schedule(network);
+    assert!(first_schedule.fingerprint() != second_schedule.fingerprint());
+
+    // ACT
+    let first_evaluation = evaluation_cache.evaluate(schedule(first_schedule.clone()));
+    let cached_evaluation = evaluation_cache.evaluate(schedule(first_schedule.clone()));
+    let hits_after_revisit = evaluation_cache.hits();
+    evaluation_cache.evaluate(schedule(second_schedule)); // evicts the first schedule
+    let evaluation_after_eviction = evaluation_cache.evaluate(schedule(first_schedule.clone()));
+
+    // ASSERT
+    let fresh_value = objective
+        .evaluate(schedule(first_schedule))
+        .objective_value()
+        .clone();
+    assert_eq!(*first_evaluation.objective_value(), fresh_value);
+    assert_eq!(*cached_evaluation.objective_value(), fresh_value);
+    assert_eq!(*evaluation_after_eviction.objective_value(), fresh_value);
+    assert_eq!(hits_after_revisit, 1);
+    assert_eq!(evaluation_cache.hits(), 1);
+    assert_eq!(evaluation_cache.len(), 1);
+}
+
+#[test]
+fn move_description_of_neighbors_test() {
+    // ARRANGE
+    let network = load_test_network();
+    let start_solution = ScheduleWithInfo::new(
+        MinCostFlowSolver::initialize(network.clone()).solve(),
+        SwapInfo::NoSwap,
+        String::from("Result from min cost flow solver"),
+    );
+    let neighborhood = RSSchedParallelNeighborhood::new(None, None, network);
+
+    // ACT
+    let neighbors: Vec<ScheduleWithInfo> = neighborhood.neighbors_of(&start_solution).collect();
+
+    // ASSERT
+    assert!(!neighbors.is_empty());
+    let removal = neighbors
+        .iter()
+        .find(|neighbor| {
+            matches!(neighbor.get_last_swap_info(), SwapInfo::RemoveSingleNode(_))
+                && neighbor.get_schedule().number_of_vehicles()
+                    == start_solution.get_schedule().number_of_vehicles()
+        })
+        .unwrap();
+    assert_eq!(
+        removal.move_description(start_solution.get_schedule()),
+        format!("{}, +0 vehicles", removal.get_print_text())
+    );
+    for neighbor in neighbors.iter() {
+        assert!(neighbor
+            .move_description(start_solution.get_schedule())
+            .starts_with(neighbor.get_print_text()));
+    }
+}
+
+#[test]
+fn fixed_fleet_is_respected_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["fixedFleet"] = serde_json::json!([
+        {"depot": "depot1", "vehicleType": "vt1", "count": 1},
+        {"depot": "depot2", "vehicleType": "vt2", "count": 1}
+    ]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let depot1 = network
+        .depots_iter()
+        .find(|&d| network.get_depot(d).id() == "depot1")
+        .unwrap();
+    let depot2 = network
+        .depots_iter()
+        .find(|&d| network.get_depot(d).id() == "depot2")
+        .unwrap();
+    let local_search_solver = build_local_search_solver(network.clone(), None, None);
+
+    // ACT
+    let start_schedule = MinCostFlowSolver::initialize(network.clone()).solve();
+    let result = local_search_solver.solve(ScheduleWithInfo::new(
+        start_schedule.clone(),
+        SwapInfo::NoSwap,
+        String::from("Result from min cost flow solver"),
+    ));
+
+    // ASSERT
+    for schedule in [&start_schedule, result.solution().get_schedule()] {
+        assert!(schedule.number_of_vehicles() <= 2);
+        assert!(schedule.unserved_passengers().0 > 0);
+        for depot in network.depots_iter() {
+            let fleet_size = if depot == depot1 || depot == depot2 {
+                1
+            } else {
+                0
+            };
+            assert!(schedule.number_of_vehicles_spawned_at(depot) <= fleet_size);
+        }
+    }
+}
+
+#[test]
+fn forbidden_vehicle_type_is_never_assigned_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    // vt3 has enough seats for every trip and may operate all routes except route_1-2
+    input_data["vehicleTypes"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"id": "vt3", "capacity": 200, "seats": 200, "length": 100}));
+    input_data["depots"][0]["allowedTypes"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"vehicleType": "vt3", "capacity": 2}));
+    for route in input_data["routes"].as_array_mut().unwrap() {
+        route["forbiddenVehicleTypes"] = json!([]);
+    }
+    input_data["routes"][0]["forbiddenVehicleTypes"] = json!(["vt3"]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let vt3 = VehicleTypeIdx::from(2);
+    let trip12 = NodeIdx::service_from(12);
+    let local_search_solver = build_local_search_solver(network.clone(), None, None);
+
+    // ACT
+    let start_schedule = MinCostFlowSolver::initialize(network.clone()).solve();
+    let result = local_search_solver.solve(ScheduleWithInfo::new(
+        start_schedule.clone(),
+        SwapInfo::NoSwap,
+        String::from("Result from min cost flow solver"),
+    ));
+
+    // ASSERT
+    assert!(!network.compatible_with_vehicle_type(trip12, vt3));
+    assert!(network.compatible_with_vehicle_type(NodeIdx::service_from(13), vt3));
+    for schedule in [&start_schedule, result.solution().get_schedule()] {
+        assert!(schedule
+            .train_formation_of(trip12)
+            .iter()
+            .all(|vehicle| vehicle.type_idx() != vt3));
+        assert!(matches!(
+            schedule.spawn_vehicle_for_path(vt3, vec![trip12]),
+            Err(ScheduleError::IncompatibleVehicleType(_))
+        ));
+    }
+}
+
+#[test]
+fn missing_mandatory_maintenance_is_resolved_by_neighborhood_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["maintenance"]["mandatoryDailyVisit"] = json!(true);
+    input_data["parameters"]["localSearch"] =
+        json!({"operatorWeights": {"spawnVehicleForMaintenance": 0.01}});
+    input_data["maintenanceSlots"] = json!([{"id": "maintenance_loc2", "location": "loc2",
+        "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00", "trackCount": 2}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let objective = objective::build_for(&network);
+    let (start_schedule, vehicle) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![NodeIdx::service_from(12)])
+        .unwrap();
+    let start_solution =
+        ScheduleWithInfo::new(start_schedule, SwapInfo::NoSwap, String::from("Start"));
+    let neighborhood = RSSchedParallelNeighborhood::new(None, None, network);
+
+    // ACT
+    let neighbors: Vec<ScheduleWithInfo> = neighborhood.neighbors_of(&start_solution).collect();
+
+    // ASSERT
+    assert_eq!(
+        start_solution
+            .get_schedule()
+            .missing_mandatory_maintenance(),
+        1
+    );
+    let with_maintenance = neighbors
+        .iter()
+        .find(|neighbor| {
+            neighbor.get_last_swap_info() == SwapInfo::SpawnVehicleForMaintenance(vehicle)
+        })
+        .unwrap();
+    assert_eq!(
+        with_maintenance
+            .get_schedule()
+            .missing_mandatory_maintenance(),
+        0
+    );
+    assert!(
+        objective
+            .evaluate(with_maintenance.clone())
+            .objective_value()
+            < objective.evaluate(start_solution).objective_value()
+    );
+}
+
+#[test]
+fn plateau_restart_improves_local_optimum_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["localSearch"] =
+        json!({"plateauLength": 3, "perturbationStrength": 2});
+    let network_with_restarts = load_rolling_stock_problem_instance_from_json(input_data);
+    let network = load_test_network();
+    let start_schedule = local_optimum_start_schedule(network.clone());
+    let start_solution =
+        ScheduleWithInfo::new(start_schedule, SwapInfo::NoSwap, String::from("Start"));
+
+    // ACT
+    let plain_descent =
+        build_local_search_solver(network, None, None).solve(start_solution.clone());
+    let with_restarts =
+        build_local_search_solver(network_with_restarts, None, None).solve(start_solution);
+
+    // ASSERT
+    assert!(
+        plain_descent
+            .solution()
+            .get_schedule()
+            .unserved_passengers()
+            .0
+            > 0
+    );
+    assert_eq!(
+        with_restarts
+            .solution()
+            .get_schedule()
+            .unserved_passengers()
+            .0,
+        0
+    );
+    assert!(with_restarts.objective_value() < plain_descent.objective_value());
+}
+
+#[test]
+fn local_search_stops_when_stop_flag_is_set_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["localSearch"] =
+        json!({"plateauLength": 3, "perturbationStrength": 2});
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let start_solution = ScheduleWithInfo::new(
+        local_optimum_start_schedule(network.clone()),
+        SwapInfo::NoSwap,
+        String::from("Start"),
+    );
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let local_search_solver = build_local_search_solver(network, None, Some(stop_flag.clone()));
+
+    // ACT
+    stop_flag.store(true, Ordering::Relaxed); // as done by the interrupt handler
+    let result = local_search_solver.solve(start_solution.clone());
+
+    // ASSERT
+    assert!(*result.solution() == start_solution);
+    result.solution().get_schedule().verify_consistency();
+}
+
+#[test]
+fn deterministic_local_search_is_reproducible_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["localSearch"] = json!({"deterministic": true});
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    // every service trip on its own vehicle, such that many neighbors have equal objective values
+    let local_optimum = local_optimum_start_schedule(network.clone());
+    let start_schedule = local_optimum
+        .vehicles_iter_all()
+        .fold(local_optimum.clone(), |schedule, vehicle| {
+            schedule.split_tour_into_single_node_tours(vehicle).unwrap()
+        });
+    let start_solution =
+        ScheduleWithInfo::new(start_schedule, SwapInfo::NoSwap, String::from("Start"));
+
+    // ACT
+    let first_run =
+        build_local_search_solver(network.clone(), None, None).solve(start_solution.clone());
+    let second_run = build_local_search_solver(network, None, None).solve(start_solution);
+
+    // ASSERT
+    assert_eq!(first_run.objective_value(), second_run.objective_value());
+    assert!(first_run.solution().get_schedule() == second_run.solution().get_schedule());
+    assert_eq!(
+        first_run.solution().get_schedule().format_tours(),
+        second_run.solution().get_schedule().format_tours()
+    );
+}
+
+#[test]
+fn time_windows_cover_all_trips_test() {
+    // ARRANGE
+    let network = load_test_network();
+
+    // ACT
+    let windows = time_windows(network.time_horizon(), Duration::new("1:00:00"));
+
+    // ASSERT
+    assert!(windows.len() > 1);
+    for (window1, window2) in windows.iter().tuple_windows() {
+        assert_eq!(window1.1, window2.0);
+    }
+    for node in network
+        .all_service_nodes()
+        .chain(network.maintenance_nodes())
+    {
+        let start_time = network.node(node).start_time();
+        assert_eq!(
+            windows
+                .iter()
+                .filter(|(start, end)| *start <= start_time && start_time < *end)
+                .count(),
+            1
+        );
+    }
+}
+
+#[test]
+fn windowed_local_search_improves_objective_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["localSearch"] = json!({"timeWindow": "PT1H"});
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let objective = objective::build();
+    let local_optimum = local_optimum_start_schedule(network.clone());
+    let start_schedule = local_optimum
+        .vehicles_iter_all()
+        .fold(local_optimum.clone(), |schedule, vehicle| {
+            schedule.split_tour_into_single_node_tours(vehicle).unwrap()
+        });
+    let start_solution = objective.evaluate(ScheduleWithInfo::new(
+        start_schedule,
+        SwapInfo::NoSwap,
+        String::from("Start"),
+    ));
+
+    // ACT
+    let result =
+        build_local_search_solver(network, None, None).solve(start_solution.solution().clone());
+
+    // ASSERT
+    assert!(result.objective_value() < start_solution.objective_value());
+    assert!(
+        result.solution().get_schedule().number_of_vehicles()
+            < start_solution
+                .solution()
+                .get_schedule()
+                .number_of_vehicles()
+    );
+}
+
+#[test]
+fn recursion_local_search_improves_objective_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["localSearch"] = json!({"recursion": {"depth": 1, "width": 2}});
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let objective = objective::build();
+    let local_optimum = local_optimum_start_schedule(network.clone());
+    let start_solution = objective.evaluate(ScheduleWithInfo::new(
+        local_optimum,
+        SwapInfo::NoSwap,
+        String::from("Start"),
+    ));
+
+    // ACT
+    let result =
+        build_local_search_solver(network, None, None).solve(start_solution.solution().clone());
+
+    // ASSERT
+    result.solution().get_schedule().verify_consistency();
+    assert!(result.objective_value() <= start_solution.objective_value());
+}
+
+#[test]
+fn move_tally_counts_accepted_improvements_test() {
+    // ARRANGE
+    let network = load_test_network();
+    let objective = Arc::new(objective::build());
+    let local_optimum = local_optimum_start_schedule(network.clone());
+    let start_schedule = local_optimum
+        .vehicles_iter_all()
+        .fold(local_optimum.clone(), |schedule, vehicle| {
+            schedule.split_tour_into_single_node_tours(vehicle).unwrap()
+        });
+    let move_tally = Arc::new(MoveTally::default());
+    let improver = StoppableImprover {
+        minimizer: Box::new(DeterministicParallelMinimizer {
+            neighborhood: Arc::new(RSSchedParallelNeighborhood::new(
+                None,
+                None,
+                network.clone(),
+            )),
+            objective: objective.clone(),
+        }),
+        target_objective: None,
+        stop_flag: None,
+        move_tally: move_tally.clone(),
+        iterations: Arc::new(IterationCounter::new(None)),
+    };
+    let mut solution = objective.evaluate(ScheduleWithInfo::new(
+        start_schedule.clone(),
+        SwapInfo::NoSwap,
+        String::from("Start"),
+    ));
+
+    // ACT
+    let mut accepted_improvements = 0;
+    while let Some(improved_solution) = improver.improve(&solution) {
+        accepted_improvements += 1;
+        solution = improved_solution;
+    }
+    let solver = build_local_search_solver(network, None, None);
+    solver.solve(ScheduleWithInfo::new(
+        start_schedule,
+        SwapInfo::NoSwap,
+        String::from("Start"),
+    ));
+
+    // ASSERT
+    assert!(accepted_improvements > 0);
+    assert_eq!(move_tally.total(), accepted_improvements);
+    assert_eq!(
+        move_tally.counts().values().sum::<u32>(),
+        accepted_improvements
+    );
+    assert!(!move_tally.counts().contains_key("noSwap"));
+    assert!(solver.move_tally().total() > 0);
+    assert_eq!(
+        solver.move_tally().to_json()["improvingMoves"]
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|count| count.as_u64().unwrap())
+            .sum::<u64>(),
+        solver.move_tally().total() as u64
+    );
+}
+
+#[test]
+fn zero_weight_operator_is_never_applied_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["localSearch"] = json!({"operatorWeights": {"pathExchange": 0, "removeSingleNode": 2}, "adaptationRate": 0.5});
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let local_optimum = local_optimum_start_schedule(network.clone());
+    let start_schedule = local_optimum
+        .vehicles_iter_all()
+        .fold(local_optimum.clone(), |schedule, vehicle| {
+            schedule.split_tour_into_single_node_tours(vehicle).unwrap()
+        });
+    let start_solution =
+        ScheduleWithInfo::new(start_schedule, SwapInfo::NoSwap, String::from("Start"));
+    let neighborhood = RSSchedParallelNeighborhood::new(None, None, network.clone());
+
+    // ACT
+    let neighbors: Vec<ScheduleWithInfo> = neighborhood
+        .exhaustive()
+        .neighbors_of(&start_solution)
+        .collect();
+    let solver = build_local_search_solver(network, None, None);
+    solver.solve(start_solution);
+
+    // ASSERT
+    assert!(!neighbors.is_empty());
+    assert!(neighbors
+        .iter()
+        .all(|neighbor| !matches!(neighbor.get_last_swap_info(), SwapInfo::PathExchange(_))));
+    assert!(solver.move_tally().total() > 0);
+    assert!(!solver.move_tally().counts().contains_key("pathExchange"));
+}
+
+#[test]
+fn adaptive_operator_weights_test() {
+    // ARRANGE
+    let selection = OperatorSelection::new(
+        OperatorWeights {
+            path_exchange: 0.0,
+            ..OperatorWeights::default()
+        },
+        0.5,
+    );
+
+    // ACT
+    for _ in 0..10 {
+        selection.reward(SwapInfo::RemoveSingleNode(VehicleIdx::vehicle_from(0)));
+    }
+
+    // ASSERT
+    assert!(!selection.is_exhaustive());
+    assert_eq!(selection.share(Operator::RemoveSingleNode), 1.0);
+    assert_eq!(selection.share(Operator::PathExchange), 0.0);
+    let hitch_hiking_share = selection.share(Operator::AddTripForHitchHiking);
+    assert!((0.1..0.2).contains(&hitch_hiking_share));
+    assert_eq!(sample(vec![0, 1, 2, 3, 4], 0.4, 0), vec![0, 1]);
+    assert_eq!(sample(vec![0, 1, 2, 3, 4], 0.4, 1), vec![2, 3]);
+    assert_eq!(sample(vec![0, 1, 2, 3, 4], 0.0, 1), Vec::<i32>::new());
+    assert!(OperatorSelection::new(OperatorWeights::default(), 0.0).is_exhaustive());
+}
+
+#[test]
+fn local_search_stops_at_max_iterations_test() {
+    // ARRANGE
+    let mut input_data = load_test_input();
+    input_data["parameters"]["localSearch"] =
+        json!({"maxIterations": 2, "plateauLength": 5, "perturbationStrength": 2});
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let objective = objective::build();
+    let local_optimum = local_optimum_start_schedule(network.clone());
+    let start_schedule = local_optimum
+        .vehicles_iter_all()
+        .fold(local_optimum.clone(), |schedule, vehicle| {
+            schedule.split_tour_into_single_node_tours(vehicle).unwrap()
+        });
+    let start_solution = objective.evaluate(ScheduleWithInfo::new(
+        start_schedule,
+        SwapInfo::NoSwap,
+        String::from("Start"),
+    ));
+    let uncapped_solver = build_local_search_solver(load_test_network(), None, None);
+    let solver = build_local_search_solver(network, None, None);
+
+    // ACT
+    uncapped_solver.solve(start_solution.solution().clone());
+    let result = solver.solve(start_solution.solution().clone());
+
+    // ASSERT
+    assert!(uncapped_solver.iterations() > 2);
+    assert_eq!(solver.iterations(), 2);
+    assert_eq!(solver.move_tally().total(), 2);
+    result.solution().get_schedule().verify_consistency();
+    assert!(result.objective_value() < start_solution.objective_value());
+}
+
+/// Positions 0 to 3 on a line, each position is a neighbor of the adjacent positions.
+struct LineNeighborhood;
+
+impl ParallelNeighborhood<i64> for LineNeighborhood {
+    fn neighbors_of<'a>(
+        &'a self,
+        current_solution: &'a i64,
+    ) -> impl ParallelIterator<Item = i64> + 'a {
+        vec![current_solution - 1, current_solution + 1]
+            .into_par_iter()
+            .filter(|position| (0..=3).contains(position))
+    }
+}
+
+/// Costs of the positions: the ridge 0 -> 1 -> 2 leads to the optimum at position 3, but its first
+/// step is slightly worse.
+struct RidgeIndicator;
+
+impl Indicator<i64> for RidgeIndicator {
+    fn evaluate(&self, position: &i64) -> BaseValue {
+        BaseValue::Integer([10, 11, 9, 5][*position as usize])
+    }
+
+    fn name(&self) -> String {
+        String::from("ridge")
+    }
+}
+
+#[test]
+fn threshold_accepting_traverses_ridge_test() {
+    // ARRANGE
+    let neighborhood = Arc::new(LineNeighborhood);
+    let objective = Arc::new(Objective::new(vec![LinearCombination::new(vec![(
+        Coefficient::Integer(1),
+        Box::new(RidgeIndicator) as Box<dyn Indicator<i64>>,
+    )])]));
+    let minimizer_solver =
+        ParallelLocalSearchSolver::initialize(neighborhood.clone(), objective.clone());
+    let threshold_accepting_solver = ParallelLocalSearchSolver::with_options(
+        neighborhood.clone(),
+        objective.clone(),
+        Some(Box::new(ThresholdAccepting::new(
+            neighborhood,
+            objective,
+            Arc::new(ThresholdState::new(ObjectiveValue::new(vec![
+                BaseValue::Integer(5),
+            ]))),
+            0.5,
+            RoundingPolicy::default(),
+        ))),
+        None,
+        None,
+        None,
+    );
+
+    // ACT
+    let minimizer_solution = minimizer_solver.solve(0);
+    let threshold_accepting_solution = threshold_accepting_solver.solve(0);
+
+    // ASSERT
+    assert_eq!(*minimizer_solution.solution(), 0);
+    assert_eq!(*threshold_accepting_solution.solution(), 3);
+    assert_eq!(
+        *threshold_accepting_solution.objective_value(),
+        ObjectiveValue::new(vec![BaseValue::Integer(5)])
+    );
+}
+
+#[test]
+fn threshold_accepting_restarts_with_initial_threshold_test() {
+    // ARRANGE
+    let neighborhood = Arc::new(LineNeighborhood);
+    let objective = Arc::new(Objective::new(vec![LinearCombination::new(vec![(
+        Coefficient::Integer(1),
+        Box::new(RidgeIndicator) as Box<dyn Indicator<i64>>,
+    )])]));
+    let threshold_state = Arc::new(ThresholdState::new(ObjectiveValue::new(vec![
+        BaseValue::Integer(5),
+    ])));
+    let solver = ParallelLocalSearchSolver::with_options(
+        neighborhood.clone(),
+        objective.clone(),
+        Some(Box::new(ThresholdAccepting::new(
+            neighborhood,
+            objective,
+            threshold_state.clone(),
+            0.5,
+            RoundingPolicy::default(),
+        ))),
+        None,
+        None,
+        None,
+    );
+
+    // ACT
+    solver.solve(0);
+    let without_reset = solver.solve(0);
+    threshold_state.reset();
+    let with_reset = solver.solve(0);
+
+    // ASSERT
+    assert_eq!(*without_reset.solution(), 0);
+    assert_eq!(*with_reset.solution(), 3);
+}
+
+/// Costs of the positions: position 1 is slightly worse than position 0, position 2 is far worse.
+struct ValleyIndicator;
+
+impl Indicator<i64> for ValleyIndicator {
+    fn evaluate(&self, position: &i64) -> BaseValue {
+        BaseValue::Integer([5, 6, 20, 20][*position as usize])
+    }
+
+    fn name(&self) -> String {
+        String::from("valley")
+    }
+}
+
+#[test]
+fn threshold_accepting_does_not_alternate_test() {
+    // ARRANGE
+    let objective = Arc::new(Objective::new(vec![LinearCombination::new(vec![(
+        Coefficient::Integer(1),
+        Box::new(ValleyIndicator) as Box<dyn Indicator<i64>>,
+    )])]));
+    let threshold_accepting = ThresholdAccepting::new(
+        Arc::new(LineNeighborhood),
+        objective.clone(),
+        Arc::new(ThresholdState::new(ObjectiveValue::new(vec![
+            BaseValue::Integer(8),
+        ]))),
+        0.9,
+        RoundingPolicy::Truncate,
+    );
+
+    // ACT
+    let mut visited = Vec::new();
+    let mut solution = objective.evaluate(0);
+    while let Some(next_solution) = threshold_accepting.improve(&solution) {
+        visited.push(*next_solution.solution());
+        solution = next_solution;
+        if visited.len() > 10 {
+            break;
+        }
+    }
+
+    // ASSERT
+    // 0 -> 1 is accepted, going back from 1 to 0 is only allowed as the jump to the best solution
+    // seen, and from 0 only the excluded 1 is left
+    assert_eq!(visited, vec![1, 0]);
+}
+
+#[test]
+fn take_any_parallel_recursion_matches_take_any_recursion_test() {
+    // ARRANGE
+    let neighborhood = Arc::new(LineNeighborhood);
+    let objective = Arc::new(Objective::new(vec![LinearCombination::new(vec![(
+        Coefficient::Integer(1),
+        Box::new(RidgeIndicator) as Box<dyn Indicator<i64>>,
+    )])]));
+    let improvers = |recursion_depth: u8| {
+        (
+            TakeAnyRecursion::new(recursion_depth, 1, neighborhood.clone(), objective.clone()),
+            TakeAnyParallelRecursion::new(
+                recursion_depth,
+                1,
+                neighborhood.clone(),
+                objective.clone(),
+            ),
+        )
+    };
+
+    for recursion_depth in 0..3 {
+        let (take_any_recursion, take_any_parallel_recursion) = improvers(recursion_depth);
+        // at position 1 both neighbors improve, so any of them might be taken
+        for position in [0, 2, 3] {
+            let solution = objective.evaluate(position);
+
+            // ACT
+            let expected = take_any_recursion.improve(&solution);
+            let result = take_any_parallel_recursion.improve(&solution);
+
+            // ASSERT
+            assert_eq!(
+                result.as_ref().map(|neighbor| *neighbor.solution()),
+                expected.as_ref().map(|neighbor| *neighbor.solution()),
+                "position {} with recursion depth {}",
+                position,
+                recursion_depth
+            );
+        }
+    }
+    // the ridge is only crossed with recursion (0 -> 1 -> 2)
+    let (_, without_recursion) = improvers(0);
+    let (_, with_recursion) = improvers(1);
+    assert!(without_recursion.improve(&objective.evaluate(0)).is_none());
+    assert_eq!(
+        *with_recursion
+            .improve(&objective.evaluate(0))
+            .unwrap()
+            .solution(),
+        2
+    );
+}
+
+/// From each position p the positions p + 1 to p + 100000 are neighbors (all worse than p).
+struct WideNeighborhood;
+
+impl ParallelNeighborhood<i64> for WideNeighborhood {
+    fn neighbors_of<'a>(
+        &'a self,
+        current_solution: &'a i64,
+    ) -> impl ParallelIterator<Item = i64> + 'a {
+        (1..=100000)
+            .into_par_iter()
+            .map(move |step| current_solution + step)
+    }
+}
+
+struct PositionIndicator;
+
+impl Indicator<i64> for PositionIndicator {
+    fn evaluate(&self, position: &i64) -> BaseValue {
+        BaseValue::Integer(*position)
+    }
+
+    fn name(&self) -> String {
+        String::from("position")
+    }
+}
+
+/// Benchmark: without any improving neighbor, the whole neighborhood is collected for the
+/// recursion. The TakeAnyRecursion locks a shared vector for each neighbor, the
+/// TakeAnyParallelRecursion merges thread-local candidates.
+/// Run with `cargo test --release -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn take_any_parallel_recursion_lock_overhead_benchmark() {
+    // ARRANGE
+    let neighborhood = Arc::new(WideNeighborhood);
+    let objective = Arc::new(Objective::new(vec![LinearCombination::new(vec![(
+        Coefficient::Integer(1),
+        Box::new(PositionIndicator) as Box<dyn Indicator<i64>>,
+    )])]));
+    let take_any_recursion = TakeAnyRecursion::new(1, 4, neighborhood.clone(), objective.clone());
+    let take_any_parallel_recursion =
+        TakeAnyParallelRecursion::new(1, 4, neighborhood, objective.clone());
+    let solution = objective.evaluate(0);
+
+    // ACT
+    let start = Instant::now();
+    let expected = take_any_recursion.improve(&solution);
+    let with_lock = start.elapsed();
+    let start = Instant::now();
+    let result = take_any_parallel_recursion.improve(&solution);
+    let lock_free = start.elapsed();
+    println!(
+        "TakeAnyRecursion: {:0.3}sec, TakeAnyParallelRecursion: {:0.3}sec",
+        with_lock.as_secs_f32(),
+        lock_free.as_secs_f32()
+    );
+
+    // ASSERT
+    assert!(expected.is_none());
+    assert!(result.is_none());
+    assert!(lock_free < with_lock);
+}
+
+#[test]
+fn recursion_candidates_stay_within_budget_test() {
+    // ARRANGE
+    let objective = Objective::new(vec![LinearCombination::new(vec![(
+        Coefficient::Integer(1),
+        Box::new(PositionIndicator) as Box<dyn Indicator<i64>>,
+    )])]);
+    let objective_to_beat = ObjectiveValue::new(vec![BaseValue::Integer(0)]);
+    let budget = 3;
+    let positions = [7, 3, 9, 3, 1, 8, 2, 6, 5, 4];
+
+    // ACT
+    let mut first = RecursionCandidates::new(budget);
+    let mut second = RecursionCandidates::new(budget);
+    for (i, position) in positions.iter().enumerate() {
+        let neighbor = objective.evaluate(*position);
+        if i % 2 == 0 {
+            first = first.insert(neighbor, &objective_to_beat);
+            assert!(first.best.len() <= budget);
+        } else {
+            second = second.insert(neighbor, &objective_to_beat);
+            assert!(second.best.len() <= budget);
+        }
+    }
+    let merged = first.merge(second);
+    let wider = RecursionCandidates::new(10)
+        .insert(objective.evaluate(0), &objective_to_beat)
+        .insert(objective.evaluate(11), &objective_to_beat)
+        .merge(merged)
+        .with_limit(2);
+
+    // ASSERT
+    assert!(wider.improving.is_none());
+    assert_eq!(
+        wider
+            .best
+            .iter()
+            .map(|candidate| *candidate.solution())
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+}
+
+#[test]
+fn take_any_parallel_recursion_with_candidate_budget_test() {
+    // ARRANGE
+    let neighborhood = Arc::new(LineNeighborhood);
+    let objective = Arc::new(Objective::new(vec![LinearCombination::new(vec![(
+        Coefficient::Integer(1),
+        Box::new(RidgeIndicator) as Box<dyn Indicator<i64>>,
+    )])]));
+    let improver = TakeAnyParallelRecursion::new(2, 2, neighborhood.clone(), objective.clone())
+        .with_candidate_budget(Some(1));
+    let unbounded_improver = TakeAnyParallelRecursion::new(2, 2, neighborhood, objective.clone());
+
+    // ACT
+    let result = improver.improve(&objective.evaluate(0));
+    let unbounded_result = unbounded_improver.improve(&objective.evaluate(0));
+
+    // ASSERT
+    // the ridge only needs the single best candidate per level
+    assert_eq!(*result.unwrap().solution(), 2);
+    assert_eq!(*unbounded_result.unwrap().solution(), 2);
+}
@@ -0,0 +1,83 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(test)]
+mod tests;
+
+use model::base_types::{NodeIdx, VehicleIdx, VehicleTypeIdx};
+use rapid_solve::objective::{EvaluatedSolution, Objective, ObjectiveValue};
+use solution::{path::Path, Schedule};
+
+use crate::local_search::{neighborhood::swaps::SwapInfo, ScheduleWithInfo};
+
+/// How a service trip is covered in the best candidate of [`marginal_cost_to_cover`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverMethod {
+    /// The trip is inserted into the tour of an existing vehicle (without removing other nodes).
+    Insert(VehicleIdx),
+    /// A new vehicle of the given type is spawned to cover only this trip.
+    Spawn(VehicleTypeIdx),
+}
+
+/// Estimates the objective value that is reached if the given service trip is covered by one
+/// additional vehicle. Two kinds of candidates are evaluated: inserting the trip into the tour of
+/// any existing vehicle that is not yet part of the trip's train formation (only if no other
+/// node has to be removed), and spawning a new vehicle of any compatible type.
+/// The best objective value is returned together with the method achieving it. On ties,
+/// inserting is preferred.
+/// # Errors
+/// If the node is not a service trip or no candidate is feasible, an error is returned.
+pub fn marginal_cost_to_cover(
+    schedule: &Schedule,
+    trip: NodeIdx,
+    objective: &Objective<ScheduleWithInfo>,
+) -> Result<(ObjectiveValue, CoverMethod), String> {
+    let network = schedule.get_network();
+    if !network.node(trip).is_service() {
+        return Err(format!("Node {} is not a service trip.", trip));
+    }
+    let formation = schedule.train_formation_of(trip).ids();
+    let vehicle_types = schedule.get_vehicle_types();
+
+    let insert_candidates = schedule
+        .vehicles_iter_all()
+        .filter(|&vehicle| !formation.contains(&vehicle))
+        .filter_map(|vehicle| {
+            let path = Path::new_from_single_node(trip, network.clone());
+            match schedule.add_path_to_vehicle_tour(vehicle, path) {
+                Ok((new_schedule, None)) => Some((new_schedule, CoverMethod::Insert(vehicle))),
+                _ => None,
+            }
+        });
+
+    let spawn_candidates = vehicle_types.iter().filter_map(|vehicle_type| {
+        schedule
+            .spawn_vehicle_for_path(vehicle_type, vec![trip])
+            .ok()
+            .map(|(new_schedule, _)| (new_schedule, CoverMethod::Spawn(vehicle_type)))
+    });
+
+    insert_candidates
+        .chain(spawn_candidates)
+        .map(|(new_schedule, method)| {
+            let evaluated: EvaluatedSolution<ScheduleWithInfo> =
+                objective.evaluate(ScheduleWithInfo::new(
+                    new_schedule,
+                    SwapInfo::NoSwap,
+                    format!("Covered {} by {:?}", trip, method),
+                ));
+            (evaluated.objective_value().clone(), method)
+        })
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .ok_or_else(|| format!("No vehicle can cover service trip {}.", trip))
+}
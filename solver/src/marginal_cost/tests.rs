@@ -0,0 +1,90 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fs::File, io::Read};
+
+use model::{
+    base_types::NodeIdx, json_serialisation::load_rolling_stock_problem_instance_from_json,
+};
+use solution::{path::Path, Schedule};
+
+use crate::{
+    local_search::{neighborhood::swaps::SwapInfo, ScheduleWithInfo},
+    objective,
+};
+
+use super::{marginal_cost_to_cover, CoverMethod};
+
+#[test]
+fn inserting_is_cheaper_than_spawning_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let network =
+        load_rolling_stock_problem_instance_from_json(serde_json::from_str(&input_data).unwrap());
+    let trip = NodeIdx::service_from;
+    let vehicle_type = network.vehicle_type_for(trip(12));
+    let (schedule, vehicle) = Schedule::empty(network.clone())
+        .spawn_vehicle_for_path(vehicle_type, vec![trip(12), trip(13)])
+        .unwrap();
+    let objective = objective::build();
+    let evaluate = |schedule: Schedule| {
+        objective
+            .evaluate(ScheduleWithInfo::new(
+                schedule,
+                SwapInfo::NoSwap,
+                String::new(),
+            ))
+            .objective_value()
+            .clone()
+    };
+    let inserted_value = evaluate(
+        schedule
+            .add_path_to_vehicle_tour(vehicle, Path::new_from_single_node(trip(14), network))
+            .unwrap()
+            .0,
+    );
+    let spawned_value = evaluate(
+        schedule
+            .spawn_vehicle_for_path(vehicle_type, vec![trip(14)])
+            .unwrap()
+            .0,
+    );
+
+    // ACT
+    let (value, method) = marginal_cost_to_cover(&schedule, trip(14), &objective).unwrap();
+
+    // ASSERT
+    assert!(inserted_value < spawned_value);
+    assert_eq!(method, CoverMethod::Insert(vehicle));
+    assert_eq!(value, inserted_value);
+    assert!(value < evaluate(schedule.clone()));
+}
+
+#[test]
+fn marginal_cost_of_non_service_node_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let network =
+        load_rolling_stock_problem_instance_from_json(serde_json::from_str(&input_data).unwrap());
+    let depot = network.start_depot_nodes().next().unwrap();
+
+    // ACT
+    let result = marginal_cost_to_cover(&Schedule::empty(network), depot, &objective::build());
+
+    // ASSERT
+    assert!(result.is_err());
+}
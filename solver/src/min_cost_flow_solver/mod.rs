@@ -11,6 +11,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+#[cfg(test)]
+mod tests;
+
 use model::base_types::DepotIdx;
 use model::base_types::Distance;
 use model::base_types::NodeIdx;
@@ -35,7 +38,7 @@ use rs_graph::LinkedListGraph;
 use std::collections::HashMap;
 use std::io;
 use std::io::Write;
-use std::iter::repeat;
+use std::iter::repeat_n;
 use std::sync::Arc;
 use std::time;
 
@@ -57,18 +60,74 @@ struct EdgeLabel {
     cost: Cost,
 }
 
+/// Weight of the edge between two nodes (predecessor, successor) in the flow network, i.e., the
+/// cost of a vehicle operating the successor right after the predecessor. One of them might be a
+/// depot. The spawning costs dominate these weights, so the solver first minimizes the number of
+/// vehicles and then the total weight among all schedules with this number of vehicles.
+pub type ConnectionCost = Arc<dyn Fn(&Network, NodeIdx, NodeIdx) -> Cost + Send + Sync>;
+
+/// Default weights: dead head time and idle time (not at depots) weighted by the costs of the
+/// config. (Unreachable dead head trips count as the whole planning period.)
+pub fn dead_head_time_connection_cost() -> ConnectionCost {
+    Arc::new(|network: &Network, pred: NodeIdx, succ: NodeIdx| {
+        let config = network.config();
+        let idle_time_cost = if network.node(pred).is_depot() || network.node(succ).is_depot() {
+            0
+        } else {
+            network.idle_time_between(pred, succ).in_sec().unwrap() as Cost
+                * config.costs.idle as Cost
+        };
+
+        network
+            .dead_head_time_between(pred, succ)
+            .in_sec()
+            .unwrap_or(network.planning_days().in_sec().unwrap()) as Cost
+            * config.costs.dead_head_trip as Cost
+            + idle_time_cost
+    })
+}
+
+/// Weights by the dead head distance in meters (e.g., to reduce empty running). Unreachable dead
+/// head trips (e.g., from the overflow depot) count as more than the longest dead head trip of
+/// the given network.
+pub fn dead_head_distance_connection_cost(network: &Network) -> ConnectionCost {
+    let locations = network.locations();
+    let unreachable_cost = locations
+        .iter()
+        .flat_map(|a| locations.iter().map(move |b| (a, b)))
+        .filter_map(|(a, b)| locations.distance(a, b).in_meter().ok())
+        .max()
+        .unwrap_or(0) as Cost
+        + 1;
+    Arc::new(move |network: &Network, pred: NodeIdx, succ: NodeIdx| {
+        network
+            .dead_head_distance_between(pred, succ)
+            .in_meter()
+            .map_or(unreachable_cost, |meter| meter as Cost)
+    })
+}
+
 pub struct MinCostFlowSolver {
     vehicle_types: Arc<VehicleTypes>,
     config: Arc<Config>,
     network: Arc<Network>,
+    connection_cost: ConnectionCost,
 }
 
 impl MinCostFlowSolver {
     pub fn initialize(network: Arc<Network>) -> Self {
+        Self::initialize_with_connection_cost(network, dead_head_time_connection_cost())
+    }
+
+    pub fn initialize_with_connection_cost(
+        network: Arc<Network>,
+        connection_cost: ConnectionCost,
+    ) -> Self {
         Self {
             vehicle_types: network.vehicle_types(),
             config: network.config(),
             network,
+            connection_cost,
         }
     }
 
@@ -93,7 +152,7 @@ impl MinCostFlowSolver {
             );
         }
 
-        Schedule::from_tours(tours, self.network.clone()).unwrap()
+        Schedule::from_tours(tours, self.network.clone()).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
@@ -196,6 +255,7 @@ impl MinCostFlowSolver {
         let mut total_lower_bound: LowerBound = 0;
         let mut cost_overflow_checker: Cost = 0; // computes the maximal cost for the worst
                                                  // feasible flow
+        let mut max_connection_cost: Cost = 0;
 
         let maximal_formation_count_for_vehicle_type = self
             .vehicle_types
@@ -305,26 +365,8 @@ impl MinCostFlowSolver {
                 };
                 let pred_right_rsnode = node_to_rsnode[&pred_node].1;
 
-                let idle_time_cost = if self.network.node(pred).is_depot()
-                    || self.network.node(node_id).is_depot()
-                {
-                    0
-                } else {
-                    self.network
-                        .idle_time_between(pred, node_id)
-                        .in_sec()
-                        .unwrap() as Cost
-                        * self.config.costs.idle as Cost
-                };
-
-                let cost: Cost = self
-                    .network
-                    .dead_head_time_between(pred, node_id)
-                    .in_sec()
-                    .unwrap_or(self.network.planning_days().in_sec().unwrap())
-                    as Cost
-                    * self.config.costs.dead_head_trip as Cost
-                    + idle_time_cost;
+                let cost: Cost = (self.connection_cost)(&self.network, pred, node_id);
+                max_connection_cost = max_connection_cost.max(cost);
 
                 cost_overflow_checker = cost_overflow_checker
                     .checked_add(
@@ -377,11 +419,19 @@ impl MinCostFlowSolver {
         // - Service trip duration <= planning days
         // - Dead head trip to the depot <= planning days
         // Hence, each vehicle costs at most costliest activity * 3 * planning days.
-        let spawning_cost = (max_cost_per_sec as Cost)
-            .checked_mul(3)
-            .unwrap()
+        // For other connection costs, the two dead head trips are bounded by the maximal
+        // connection cost instead.
+        let activity_cost = (max_cost_per_sec as Cost)
             .checked_mul(self.network.planning_days().in_sec().unwrap() as Cost)
+            .unwrap();
+        let spawning_cost = activity_cost
+            .checked_mul(3)
             .unwrap()
+            .max(
+                activity_cost
+                    .checked_add(max_connection_cost.checked_mul(2).unwrap())
+                    .unwrap(),
+            )
             .checked_mul(total_lower_bound)
             .unwrap();
 
@@ -463,10 +513,10 @@ impl MinCostFlowSolver {
                         None
                     } else {
                         // take rs_node flow-value often and turn into a node_id
-                        Some(
-                            repeat(right_rsnode_to_node[&n])
-                                .take(flow[graph.edge_id(e)].1 as usize),
-                        )
+                        Some(repeat_n(
+                            right_rsnode_to_node[&n],
+                            flow[graph.edge_id(e)].1 as usize,
+                        ))
                     }
                 })
                 .flatten()
@@ -0,0 +1,54 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::{fs::File, io::Read, sync::Arc};
+
+use model::{
+    base_types::Distance, json_serialisation::load_rolling_stock_problem_instance_from_json,
+};
+
+use super::{dead_head_distance_connection_cost, MinCostFlowSolver};
+
+#[test]
+fn dead_head_distance_connection_cost_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+
+    // ACT
+    let unweighted_schedule =
+        MinCostFlowSolver::initialize_with_connection_cost(network.clone(), Arc::new(|_, _, _| 0))
+            .solve();
+    let weighted_schedule = MinCostFlowSolver::initialize_with_connection_cost(
+        network.clone(),
+        dead_head_distance_connection_cost(&network),
+    )
+    .solve();
+
+    // ASSERT
+    assert_eq!(
+        weighted_schedule.number_of_vehicles(),
+        unweighted_schedule.number_of_vehicles()
+    );
+    assert!(
+        weighted_schedule.total_dead_head_distance()
+            < unweighted_schedule.total_dead_head_distance()
+    );
+    assert_eq!(
+        weighted_schedule.total_dead_head_distance(),
+        Distance::from_meter(96000)
+    );
+    weighted_schedule.verify_consistency();
+}
@@ -11,8 +11,22 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod composition;
+#[cfg(test)]
+mod tests;
+
+use crate::local_search::neighborhood::swaps::SwapInfo;
 use crate::local_search::ScheduleWithInfo;
-use rapid_solve::objective::{BaseValue, Coefficient, Indicator, LinearCombination, Objective};
+use itertools::Itertools;
+use model::base_types::{Cost, Distance, NodeIdx, RoundingPolicy, VehicleCount};
+use model::config::{ReferenceAssignment, RotationClosure};
+use model::network::Network;
+use rapid_solve::objective::{
+    BaseValue, Coefficient, Indicator, LinearCombination, Objective, ObjectiveValue,
+};
+use rapid_time::{DateTime, Duration};
+use serde_json::json;
+use solution::Schedule;
 
 /// Sum over all service trips: max{0, passengers - capacity} + max{0, seated_passengers - seats}
 struct UnservedPassengersIndicator;
@@ -28,6 +42,19 @@ impl Indicator<ScheduleWithInfo> for UnservedPassengersIndicator {
     }
 }
 
+/// Number of service trips operated by fewer vehicles than their required minimum
+struct MinVehiclesViolationIndicator;
+
+impl Indicator<ScheduleWithInfo> for MinVehiclesViolationIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        BaseValue::Integer(schedule_with_info.get_schedule().min_vehicles_violation() as i64)
+    }
+
+    fn name(&self) -> String {
+        String::from("minVehiclesViolation")
+    }
+}
+
 /// Each fleet is partitioned into rotation cycles, if total length exceeds the maintenance limit,
 /// the excess is counted as violation
 struct MaintenanceViolationIndicator;
@@ -42,6 +69,24 @@ impl Indicator<ScheduleWithInfo> for MaintenanceViolationIndicator {
     }
 }
 
+/// Number of vehicles that do not visit any maintenance slot although a daily maintenance visit
+/// is mandatory
+struct MissingMandatoryMaintenanceIndicator;
+
+impl Indicator<ScheduleWithInfo> for MissingMandatoryMaintenanceIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        BaseValue::Integer(
+            schedule_with_info
+                .get_schedule()
+                .missing_mandatory_maintenance() as i64,
+        )
+    }
+
+    fn name(&self) -> String {
+        String::from("missingMandatoryMaintenance")
+    }
+}
+
 /// Number of vehicles (each type count as 1)
 struct VehicleCountIndicator;
 
@@ -55,11 +100,99 @@ impl Indicator<ScheduleWithInfo> for VehicleCountIndicator {
     }
 }
 
+/// Total dead head distance of all real vehicles in meters.
+pub struct DeadHeadDistanceIndicator;
+
+impl Indicator<ScheduleWithInfo> for DeadHeadDistanceIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        distance_in_meter(schedule_with_info.get_schedule().total_dead_head_distance())
+    }
+
+    fn name(&self) -> String {
+        String::from("deadHeadDistance")
+    }
+}
+
+/// Total distance (service and dead head) of all real vehicles in meters.
+pub struct TotalDistanceIndicator;
+
+impl Indicator<ScheduleWithInfo> for TotalDistanceIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        distance_in_meter(schedule_with_info.get_schedule().total_distance())
+    }
+
+    fn name(&self) -> String {
+        String::from("totalDistance")
+    }
+}
+
+/// Summed distance in meters the real vehicles travel beyond their maximal distance between
+/// maintenance within their own tour (see Schedule::total_maintenance_distance_violation).
+pub struct MaintenanceDistanceViolationIndicator;
+
+impl Indicator<ScheduleWithInfo> for MaintenanceDistanceViolationIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        distance_in_meter(
+            schedule_with_info
+                .get_schedule()
+                .total_maintenance_distance_violation(),
+        )
+    }
+
+    fn name(&self) -> String {
+        String::from("maintenanceDistanceViolation")
+    }
+}
+
+/// Number of distinct vehicle types among the real vehicles. Minimizing it favours a
+/// homogeneous fleet (e.g., to simplify maintenance).
+pub struct DistinctVehicleTypesIndicator;
+
+impl Indicator<ScheduleWithInfo> for DistinctVehicleTypesIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        let schedule = schedule_with_info.get_schedule();
+        BaseValue::Integer(
+            schedule
+                .vehicles_iter_all()
+                .map(|vehicle| schedule.vehicle_type_of(vehicle).unwrap())
+                .unique()
+                .count() as i64,
+        )
+    }
+
+    fn name(&self) -> String {
+        String::from("distinctVehicleTypes")
+    }
+}
+
+/// Spread (maximal minus minimal) of the service duration of the real vehicles. Minimizing it
+/// balances the workload, i.e., avoids overworking some vehicles while others idle.
+pub struct WorkloadBalanceIndicator;
+
+impl Indicator<ScheduleWithInfo> for WorkloadBalanceIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        let schedule = schedule_with_info.get_schedule();
+        match schedule
+            .vehicles_iter_all()
+            .map(|vehicle| schedule.tour_of(vehicle).unwrap().service_duration())
+            .minmax()
+            .into_option()
+        {
+            Some((min, max)) => BaseValue::Duration(max - min),
+            None => BaseValue::Duration(Duration::ZERO),
+        }
+    }
+
+    fn name(&self) -> String {
+        String::from("workloadBalance")
+    }
+}
+
 struct CostsIndicator;
 
 impl Indicator<ScheduleWithInfo> for CostsIndicator {
     fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
-        BaseValue::Integer(schedule_with_info.get_schedule().costs() as i64)
+        saturating_integer(schedule_with_info.get_schedule().costs())
     }
 
     fn name(&self) -> String {
@@ -67,28 +200,390 @@ impl Indicator<ScheduleWithInfo> for CostsIndicator {
     }
 }
 
+/// Number of vehicles not starting and ending at the same home depot weighted by the configured
+/// non-home-spawn costs.
+struct NonHomeSpawnPenaltyIndicator;
+
+impl Indicator<ScheduleWithInfo> for NonHomeSpawnPenaltyIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        let schedule = schedule_with_info.get_schedule();
+        saturating_integer(
+            (schedule.non_home_spawn_penalty() as Cost)
+                .saturating_mul(schedule.get_network().config().costs.non_home_spawn),
+        )
+    }
+
+    fn name(&self) -> String {
+        String::from("nonHomeSpawnPenalty")
+    }
+}
+
+/// Number of vehicles not returning to their start depot weighted by the penalty of the soft
+/// rotation closure. Zero for open or strictly closed rotations.
+struct ClosedRotationPenaltyIndicator;
+
+impl Indicator<ScheduleWithInfo> for ClosedRotationPenaltyIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        let schedule = schedule_with_info.get_schedule();
+        match schedule.get_network().config().rotation_closure {
+            RotationClosure::Soft(penalty) => saturating_integer(
+                (schedule.closed_rotation_violation() as Cost).saturating_mul(penalty),
+            ),
+            RotationClosure::Open | RotationClosure::Strict => BaseValue::Integer(0),
+        }
+    }
+
+    fn name(&self) -> String {
+        String::from("closedRotationPenalty")
+    }
+}
+
+/// Number of preferred successor hints that are not honored. Only used as a tie-breaker between
+/// otherwise equal schedules.
+struct UnhonoredPreferredSuccessorsIndicator;
+
+impl Indicator<ScheduleWithInfo> for UnhonoredPreferredSuccessorsIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        BaseValue::Integer(
+            schedule_with_info
+                .get_schedule()
+                .unhonored_preferred_successors() as i64,
+        )
+    }
+
+    fn name(&self) -> String {
+        String::from("unhonoredPreferredSuccessors")
+    }
+}
+
+/// Number of service trips that are assigned to other vehicles than in the reference assignment
+/// (e.g., the previous plan of a rolling re-optimization).
+struct AssignmentChangesIndicator {
+    reference: ReferenceAssignment,
+}
+
+impl Indicator<ScheduleWithInfo> for AssignmentChangesIndicator {
+    fn evaluate(&self, schedule_with_info: &ScheduleWithInfo) -> BaseValue {
+        BaseValue::Integer(
+            schedule_with_info
+                .get_schedule()
+                .assignment_distance_to(&self.reference) as i64,
+        )
+    }
+
+    fn name(&self) -> String {
+        String::from("assignmentChanges")
+    }
+}
+
+/// Converts an unsigned value (e.g., costs) into an integer base value. Values that do not fit
+/// into an i64 saturate at i64::MAX instead of wrapping around to a negative value.
+fn saturating_integer(value: u64) -> BaseValue {
+    BaseValue::Integer(i64::try_from(value).unwrap_or(i64::MAX))
+}
+
+/// Infinite distances are Maximum.
+fn distance_in_meter(distance: Distance) -> BaseValue {
+    match distance.in_meter() {
+        Ok(meter) => saturating_integer(meter),
+        Err(_) => BaseValue::Maximum,
+    }
+}
+
+/// Objective value of a schedule together with the named values of its indicators, e.g., for
+/// reports. The costs do not include the non-home-spawn and closed rotation penalties (which are
+/// part of the same objective level). Levels that depend on a reference assignment only appear in
+/// objective_value.
+#[derive(Debug, Clone)]
+pub struct ObjectiveBreakdown {
+    pub objective_value: ObjectiveValue,
+    pub unserved_passengers: i64,
+    pub min_vehicles_violation: i64,
+    pub missing_mandatory_maintenance: i64,
+    pub maintenance_violation: i64,
+    pub vehicle_count: i64,
+    pub costs: i64,
+    pub non_home_spawn_penalty: i64,
+    pub closed_rotation_penalty: i64,
+    pub unhonored_preferred_successors: i64,
+}
+
+/// Evaluates the schedule with the given objective and breaks the value down into the named
+/// indicators.
+pub fn objective_breakdown(
+    schedule: &Schedule,
+    objective: &Objective<ScheduleWithInfo>,
+) -> ObjectiveBreakdown {
+    let schedule_with_info = ScheduleWithInfo::new(
+        schedule.clone(),
+        SwapInfo::NoSwap,
+        String::from("Objective breakdown"),
+    );
+    let value_of =
+        |indicator: &dyn Indicator<ScheduleWithInfo>| indicator.evaluate(&schedule_with_info);
+    ObjectiveBreakdown {
+        unserved_passengers: value_of(&UnservedPassengersIndicator).unwrap_integer(),
+        min_vehicles_violation: value_of(&MinVehiclesViolationIndicator).unwrap_integer(),
+        missing_mandatory_maintenance: value_of(&MissingMandatoryMaintenanceIndicator)
+            .unwrap_integer(),
+        maintenance_violation: value_of(&MaintenanceViolationIndicator).unwrap_integer(),
+        vehicle_count: value_of(&VehicleCountIndicator).unwrap_integer(),
+        costs: value_of(&CostsIndicator).unwrap_integer(),
+        non_home_spawn_penalty: value_of(&NonHomeSpawnPenaltyIndicator).unwrap_integer(),
+        closed_rotation_penalty: value_of(&ClosedRotationPenaltyIndicator).unwrap_integer(),
+        unhonored_preferred_successors: value_of(&UnhonoredPreferredSuccessorsIndicator)
+            .unwrap_integer(),
+        objective_value: objective
+            .evaluate(schedule_with_info.clone())
+            .objective_value()
+            .clone(),
+    }
+}
+
 pub fn build() -> Objective<ScheduleWithInfo> {
-    let maintenance_violation = LinearCombination::new(vec![(
-        Coefficient::Integer(1),
-        Box::new(MaintenanceViolationIndicator),
-    )]);
-
-    let unserved_passengers = LinearCombination::new(vec![(
-        Coefficient::Integer(1),
-        Box::new(UnservedPassengersIndicator),
-    )]);
-
-    let vehicle_count = LinearCombination::new(vec![(
-        Coefficient::Integer(1),
-        Box::new(VehicleCountIndicator),
-    )]);
-
-    let costs = LinearCombination::new(vec![(Coefficient::Integer(1), Box::new(CostsIndicator))]);
-
-    Objective::new(vec![
-        unserved_passengers,
-        maintenance_violation,
-        vehicle_count,
-        costs,
-    ])
+    build_with_reference(None)
+}
+
+/// The objective for the network, i.e., build_with_reference with the reference assignment of
+/// the config (if any).
+pub fn build_for(network: &Network) -> Objective<ScheduleWithInfo> {
+    build_with_reference(network.config().reference_assignment.clone())
+}
+
+/// Hierarchy levels of the objective in the order of their priority. Shared by
+/// build_with_reference and objective_value_for, such that both agree on the layout.
+enum Level {
+    UnservedPassengers,
+    MinVehiclesViolation,
+    MissingMandatoryMaintenance,
+    MaintenanceViolation,
+    VehicleCount,
+    AssignmentChanges,
+    Costs,
+    UnhonoredPreferredSuccessors,
+}
+
+/// The levels of the objective; AssignmentChanges only if there is a reference assignment.
+fn levels(with_reference: bool) -> Vec<Level> {
+    let mut levels = vec![
+        Level::UnservedPassengers,
+        // trips below their minimal number of vehicles are penalized right after the unserved
+        // passengers, such that adding a vehicle to such a trip (e.g., by hitch-hiking) is an
+        // improvement, but never at the expense of the passengers
+        Level::MinVehiclesViolation,
+        Level::MissingMandatoryMaintenance,
+        Level::MaintenanceViolation,
+        Level::VehicleCount,
+    ];
+    if with_reference {
+        levels.push(Level::AssignmentChanges);
+    }
+    levels.push(Level::Costs);
+    levels.push(Level::UnhonoredPreferredSuccessors);
+    levels
+}
+
+/// Same as build(), but if a reference assignment is provided, an additional level (after the
+/// vehicle count, before the costs) penalizes each service trip that is assigned to other
+/// vehicles than in the reference. This keeps re-optimized plans close to the previous plan.
+pub fn build_with_reference(reference: Option<ReferenceAssignment>) -> Objective<ScheduleWithInfo> {
+    let single = |indicator: Box<dyn Indicator<ScheduleWithInfo>>| {
+        LinearCombination::new(vec![(Coefficient::Integer(1), indicator)])
+    };
+    let mut reference = reference;
+    let levels = levels(reference.is_some())
+        .into_iter()
+        .map(|level| match level {
+            Level::UnservedPassengers => single(Box::new(UnservedPassengersIndicator)),
+            Level::MinVehiclesViolation => single(Box::new(MinVehiclesViolationIndicator)),
+            Level::MissingMandatoryMaintenance => {
+                single(Box::new(MissingMandatoryMaintenanceIndicator))
+            }
+            Level::MaintenanceViolation => single(Box::new(MaintenanceViolationIndicator)),
+            Level::VehicleCount => single(Box::new(VehicleCountIndicator)),
+            Level::AssignmentChanges => single(Box::new(AssignmentChangesIndicator {
+                reference: reference.take().unwrap(),
+            })),
+            Level::Costs => LinearCombination::new(vec![
+                (Coefficient::Integer(1), Box::new(CostsIndicator)),
+                (
+                    Coefficient::Integer(1),
+                    Box::new(NonHomeSpawnPenaltyIndicator),
+                ),
+                (
+                    Coefficient::Integer(1),
+                    Box::new(ClosedRotationPenaltyIndicator),
+                ),
+            ]),
+            Level::UnhonoredPreferredSuccessors => {
+                single(Box::new(UnhonoredPreferredSuccessorsIndicator))
+            }
+        })
+        .collect();
+
+    Objective::new(levels)
+}
+
+/// Objective value (of the objective of build_for(network)) that no schedule can undercut: no
+/// unserved passengers, no trips below their minimal number of vehicles, no missing mandatory
+/// maintenance, no maintenance violation, the number of vehicles that are
+/// simultaneously needed on service trips, no assignment changes and the costs of covering each
+/// service trip with its minimal formation and all preferred successor hints honored.
+/// If a schedule reaches this value, it is optimal.
+pub fn lower_bound(network: &Network) -> ObjectiveValue {
+    objective_value_for(
+        network,
+        BaseValue::Integer(fleet_lower_bound(network) as i64),
+        saturating_integer(costs_lower_bound(network)),
+    )
+}
+
+/// Objective value (of the objective of build_for(network)) that is zero on all levels except
+/// for the costs, e.g., as a tolerance for accepting slightly more expensive schedules.
+pub fn costs_threshold(network: &Network, threshold: Cost) -> ObjectiveValue {
+    objective_value_for(
+        network,
+        BaseValue::Integer(0),
+        saturating_integer(threshold),
+    )
+}
+
+/// Objective value with the level layout of build_for(network) that has the given vehicle count
+/// and costs and is zero on all other levels.
+fn objective_value_for(
+    network: &Network,
+    vehicle_count: BaseValue,
+    costs: BaseValue,
+) -> ObjectiveValue {
+    ObjectiveValue::new(
+        levels(network.config().reference_assignment.is_some())
+            .into_iter()
+            .map(|level| match level {
+                Level::VehicleCount => vehicle_count,
+                Level::Costs => costs,
+                _ => BaseValue::Integer(0),
+            })
+            .collect(),
+    )
+}
+
+/// Multiplies each level of the objective value by the factor. Unlike the multiplication of
+/// rapid_solve (which truncates), integers and durations are rounded with the given policy.
+pub fn scale(
+    objective_value: &ObjectiveValue,
+    factor: f64,
+    rounding: RoundingPolicy,
+) -> ObjectiveValue {
+    ObjectiveValue::new(
+        objective_value
+            .iter()
+            .map(|value| match *value {
+                BaseValue::Integer(i) => BaseValue::Integer(rounding.to_integer(i as f64 * factor)),
+                BaseValue::Float(f) => BaseValue::Float(f * factor),
+                BaseValue::Duration(d) => match d.in_sec() {
+                    Ok(sec) => BaseValue::Duration(Duration::from_seconds(
+                        rounding.to_unsigned(sec as f64 * factor),
+                    )),
+                    Err(_) => BaseValue::Duration(Duration::Infinity),
+                },
+                BaseValue::Maximum => BaseValue::Maximum,
+                BaseValue::Zero => BaseValue::Zero,
+            })
+            .collect(),
+    )
+}
+
+/// Serializes the objective value as a JSON array with one entry per level: {"integer": i},
+/// {"float": f}, {"duration": seconds} (null if infinite), "maximum" or "zero". Inverse of
+/// deserialize_objective_value.
+pub fn serialize_objective_value(objective_value: &ObjectiveValue) -> serde_json::Value {
+    serde_json::Value::Array(
+        objective_value
+            .iter()
+            .map(|value| match *value {
+                BaseValue::Integer(i) => json!({ "integer": i }),
+                BaseValue::Float(f) => json!({ "float": f }),
+                BaseValue::Duration(d) => json!({ "duration": d.in_sec().ok() }),
+                BaseValue::Maximum => json!("maximum"),
+                BaseValue::Zero => json!("zero"),
+            })
+            .collect(),
+    )
+}
+
+/// Restores an objective value serialized by serialize_objective_value.
+pub fn deserialize_objective_value(value: &serde_json::Value) -> Result<ObjectiveValue, String> {
+    let levels = value
+        .as_array()
+        .ok_or_else(|| format!("Objective value must be an array, not {}.", value))?;
+    levels
+        .iter()
+        .map(|level| {
+            if level == "maximum" {
+                return Ok(BaseValue::Maximum);
+            }
+            if level == "zero" {
+                return Ok(BaseValue::Zero);
+            }
+            if let Some(i) = level.get("integer").and_then(|i| i.as_i64()) {
+                return Ok(BaseValue::Integer(i));
+            }
+            if let Some(f) = level.get("float").and_then(|f| f.as_f64()) {
+                return Ok(BaseValue::Float(f));
+            }
+            match level.get("duration") {
+                Some(serde_json::Value::Null) => Ok(BaseValue::Duration(Duration::Infinity)),
+                Some(seconds) => seconds
+                    .as_u64()
+                    .map(|seconds| BaseValue::Duration(Duration::from_seconds(seconds)))
+                    .ok_or_else(|| format!("Invalid duration in objective value: {}.", level)),
+                None => Err(format!("Invalid level of objective value: {}.", level)),
+            }
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map(ObjectiveValue::new)
+}
+
+/// Maximal number of vehicles that are required at the same time to fully serve all service
+/// trips.
+fn fleet_lower_bound(network: &Network) -> VehicleCount {
+    let mut events: Vec<(DateTime, i64)> = network
+        .all_service_nodes()
+        .flat_map(|service_trip| {
+            let required = minimal_formation_size(network, service_trip) as i64;
+            let node = network.node(service_trip);
+            [(node.start_time(), required), (node.end_time(), -required)]
+        })
+        .collect();
+    // at the same time, arrivals (negative) are processed before departures
+    events.sort();
+
+    events
+        .iter()
+        .scan(0, |active, (_, change)| {
+            *active += change;
+            Some(*active)
+        })
+        .max()
+        .unwrap_or(0) as VehicleCount
+}
+
+/// Staff costs plus the service trip costs of the minimal formation for each service trip.
+/// Dead-head trips, idle times and maintenance are neglected.
+fn costs_lower_bound(network: &Network) -> Cost {
+    let config = network.config();
+    network.number_of_service_nodes() as Cost * config.costs.staff
+        + network
+            .all_service_nodes()
+            .map(|service_trip| {
+                minimal_formation_size(network, service_trip) as Cost
+                    * network.node(service_trip).duration().in_sec().unwrap()
+                    * config.costs.service_trip
+            })
+            .sum::<Cost>()
+}
+
+fn minimal_formation_size(network: &Network, service_trip: NodeIdx) -> VehicleCount {
+    network
+        .number_of_vehicles_required_to_serve(network.vehicle_type_for(service_trip), service_trip)
 }
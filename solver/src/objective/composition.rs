@@ -0,0 +1,136 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Indicators composed of other indicators, e.g., to express the dead head distance as a
+//! fraction of the total distance without a dedicated indicator.
+
+use rapid_solve::objective::{BaseValue, Indicator};
+
+/// Quotient of two indicators as a float. If the denominator is zero, the ratio is Zero if the
+/// numerator is zero as well and Maximum otherwise. A Maximum numerator gives Maximum, a Maximum
+/// denominator (with a finite numerator) gives 0.
+pub struct RatioIndicator<S> {
+    numerator: Box<dyn Indicator<S>>,
+    denominator: Box<dyn Indicator<S>>,
+}
+
+impl<S> RatioIndicator<S> {
+    pub fn new(
+        numerator: Box<dyn Indicator<S>>,
+        denominator: Box<dyn Indicator<S>>,
+    ) -> RatioIndicator<S> {
+        RatioIndicator {
+            numerator,
+            denominator,
+        }
+    }
+}
+
+impl<S> Indicator<S> for RatioIndicator<S> {
+    fn evaluate(&self, solution: &S) -> BaseValue {
+        let numerator = as_float(self.numerator.evaluate(solution));
+        let denominator = as_float(self.denominator.evaluate(solution));
+        if numerator.is_infinite() {
+            BaseValue::Maximum
+        } else if denominator == 0.0 {
+            if numerator == 0.0 {
+                BaseValue::Zero
+            } else {
+                BaseValue::Maximum
+            }
+        } else {
+            // a Maximum denominator yields 0.0
+            BaseValue::Float(numerator / denominator)
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("{}/{}", self.numerator.name(), self.denominator.name())
+    }
+}
+
+/// Sum of several indicators. All summands need to evaluate to the same kind of base value
+/// (besides Zero and Maximum), e.g., all integers.
+pub struct SumIndicator<S> {
+    summands: Vec<Box<dyn Indicator<S>>>,
+}
+
+impl<S> SumIndicator<S> {
+    pub fn new(summands: Vec<Box<dyn Indicator<S>>>) -> SumIndicator<S> {
+        SumIndicator { summands }
+    }
+}
+
+impl<S> Indicator<S> for SumIndicator<S> {
+    fn evaluate(&self, solution: &S) -> BaseValue {
+        self.summands
+            .iter()
+            .map(|summand| summand.evaluate(solution))
+            .sum()
+    }
+
+    fn name(&self) -> String {
+        format!(
+            "({})",
+            self.summands
+                .iter()
+                .map(|summand| summand.name())
+                .collect::<Vec<_>>()
+                .join(" + ")
+        )
+    }
+}
+
+/// Turns an indicator that should be maximized (e.g., robustness or coverage) into one that can
+/// be minimized by negating its value. Durations cannot be negative and are therefore negated as
+/// integer seconds; Maximum (and infinite durations) become i64::MIN, i.e., the best value.
+pub struct MaximizedIndicator<S> {
+    indicator: Box<dyn Indicator<S>>,
+}
+
+impl<S> MaximizedIndicator<S> {
+    pub fn new(indicator: Box<dyn Indicator<S>>) -> MaximizedIndicator<S> {
+        MaximizedIndicator { indicator }
+    }
+}
+
+impl<S> Indicator<S> for MaximizedIndicator<S> {
+    fn evaluate(&self, solution: &S) -> BaseValue {
+        match self.indicator.evaluate(solution) {
+            BaseValue::Integer(i) => BaseValue::Integer(i.saturating_neg()),
+            BaseValue::Float(f) => BaseValue::Float(-f),
+            BaseValue::Duration(d) => BaseValue::Integer(
+                d.in_sec()
+                    .map(|sec| i64::try_from(sec).map_or(i64::MIN, |sec| -sec))
+                    .unwrap_or(i64::MIN),
+            ),
+            BaseValue::Maximum => BaseValue::Integer(i64::MIN),
+            BaseValue::Zero => BaseValue::Zero,
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("max({})", self.indicator.name())
+    }
+}
+
+/// Durations are taken in seconds, Maximum (and infinite durations) as infinity.
+fn as_float(value: BaseValue) -> f64 {
+    match value {
+        BaseValue::Integer(i) => i as f64,
+        BaseValue::Float(f) => f,
+        BaseValue::Duration(d) => d.in_sec().map(|sec| sec as f64).unwrap_or(f64::INFINITY),
+        BaseValue::Maximum => f64::INFINITY,
+        BaseValue::Zero => 0.0,
+    }
+}
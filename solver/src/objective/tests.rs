@@ -0,0 +1,568 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fs::File, io::Read};
+
+use model::base_types::{Distance, NodeIdx, RoundingPolicy, VehicleTypeIdx};
+use model::json_serialisation::load_rolling_stock_problem_instance_from_json;
+use rapid_solve::objective::{BaseValue, Indicator, ObjectiveValue};
+use rapid_time::Duration;
+use serde_json::json;
+use solution::test_utilities::init_test_data;
+use solution::Schedule;
+
+use crate::{
+    local_search::{neighborhood::swaps::SwapInfo, ScheduleWithInfo},
+    min_cost_flow_solver::MinCostFlowSolver,
+};
+
+use super::composition::{MaximizedIndicator, RatioIndicator, SumIndicator};
+use super::{
+    build, build_for, costs_threshold, deserialize_objective_value, lower_bound,
+    objective_breakdown, saturating_integer, scale, serialize_objective_value,
+    DeadHeadDistanceIndicator, DistinctVehicleTypesIndicator, TotalDistanceIndicator,
+    WorkloadBalanceIndicator,
+};
+
+#[test]
+fn saturating_integer_test() {
+    assert_eq!(saturating_integer(42), BaseValue::Integer(42));
+    assert_eq!(saturating_integer(u64::MAX), BaseValue::Integer(i64::MAX));
+    assert_eq!(
+        saturating_integer(i64::MAX as u64 + 1),
+        BaseValue::Integer(i64::MAX)
+    );
+}
+
+#[test]
+fn base_value_addition_test() {
+    // the objective aggregation relies on these rules of BaseValue
+    let integer = BaseValue::Integer(3);
+    let duration = BaseValue::Duration(Duration::from_seconds(60));
+
+    assert_eq!(integer + BaseValue::Integer(4), BaseValue::Integer(7));
+    assert_eq!(BaseValue::Maximum + integer, BaseValue::Maximum);
+    assert_eq!(duration + BaseValue::Maximum, BaseValue::Maximum);
+    assert_eq!(BaseValue::Zero + integer, integer);
+    assert_eq!(duration + BaseValue::Zero, duration);
+    assert_eq!(
+        duration + BaseValue::Duration(Duration::Infinity),
+        BaseValue::Duration(Duration::Infinity)
+    );
+    assert_eq!(
+        vec![integer, BaseValue::Zero, BaseValue::Integer(5)]
+            .into_iter()
+            .sum::<BaseValue>(),
+        BaseValue::Integer(8)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Cannot add")]
+fn base_value_addition_type_mismatch_test() {
+    let _ = BaseValue::Duration(Duration::from_seconds(60)) + BaseValue::Integer(3);
+}
+
+#[test]
+fn objective_value_serialization_round_trip_test() {
+    // ARRANGE
+    let objective_value = ObjectiveValue::new(vec![
+        BaseValue::Integer(-3),
+        BaseValue::Float(2.5),
+        BaseValue::Duration(Duration::from_seconds(90)),
+        BaseValue::Duration(Duration::Infinity),
+        BaseValue::Maximum,
+        BaseValue::Zero,
+    ]);
+
+    // ACT
+    let serialized = serialize_objective_value(&objective_value);
+    let deserialized = deserialize_objective_value(&serialized).unwrap();
+
+    // ASSERT
+    assert_eq!(
+        serialized,
+        json!([
+            {"integer": -3},
+            {"float": 2.5},
+            {"duration": 90},
+            {"duration": null},
+            "maximum",
+            "zero"
+        ])
+    );
+    assert_eq!(deserialized, objective_value);
+    assert!(deserialize_objective_value(&json!([{"unknown": 1}])).is_err());
+}
+
+#[test]
+fn assignment_changes_level_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let mut input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    let network = load_rolling_stock_problem_instance_from_json(input_data.clone());
+    let reference = MinCostFlowSolver::initialize(network.clone()).solve();
+    let vehicle = reference.vehicles_iter_all().next().unwrap();
+    let changed = reference.replace_vehicle_by_dummy(vehicle).unwrap();
+    let expected_changes = changed.assignment_distance(&reference) as i64;
+    input_data["parameters"]["referenceAssignment"] = json!(reference.reference_assignment());
+    let network_with_reference = load_rolling_stock_problem_instance_from_json(input_data);
+    let objective = build_for(&network_with_reference);
+
+    // ACT
+    let value_of_reference = objective.evaluate(ScheduleWithInfo::new(
+        reference.clone(),
+        SwapInfo::NoSwap,
+        String::from("reference"),
+    ));
+    let value_of_changed = objective.evaluate(ScheduleWithInfo::new(
+        changed,
+        SwapInfo::NoSwap,
+        String::from("changed"),
+    ));
+
+    // ASSERT
+    assert!(expected_changes > 0);
+    assert_eq!(value_of_reference.objective_value().iter().count(), 8);
+    assert_eq!(
+        build_for(&network)
+            .evaluate(ScheduleWithInfo::new(
+                reference,
+                SwapInfo::NoSwap,
+                String::new()
+            ))
+            .objective_value()
+            .iter()
+            .count(),
+        7
+    );
+    assert_eq!(
+        value_of_reference.objective_value().iter().nth(5),
+        Some(&BaseValue::Integer(0))
+    );
+    assert_eq!(
+        value_of_changed.objective_value().iter().nth(5),
+        Some(&BaseValue::Integer(expected_changes))
+    );
+    for network in [&network, &network_with_reference] {
+        let levels = if network.config().reference_assignment.is_some() {
+            8
+        } else {
+            7
+        };
+        assert_eq!(lower_bound(network).iter().count(), levels);
+        assert_eq!(costs_threshold(network, 5).iter().count(), levels);
+        assert_eq!(
+            costs_threshold(network, 5).iter().nth(levels - 2),
+            Some(&BaseValue::Integer(5))
+        );
+    }
+}
+
+#[test]
+fn objective_breakdown_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let network =
+        load_rolling_stock_problem_instance_from_json(serde_json::from_str(&input_data).unwrap());
+    let schedule = MinCostFlowSolver::initialize(network).solve();
+    let objective = build();
+
+    // ACT
+    let breakdown = objective_breakdown(&schedule, &objective);
+
+    // ASSERT
+    let unserved_passengers = schedule.unserved_passengers();
+    assert_eq!(
+        breakdown.unserved_passengers,
+        (unserved_passengers.0 + unserved_passengers.1) as i64
+    );
+    assert_eq!(
+        breakdown.missing_mandatory_maintenance,
+        schedule.missing_mandatory_maintenance() as i64
+    );
+    assert_eq!(
+        breakdown.maintenance_violation,
+        schedule.maintenance_violation()
+    );
+    assert_eq!(
+        breakdown.vehicle_count,
+        schedule.number_of_vehicles() as i64
+    );
+    assert_eq!(breakdown.costs, schedule.costs() as i64);
+    assert_eq!(
+        breakdown.unhonored_preferred_successors,
+        schedule.unhonored_preferred_successors() as i64
+    );
+    assert_eq!(
+        breakdown.objective_value.as_vec(),
+        &vec![
+            BaseValue::Integer(breakdown.unserved_passengers),
+            BaseValue::Integer(breakdown.min_vehicles_violation),
+            BaseValue::Integer(breakdown.missing_mandatory_maintenance),
+            BaseValue::Integer(breakdown.maintenance_violation),
+            BaseValue::Integer(breakdown.vehicle_count),
+            BaseValue::Integer(
+                breakdown.costs
+                    + breakdown.non_home_spawn_penalty
+                    + breakdown.closed_rotation_penalty
+            ),
+            BaseValue::Integer(breakdown.unhonored_preferred_successors),
+        ]
+    );
+}
+
+#[test]
+fn soft_closed_rotations_are_penalized_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let mut input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    input_data["parameters"]["closedRotations"] = json!({"soft": 1000});
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let spawn_from_depot2_to = |end_depot: NodeIdx| {
+        Schedule::empty(network.clone())
+            .spawn_vehicle_for_path(
+                VehicleTypeIdx::from(0),
+                vec![
+                    NodeIdx::start_depot_from(2),
+                    NodeIdx::service_from(14),
+                    end_depot,
+                ],
+            )
+            .unwrap()
+            .0
+    };
+    let closed_schedule = spawn_from_depot2_to(NodeIdx::end_depot_from(3));
+    let open_schedule = spawn_from_depot2_to(NodeIdx::end_depot_from(1));
+    let objective = build();
+
+    // ACT
+    let closed_breakdown = objective_breakdown(&closed_schedule, &objective);
+    let open_breakdown = objective_breakdown(&open_schedule, &objective);
+
+    // ASSERT
+    assert_eq!(closed_schedule.closed_rotation_violation(), 0);
+    assert_eq!(open_schedule.closed_rotation_violation(), 1);
+    assert_eq!(closed_breakdown.closed_rotation_penalty, 0);
+    assert_eq!(open_breakdown.closed_rotation_penalty, 1000);
+    assert_eq!(
+        open_breakdown.objective_value.as_vec()[5],
+        BaseValue::Integer(
+            open_breakdown.costs
+                + open_breakdown.non_home_spawn_penalty
+                + open_breakdown.closed_rotation_penalty
+        )
+    );
+}
+
+#[test]
+fn scale_with_rounding_policy_test() {
+    // ARRANGE
+    let objective_value = ObjectiveValue::new(vec![
+        BaseValue::Integer(7),
+        BaseValue::Integer(-7),
+        BaseValue::Duration(Duration::from_seconds(7)),
+        BaseValue::Float(7.0),
+    ]);
+    let expected = |integer: i64, negative: i64, seconds: u64| {
+        ObjectiveValue::new(vec![
+            BaseValue::Integer(integer),
+            BaseValue::Integer(negative),
+            BaseValue::Duration(Duration::from_seconds(seconds)),
+            BaseValue::Float(2.1),
+        ])
+    };
+
+    // ACT
+    let truncated = scale(&objective_value, 0.3, RoundingPolicy::Truncate); // 2.1
+    let rounded = scale(&objective_value, 0.5, RoundingPolicy::RoundHalfUp); // 3.5
+    let ceiled = scale(&objective_value, 0.3, RoundingPolicy::Ceil); // 2.1
+
+    // ASSERT
+    assert_eq!(truncated.as_vec()[..3], expected(2, -2, 2).as_vec()[..3]);
+    assert_eq!(rounded.as_vec()[..3], expected(4, -3, 4).as_vec()[..3]);
+    assert_eq!(ceiled.as_vec()[..3], expected(3, -2, 3).as_vec()[..3]);
+    assert!((ceiled.as_vec()[3].unwrap_float() - 2.1).abs() < 1e-9);
+    assert_eq!(
+        scale(&objective_value, 0.3, RoundingPolicy::RoundHalfUp).as_vec()[..3],
+        expected(2, -2, 2).as_vec()[..3]
+    );
+    assert_eq!(RoundingPolicy::default(), RoundingPolicy::RoundHalfUp);
+}
+
+#[test]
+fn distinct_vehicle_types_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let mut input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    input_data["maintenanceSlots"] = json!([{"id": "maintenance_loc2", "location": "loc2",
+        "start": "2020-01-01T06:35:00", "end": "2020-01-01T06:45:00", "trackCount": 2}]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let maintenance = network.maintenance_nodes().next().unwrap();
+    let vt1 = VehicleTypeIdx::from(0);
+    let vt2 = VehicleTypeIdx::from(1);
+    let trip12 = NodeIdx::service_from(12);
+    // trip12 is covered by vt1 in both schedules, only the vehicle for maintenance differs
+    let schedule_with_maintenance_by = |vehicle_type: VehicleTypeIdx| {
+        Schedule::empty(network.clone())
+            .spawn_vehicle_for_path(vt1, vec![trip12])
+            .unwrap()
+            .0
+            .spawn_vehicle_for_path(vehicle_type, vec![maintenance])
+            .unwrap()
+            .0
+    };
+    let homogeneous_schedule = schedule_with_maintenance_by(vt1);
+    let mixed_schedule = schedule_with_maintenance_by(vt2);
+    let value_of = |schedule: &Schedule| {
+        DistinctVehicleTypesIndicator.evaluate(&ScheduleWithInfo::new(
+            schedule.clone(),
+            SwapInfo::NoSwap,
+            String::new(),
+        ))
+    };
+
+    // ACT
+    let homogeneous_value = value_of(&homogeneous_schedule);
+    let mixed_value = value_of(&mixed_schedule);
+
+    // ASSERT
+    assert_eq!(
+        homogeneous_schedule.unserved_passengers(),
+        mixed_schedule.unserved_passengers()
+    );
+    assert_eq!(homogeneous_value, BaseValue::Integer(1));
+    assert_eq!(mixed_value, BaseValue::Integer(2));
+    assert_eq!(
+        value_of(&Schedule::empty(network.clone())),
+        BaseValue::Integer(0)
+    );
+    assert_eq!(DistinctVehicleTypesIndicator.name(), "distinctVehicleTypes");
+}
+
+/// Indicator with a fixed value (for testing compositions).
+struct ConstantIndicator(BaseValue);
+
+impl Indicator<()> for ConstantIndicator {
+    fn evaluate(&self, _: &()) -> BaseValue {
+        self.0
+    }
+
+    fn name(&self) -> String {
+        format!("{}", self.0)
+    }
+}
+
+#[test]
+fn composed_indicators_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let network =
+        load_rolling_stock_problem_instance_from_json(serde_json::from_str(&input_data).unwrap());
+    let schedule = MinCostFlowSolver::initialize(network).solve();
+    let schedule_with_info = ScheduleWithInfo::new(
+        schedule.clone(),
+        SwapInfo::NoSwap,
+        String::from("Min cost flow solution"),
+    );
+    let dead_head_ratio = RatioIndicator::new(
+        Box::new(DeadHeadDistanceIndicator),
+        Box::new(TotalDistanceIndicator),
+    );
+    let ratio = |numerator: BaseValue, denominator: BaseValue| {
+        RatioIndicator::new(
+            Box::new(ConstantIndicator(numerator)),
+            Box::new(ConstantIndicator(denominator)),
+        )
+        .evaluate(&())
+    };
+
+    // ACT
+    let value = dead_head_ratio.evaluate(&schedule_with_info);
+
+    // ASSERT
+    let dead_head = schedule.total_dead_head_distance().in_meter().unwrap();
+    let total = schedule.total_distance().in_meter().unwrap();
+    assert!(dead_head > 0);
+    assert_eq!(value, BaseValue::Float(dead_head as f64 / total as f64));
+    assert_eq!(dead_head_ratio.name(), "deadHeadDistance/totalDistance");
+
+    assert_eq!(
+        ratio(BaseValue::Integer(0), BaseValue::Integer(0)),
+        BaseValue::Zero
+    );
+    assert_eq!(
+        ratio(BaseValue::Integer(3), BaseValue::Zero),
+        BaseValue::Maximum
+    );
+    assert_eq!(
+        ratio(BaseValue::Maximum, BaseValue::Integer(2)),
+        BaseValue::Maximum
+    );
+    assert_eq!(
+        ratio(
+            BaseValue::Duration(Duration::from_seconds(30)),
+            BaseValue::Duration(Duration::from_seconds(120))
+        ),
+        BaseValue::Float(0.25)
+    );
+
+    let sum = SumIndicator::new(vec![
+        Box::new(ConstantIndicator(BaseValue::Integer(3))),
+        Box::new(ConstantIndicator(BaseValue::Zero)),
+        Box::new(ConstantIndicator(BaseValue::Integer(4))),
+    ]);
+    assert_eq!(sum.evaluate(&()), BaseValue::Integer(7));
+    assert_eq!(sum.name(), "(3 + 0 + 4)");
+}
+
+#[test]
+fn maximized_indicator_test() {
+    // ARRANGE
+    let maximized = |value: BaseValue| {
+        MaximizedIndicator::new(Box::new(ConstantIndicator(value))).evaluate(&())
+    };
+
+    // ACT
+    let small = maximized(BaseValue::Integer(3));
+    let large = maximized(BaseValue::Integer(7));
+    let short = maximized(BaseValue::Duration(Duration::from_seconds(30)));
+    let long = maximized(BaseValue::Duration(Duration::from_seconds(120)));
+
+    // ASSERT
+    assert!(large < small);
+    assert!(long < short);
+    assert_eq!(long, BaseValue::Integer(-120));
+    assert_eq!(maximized(BaseValue::Float(0.5)), BaseValue::Float(-0.5));
+    assert_eq!(maximized(BaseValue::Zero), BaseValue::Zero);
+    assert_eq!(maximized(BaseValue::Maximum), BaseValue::Integer(i64::MIN));
+    assert_eq!(
+        maximized(BaseValue::Duration(Duration::Infinity)),
+        BaseValue::Integer(i64::MIN)
+    );
+    assert_eq!(
+        MaximizedIndicator::new(Box::new(ConstantIndicator(BaseValue::Integer(1)))).name(),
+        "max(1)"
+    );
+}
+
+#[test]
+fn dead_head_to_maintenance_location_test() {
+    // ARRANGE
+    let mut file = File::open("../solution/resources/test_instance.json").unwrap();
+    let mut input_data = String::new();
+    file.read_to_string(&mut input_data).unwrap();
+    let mut input_data: serde_json::Value = serde_json::from_str(&input_data).unwrap();
+    input_data["maintenanceSlots"] = json!([
+        {"id": "maintenance_loc2", "location": "loc2",
+            "start": "2020-01-01T06:35:00", "end": "2020-01-01T07:35:00", "trackCount": 1},
+        {"id": "maintenance_loc3", "location": "loc3",
+            "start": "2020-01-01T07:35:00", "end": "2020-01-01T08:35:00", "trackCount": 1}
+    ]);
+    let network = load_rolling_stock_problem_instance_from_json(input_data);
+    let maintenance_loc2 = network.maintenance_nodes().next().unwrap();
+    let maintenance_loc3 = network.maintenance_nodes().nth(1).unwrap();
+    let trip12 = NodeIdx::service_from(12); // ends at loc2 at 06:30
+    let objective = build();
+    let evaluate = |maintenance: NodeIdx| {
+        let (schedule, vehicle) = Schedule::empty(network.clone())
+            .spawn_vehicle_for_path(VehicleTypeIdx::from(0), vec![trip12, maintenance])
+            .unwrap();
+        let tour = schedule.tour_of(vehicle).unwrap().clone();
+        let evaluated = objective.evaluate(ScheduleWithInfo::new(
+            schedule,
+            SwapInfo::NoSwap,
+            String::from("Vehicle with maintenance"),
+        ));
+        (tour, evaluated)
+    };
+
+    // ACT
+    let (tour_loc2, solution_loc2) = evaluate(maintenance_loc2);
+    let (tour_loc3, solution_loc3) = evaluate(maintenance_loc3);
+
+    // ASSERT
+    // the maintenance at loc3 requires a dead head trip from loc2 (end of trip12) to loc3
+    assert!(network.can_reach(trip12, maintenance_loc3));
+    assert_eq!(
+        network.dead_head_distance_between(trip12, maintenance_loc2),
+        Distance::ZERO
+    );
+    assert_eq!(
+        network.dead_head_distance_between(trip12, maintenance_loc3),
+        Distance::from_meter(23000)
+    );
+    assert_eq!(
+        tour_loc3.dead_head_distance() - tour_loc2.dead_head_distance(),
+        Distance::from_meter(23000)
+            + network.dead_head_distance_between(maintenance_loc3, tour_loc3.last_node())
+            - network.dead_head_distance_between(maintenance_loc2, tour_loc2.last_node())
+    );
+    assert!(tour_loc3.dead_head_distance() > tour_loc2.dead_head_distance());
+    assert_eq!(
+        DeadHeadDistanceIndicator.evaluate(solution_loc3.solution()),
+        BaseValue::Integer(tour_loc3.dead_head_distance().in_meter().unwrap() as i64)
+    );
+    assert!(tour_loc3.costs() > tour_loc2.costs());
+    assert!(solution_loc3.objective_value() > solution_loc2.objective_value());
+}
+
+#[test]
+fn workload_balance_test() {
+    // ARRANGE
+    let d = init_test_data();
+    // trip34 is moved from the first to the second vehicle
+    let schedule_with_paths = |first_path: Vec<NodeIdx>, second_path: Vec<NodeIdx>| {
+        Schedule::empty(d.network.clone())
+            .spawn_vehicle_for_path(d.vt1, first_path)
+            .unwrap()
+            .0
+            .spawn_vehicle_for_path(d.vt1, second_path)
+            .unwrap()
+            .0
+    };
+    let unbalanced_schedule =
+        schedule_with_paths(vec![d.trip12, d.trip23, d.trip34], vec![d.trip45]);
+    let balanced_schedule = schedule_with_paths(vec![d.trip12, d.trip23], vec![d.trip34, d.trip45]);
+    let value_of = |schedule: &Schedule| {
+        WorkloadBalanceIndicator.evaluate(&ScheduleWithInfo::new(
+            schedule.clone(),
+            SwapInfo::NoSwap,
+            String::new(),
+        ))
+    };
+
+    // ACT
+    let unbalanced_value = value_of(&unbalanced_schedule);
+    let balanced_value = value_of(&balanced_schedule);
+
+    // ASSERT
+    // 90min vs 30min of service
+    assert_eq!(
+        unbalanced_value,
+        BaseValue::Duration(Duration::from_seconds(3600))
+    );
+    // 60min vs 60min of service
+    assert_eq!(balanced_value, BaseValue::Duration(Duration::ZERO));
+    assert_eq!(WorkloadBalanceIndicator.name(), "workloadBalance");
+}
@@ -12,20 +12,20 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use model::network::Network;
-use rapid_solve::objective::{EvaluatedSolution, Objective};
 use solution::Schedule;
 use std::sync::Arc;
 
+/// Trivial start solver: each service trip is covered by its own vehicles (as many as needed to
+/// serve all passengers).
 pub struct OneNodePerTour {
     network: Arc<Network>,
-    objective: Arc<Objective<Schedule>>,
 }
 impl OneNodePerTour {
-    pub fn initialize(network: Arc<Network>, objective: Arc<Objective<Schedule>>) -> Self {
-        Self { network, objective }
+    pub fn initialize(network: Arc<Network>) -> Self {
+        Self { network }
     }
 
-    pub fn solve(&self) -> EvaluatedSolution<Schedule> {
+    pub fn solve(&self) -> Schedule {
         let mut schedule = Schedule::empty(self.network.clone());
 
         for service_trip in self.network.all_service_nodes() {
@@ -39,6 +39,6 @@ impl OneNodePerTour {
             }
         }
 
-        self.objective.evaluate(schedule)
+        schedule
     }
 }
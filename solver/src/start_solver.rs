@@ -0,0 +1,34 @@
+// © 2023-2024 ETH Zurich
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// any later version.
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use model::config::StartSolver;
+use model::network::Network;
+use solution::Schedule;
+
+use crate::min_cost_flow_solver::{dead_head_distance_connection_cost, MinCostFlowSolver};
+use crate::one_node_per_tour::OneNodePerTour;
+
+/// Computes the start schedule of the local search with the start solver selected in the config
+/// of the network.
+pub fn compute_start_schedule(network: Arc<Network>) -> Schedule {
+    match network.config().start_solver {
+        StartSolver::MinCostFlow => MinCostFlowSolver::initialize(network).solve(),
+        StartSolver::MinCostFlowByDistance => {
+            let connection_cost = dead_head_distance_connection_cost(&network);
+            MinCostFlowSolver::initialize_with_connection_cost(network, connection_cost).solve()
+        }
+        StartSolver::OneNodePerTour => OneNodePerTour::initialize(network).solve(),
+    }
+}